@@ -0,0 +1,108 @@
+"""桌面壳程序管理 API（仅允许本机访问）"""
+
+import logging
+import os
+from fastapi import APIRouter, HTTPException, Request
+from pydantic import BaseModel
+from sqlalchemy import text
+from typing import Optional
+
+from app.database import engine
+
+router = APIRouter(prefix="/admin", tags=["admin"])
+
+LOCAL_HOSTS = {"127.0.0.1", "::1", "localhost"}
+LOG_LEVELS = {"CRITICAL", "ERROR", "WARNING", "INFO", "DEBUG"}
+
+
+# ========== Pydantic 模型 ==========
+
+class LogLevelUpdate(BaseModel):
+    level: str
+    logger: Optional[str] = None  # 为空表示根日志器及 uvicorn 日志器
+
+
+class BackupRequest(BaseModel):
+    path: str  # 备份文件的绝对路径（文件不能已存在）
+
+
+# ========== 辅助函数 ==========
+
+def require_local(request: Request):
+    """只允许本机的桌面壳程序调用"""
+    if not request.client or request.client.host not in LOCAL_HOSTS:
+        raise HTTPException(status_code=403, detail="仅允许本机访问")
+
+
+def normalize_level(level: str) -> str:
+    """统一日志级别名称（兼容 warn / trace 等写法）"""
+    level = level.upper()
+    aliases = {"WARN": "WARNING", "TRACE": "DEBUG", "OFF": "CRITICAL"}
+    level = aliases.get(level, level)
+    if level not in LOG_LEVELS:
+        raise HTTPException(status_code=400, detail=f"无效的日志级别: {level}")
+    return level
+
+
+def apply_log_level(level: str, logger: Optional[str] = None):
+    """设置日志级别"""
+    if logger:
+        logging.getLogger(logger).setLevel(level)
+        return
+
+    for name in ("", "uvicorn", "uvicorn.error", "uvicorn.access"):
+        logging.getLogger(name).setLevel(level)
+
+
+# ========== API 路由 ==========
+
+@router.put("/log-level")
+async def set_log_level(data: LogLevelUpdate, request: Request):
+    """运行时调整日志级别（无需重启）"""
+    require_local(request)
+    level = normalize_level(data.level)
+    apply_log_level(level, data.logger)
+    logging.getLogger(__name__).info("日志级别已调整: %s=%s", data.logger or "root", level)
+    return {"logger": data.logger or "root", "level": level}
+
+
+@router.get("/startup")
+async def get_startup_timings(request: Request):
+    """启动各阶段耗时（毫秒）"""
+    require_local(request)
+    return getattr(request.app.state, "startup_timings", {})
+
+
+@router.post("/shutdown")
+async def shutdown(request: Request):
+    """正常退出：处理完当前请求、关闭数据库连接后结束进程（壳程序退出或系统关机时调用）"""
+    require_local(request)
+    server = getattr(request.app.state, "server", None)
+    if server is None:
+        raise HTTPException(status_code=409, detail="当前运行方式不支持远程关闭")
+    logging.getLogger(__name__).info("收到壳程序的关闭请求")
+    server.should_exit = True
+    return {"status": "shutting_down"}
+
+
+@router.post("/backup")
+async def backup_database(data: BackupRequest, request: Request):
+    """在线备份数据库（VACUUM INTO 生成一致的快照，备份期间不影响收银）"""
+    require_local(request)
+    if not os.path.isabs(data.path):
+        raise HTTPException(status_code=400, detail="备份路径必须是绝对路径")
+    if os.path.exists(data.path):
+        raise HTTPException(status_code=409, detail="备份文件已存在")
+    os.makedirs(os.path.dirname(data.path), exist_ok=True)
+
+    try:
+        # VACUUM 不能在事务中执行
+        with engine.connect().execution_options(isolation_level="AUTOCOMMIT") as conn:
+            conn.execute(text("VACUUM INTO :path"), {"path": data.path})
+    except Exception as e:
+        logging.getLogger(__name__).exception("备份数据库失败")
+        raise HTTPException(status_code=500, detail=f"备份数据库失败: {e}")
+
+    size = os.path.getsize(data.path)
+    logging.getLogger(__name__).info("数据库已备份: %s (%d 字节)", data.path, size)
+    return {"path": data.path, "size": size}
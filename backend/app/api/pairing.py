@@ -6,10 +6,12 @@ from sqlalchemy.orm import Session
 import socket
 from typing import List, Optional
 from datetime import datetime
+from zoneinfo import ZoneInfo
 
-from ..security import get_token_manager, is_local_network_ip
+from ..security import get_token_manager, is_local_network_ip, issue_device_token
 from ..database import get_db
-from ..models.device import Device
+from ..models.device import Device, DeviceToken
+from .websocket_api import manager
 
 router = APIRouter()
 
@@ -164,6 +166,68 @@ async def validate_token(
     }
 
 
+class PairRequest(BaseModel):
+    """设备配对请求"""
+    token: str
+    device_id: str
+    device_type: str = "miniapp"
+    device_name: Optional[str] = None
+
+
+class PairResult(BaseModel):
+    """配对结果"""
+    device_id: str
+    device_token: str
+
+
+@router.post("/pair", response_model=PairResult)
+async def pair_device(
+    body: PairRequest,
+    request: Request,
+    db: Session = Depends(get_db)
+):
+    """
+    使用配对 Token 完成配对
+    
+    **功能**:
+    - 校验扫码取得的一次性配对 Token（使用后失效）
+    - 为设备生成设备 Token，之后的 HTTP 请求和 WebSocket 连接都需要带上
+    - 撤销设备后设备 Token 立即失效，需要重新扫码配对
+    
+    **注意**: 不需要访问 Token，仅允许局域网访问
+    
+    **返回**:
+    - device_token: 设备 Token（只返回这一次）
+    """
+    if not is_local_network_ip(request.client.host):
+        raise HTTPException(
+            status_code=403,
+            detail="仅允许局域网访问"
+        )
+    
+    token_manager = get_token_manager()
+    if not token_manager.validate_token(body.token, mark_as_used=True):
+        raise HTTPException(
+            status_code=401,
+            detail="配对 Token 无效或已过期，请重新扫码"
+        )
+    
+    device = db.query(Device).filter(Device.device_id == body.device_id).first()
+    if not device:
+        device = Device(device_id=body.device_id)
+        db.add(device)
+    device.device_type = body.device_type
+    device.device_name = body.device_name or device.device_name or body.device_id
+    device.authenticated = True
+    device.last_seen = datetime.now(ZoneInfo("Asia/Shanghai"))
+    
+    device_token = issue_device_token(db, body.device_id)
+    db.commit()
+    print(f"🔐 设备配对成功: {body.device_id}")
+    
+    return PairResult(device_id=body.device_id, device_token=device_token)
+
+
 @router.get("/pairing_status")
 async def get_pairing_status():
     """
@@ -223,6 +287,7 @@ async def delete_device(
     
     **功能**:
     - 根据 device_id 删除设备
+    - 设备 Token 立即失效，已建立的 WebSocket 连接被断开
     - 删除后该设备需要重新配对
     
     **参数**:
@@ -239,9 +304,12 @@ async def delete_device(
             detail="设备不存在"
         )
     
+    db.query(DeviceToken).filter(DeviceToken.device_id == device_id).delete()
     db.delete(device)
     db.commit()
     
+    await manager.close_device(device_id)
+    
     return {
         "success": True,
         "message": f"设备 {device_id} 已删除"
@@ -17,7 +17,7 @@ from app.config import settings
 from pydantic import BaseModel
 
 # 创建静态文件目录
-STATIC_DIR = Path("static")
+STATIC_DIR = settings.STATIC_DIR
 IMAGES_DIR = STATIC_DIR / "images" / "products"
 IMAGES_DIR.mkdir(parents=True, exist_ok=True)
 
@@ -1,10 +1,14 @@
 """报表 API"""
 
 from fastapi import APIRouter, Depends, Query
+from fastapi.responses import HTMLResponse
 from sqlalchemy.orm import Session
 from typing import Optional
+from datetime import datetime
+from html import escape
 
 from ..database import get_db
+from ..models.day_close import DayClose
 from ..services.report_service import ReportService
 
 router = APIRouter()
@@ -28,6 +32,116 @@ async def get_daily_sales_report(
     return service.get_daily_sales_report(date)
 
 
+@router.get("/sales_daily/html", response_class=HTMLResponse)
+async def get_daily_sales_report_html(
+    date: str = Query(..., description="日期 (YYYY-MM-DD)", pattern=r"^\d{4}-\d{2}-\d{2}$"),
+    db: Session = Depends(get_db)
+):
+    """
+    日结报表（Z 报表）的打印版本
+
+    桌面壳程序用隐藏窗口打开此页面并另存为 PDF 归档
+    """
+    report = ReportService(db).get_daily_sales_report(date)
+
+    product_rows = "".join(
+        f"<tr><td>{i}</td><td>{escape(p['name'])}</td><td>{escape(p['barcode'] or '')}</td>"
+        f"<td class='num'>{p['quantity']}</td><td class='num'>¥{p['revenue']:.2f}</td></tr>"
+        for i, p in enumerate(report["top_products"], start=1)
+    ) or "<tr><td colspan='5' class='empty'>当日无销售</td></tr>"
+
+    hour_rows = "".join(
+        f"<tr><td>{h['hour']:02d}:00 - {h['hour']:02d}:59</td>"
+        f"<td class='num'>{h['order_count']}</td><td class='num'>¥{h['revenue']:.2f}</td></tr>"
+        for h in report["hourly_distribution"]
+        if h["order_count"] > 0
+    ) or "<tr><td colspan='3' class='empty'>当日无销售</td></tr>"
+
+    return f"""<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<title>日结报表 {date}</title>
+<style>
+  @page {{ size: A4; margin: 15mm; }}
+  body {{ font-family: "Microsoft YaHei", sans-serif; font-size: 12px; color: #222; }}
+  h1 {{ font-size: 20px; margin: 0 0 4px; }}
+  .meta {{ color: #666; margin-bottom: 16px; }}
+  .summary {{ display: flex; gap: 12px; margin-bottom: 16px; }}
+  .summary div {{ flex: 1; border: 1px solid #ccc; padding: 8px; }}
+  .summary b {{ display: block; font-size: 16px; margin-top: 4px; }}
+  h2 {{ font-size: 14px; margin: 16px 0 6px; }}
+  table {{ width: 100%; border-collapse: collapse; }}
+  th, td {{ border-bottom: 1px solid #ddd; padding: 4px 6px; text-align: left; }}
+  th {{ background: #f3f3f3; }}
+  .num {{ text-align: right; }}
+  .empty {{ text-align: center; color: #999; }}
+</style>
+</head>
+<body>
+<h1>日结报表（Z 报表）</h1>
+<div class="meta">营业日期：{date}　生成时间：{datetime.now().strftime("%Y-%m-%d %H:%M:%S")}</div>
+<div class="summary">
+  <div>销售额<b>¥{report["total_revenue"]:.2f}</b></div>
+  <div>订单数<b>{report["order_count"]}</b></div>
+  <div>商品件数<b>{report["item_count"]}</b></div>
+  <div>客单价<b>¥{report["avg_order_value"]:.2f}</b></div>
+</div>
+<h2>热销商品</h2>
+<table>
+  <tr><th>#</th><th>商品</th><th>条码</th><th class="num">数量</th><th class="num">销售额</th></tr>
+  {product_rows}
+</table>
+<h2>分时段销售</h2>
+<table>
+  <tr><th>时段</th><th class="num">订单数</th><th class="num">销售额</th></tr>
+  {hour_rows}
+</table>
+</body>
+</html>"""
+
+
+@router.post("/day_close")
+async def close_day(
+    date: str = Query(..., description="营业日期 (YYYY-MM-DD)", pattern=r"^\d{4}-\d{2}-\d{2}$"),
+    closed_by: str = Query("desktop", description="日结操作者"),
+    db: Session = Depends(get_db)
+):
+    """
+    日结
+
+    **功能**:
+    - 汇总指定营业日的销售数据并记录日结
+    - 同一营业日重复日结时返回已有记录（already_closed 为 true），不会重复记录
+
+    **示例**: `POST /reports/day_close?date=2024-01-15`
+    """
+    record = db.query(DayClose).filter(DayClose.business_date == date).first()
+    already_closed = record is not None
+    if record is None:
+        report = ReportService(db).get_daily_sales_report(date)
+        record = DayClose(
+            business_date=date,
+            total_revenue=report["total_revenue"],
+            order_count=report["order_count"],
+            item_count=report["item_count"],
+            closed_by=closed_by,
+        )
+        db.add(record)
+        db.commit()
+        db.refresh(record)
+
+    return {
+        "date": record.business_date,
+        "total_revenue": record.total_revenue,
+        "order_count": record.order_count,
+        "item_count": record.item_count,
+        "closed_by": record.closed_by,
+        "closed_at": record.closed_at.isoformat() if record.closed_at else None,
+        "already_closed": already_closed,
+    }
+
+
 @router.get("/sales_monthly")
 async def get_monthly_sales_report(
     month: str = Query(..., description="月份 (YYYY-MM)", pattern=r"^\d{4}-\d{2}$"),
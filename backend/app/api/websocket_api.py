@@ -70,6 +70,16 @@ class ConnectionManager:
             del self.active_connections[device_id]
             print(f"❌ 设备 {device_id} 已断开，当前在线: {len(self.active_connections)}")
     
+    async def close_device(self, device_id: str):
+        """关闭设备的连接（设备被撤销时）"""
+        websocket = self.active_connections.pop(device_id, None)
+        if websocket:
+            try:
+                await websocket.close(code=1008)
+            except Exception as e:
+                print(f"⚠️ 关闭 {device_id} 的连接失败: {e}")
+            print(f"🚫 设备 {device_id} 已撤销，连接已关闭")
+    
     async def send_to_device(self, device_id: str, message: dict):
         """发送消息给指定设备"""
         if device_id in self.active_connections:
@@ -190,10 +200,23 @@ async def websocket_endpoint(websocket: WebSocket):
                     })
                     continue
                 
+                # 连接时出示的设备 Token 所属的设备（由访问 Token 中间件记录）
+                paired_device = websocket.scope.get("state", {}).get("device_id")
+                if paired_device and paired_device != device_id:
+                    await websocket.send_json({
+                        "type": "REGISTER_FAILED",
+                        "message": "设备 Token 与设备不符，请重新扫码配对",
+                        "ts": int(datetime.now(ZoneInfo("Asia/Shanghai")).timestamp())
+                    })
+                    continue
+                
                 # 验证 Token（桌面端不需要 Token，小程序需要）
                 if device_type == "miniapp":
+                    if paired_device:
+                        authenticated = True
+                        print(f"🔐 设备 Token 验证成功: {device_id}")
                     # 检查是否是已认证过的设备（从数据库查询，允许重连）
-                    if manager.is_device_authenticated(device_id):
+                    elif manager.is_device_authenticated(device_id):
                         authenticated = True
                         print(f"🔄 设备重连: {device_id}（已认证，跳过 Token 验证）")
                     elif token:
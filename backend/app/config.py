@@ -7,14 +7,22 @@ from pathlib import Path
 class Settings:
     """应用配置"""
     
+    # 数据目录（数据库、上传文件、商品索引），可由桌面壳程序通过环境变量指定
+    DATA_DIR = Path(os.getenv("SMARTMART_DATA_DIR", "."))
+    
     # 数据库
-    DATABASE_URL = "sqlite:///./smartmart.db"
+    DATABASE_URL = f"sqlite:///{(DATA_DIR / 'smartmart.db').as_posix()}"
+    
+    # 访问 Token：由桌面壳程序每次启动时生成，未设置时不校验（开发模式下手动启动）
+    API_TOKEN = os.getenv("SMARTMART_API_TOKEN") or None
     
     # CORS
     CORS_ORIGINS = ["*"]  # 开发环境允许所有源，生产环境应限制
     
     # 文件上传
-    UPLOAD_DIR = Path("./uploads")
+    UPLOAD_DIR = DATA_DIR / "uploads"
+    # 静态文件（商品图片）
+    STATIC_DIR = DATA_DIR / "static"
     MAX_UPLOAD_SIZE = 10 * 1024 * 1024  # 10MB
     
     # WebSocket
@@ -26,8 +34,8 @@ class Settings:
     MODEL_CACHE_DIR = os.getenv("MODEL_CACHE_DIR", "./models")
     
     # FAISS 索引配置
-    FAISS_INDEX_PATH = os.getenv("FAISS_INDEX_PATH", "./data/index/products.index")
-    FAISS_METADATA_PATH = os.getenv("FAISS_METADATA_PATH", "./data/index/products_metadata.json")
+    FAISS_INDEX_PATH = os.getenv("FAISS_INDEX_PATH", str(DATA_DIR / "data/index/products.index"))
+    FAISS_METADATA_PATH = os.getenv("FAISS_METADATA_PATH", str(DATA_DIR / "data/index/products_metadata.json"))
     
     # 识别配置
     TOP_K = int(os.getenv("TOP_K", "5"))  # 返回前 K 个最相似的结果
@@ -5,13 +5,16 @@ from fastapi.middleware.cors import CORSMiddleware
 from fastapi.staticfiles import StaticFiles
 from contextlib import asynccontextmanager
 from pathlib import Path
+import time
 
+from app.config import settings as app_settings
 from app.database import engine, Base, init_sample_data
-from app.api import products, websocket_api, orders, vision, reports, analysis, pairing, recognition, samples, database, settings
+from app.security import AccessTokenMiddleware, AccessTokenLogFilter
+from app.api import products, websocket_api, orders, vision, reports, analysis, pairing, recognition, samples, database, settings, admin
 
 # 确保静态文件目录存在
-STATIC_DIR = Path("static")
-STATIC_DIR.mkdir(exist_ok=True)
+STATIC_DIR = app_settings.STATIC_DIR
+STATIC_DIR.mkdir(parents=True, exist_ok=True)
 (STATIC_DIR / "images" / "products").mkdir(parents=True, exist_ok=True)
 
 
@@ -32,12 +35,16 @@ def warmup_ai_models():
 @asynccontextmanager
 async def lifespan(app: FastAPI):
     """应用生命周期管理"""
-    # 启动时：创建数据库表
+    # 启动时：创建数据库表（记录耗时，供桌面壳程序统计启动时间）
     print("🚀 正在初始化数据库...")
+    started = time.perf_counter()
     Base.metadata.create_all(bind=engine)
+    app.state.startup_timings = {"migrations": round((time.perf_counter() - started) * 1000)}
     
     # 初始化示例数据
+    started = time.perf_counter()
     init_sample_data()
+    app.state.startup_timings["sample_data"] = round((time.perf_counter() - started) * 1000)
     print("✅ 数据库初始化完成")
     
     # 预热 AI 模型
@@ -46,7 +53,8 @@ async def lifespan(app: FastAPI):
     
     yield
     
-    # 关闭时清理
+    # 关闭时清理：释放数据库连接（SQLite 在此时完成 WAL 检查点）
+    engine.dispose()
     print("👋 应用关闭")
 
 
@@ -57,6 +65,10 @@ app = FastAPI(
     lifespan=lifespan,
 )
 
+# 访问 Token 校验（需在 CORS 之前添加，使 CORS 位于外层处理预检请求）
+if app_settings.API_TOKEN:
+    app.add_middleware(AccessTokenMiddleware, token=app_settings.API_TOKEN)
+
 # CORS 配置（局域网访问 - 开发环境）
 # ⚠️ 注意：生产环境请限制 allow_origins
 app.add_middleware(
@@ -68,7 +80,7 @@ app.add_middleware(
 )
 
 # 挂载静态文件目录（用于访问上传的图片）
-app.mount("/static", StaticFiles(directory="static"), name="static")
+app.mount("/static", StaticFiles(directory=STATIC_DIR), name="static")
 
 # 注册路由
 app.include_router(products.router, prefix="/products", tags=["商品管理"])
@@ -81,6 +93,7 @@ app.include_router(recognition.router, prefix="/api/recognition", tags=["AI 识
 app.include_router(samples.router, prefix="/api/samples", tags=["AI 样本管理"])
 app.include_router(database.router, prefix="/database", tags=["数据库管理"])
 app.include_router(settings.router, tags=["系统设置"])
+app.include_router(admin.router, tags=["壳程序管理"])
 app.include_router(websocket_api.router, tags=["WebSocket"])
 
 
@@ -115,12 +128,13 @@ def get_base_path():
 
 if __name__ == "__main__":
     import argparse
+    import logging
     import os
     import uvicorn
     
     # 解析命令行参数
     parser = argparse.ArgumentParser(description="SmartMart Backend Server")
-    parser.add_argument("--host", default="0.0.0.0", help="绑定地址")
+    parser.add_argument("--host", default="127.0.0.1", help="绑定地址（局域网访问时使用 0.0.0.0）")
     parser.add_argument("--port", type=int, default=8000, help="监听端口")
     args = parser.parse_args()
     
@@ -132,11 +146,21 @@ if __name__ == "__main__":
     # 启动服务器
     print(f"🚀 启动服务器: http://{args.host}:{args.port}")
     
+    # 日志级别可由桌面壳程序通过环境变量指定，运行中可通过 /admin/log-level 调整
+    log_level = os.getenv("SMARTMART_LOG_LEVEL", "info").lower()
+    
     # 注意：打包后必须直接传入 app 对象，不能用字符串 "app.main:app"
-    uvicorn.run(
+    config = uvicorn.Config(
         app,  # 直接传入 app 对象
         host=args.host,
         port=args.port,
-        log_level="info"
+        log_level=log_level
     )
+    # 访问日志和 WebSocket 日志中的地址可能包含 access_token，输出前隐藏
+    for logger_name in ("uvicorn.access", "uvicorn.error"):
+        logging.getLogger(logger_name).addFilter(AccessTokenLogFilter())
+    server = uvicorn.Server(config)
+    # 供 /admin/shutdown 正常退出
+    app.state.server = server
+    server.run()
 
@@ -3,9 +3,10 @@
 from app.models.product import Product
 from app.models.inventory import InventoryMove
 from app.models.transaction import Order, OrderItem
-from app.models.device import Device
+from app.models.device import Device, DeviceToken
 from app.models.vision import VisionSample
 from app.models.settings import SystemSettings
+from app.models.day_close import DayClose
 
-__all__ = ["Product", "InventoryMove", "Order", "OrderItem", "Device", "VisionSample", "SystemSettings"]
+__all__ = ["Product", "InventoryMove", "Order", "OrderItem", "Device", "DeviceToken", "VisionSample", "SystemSettings", "DayClose"]
 
@@ -0,0 +1,21 @@
+"""日结记录模型"""
+
+from sqlalchemy import Column, Integer, String, Float, DateTime
+from datetime import datetime
+from zoneinfo import ZoneInfo
+
+from app.database import Base
+
+
+class DayClose(Base):
+    """日结记录 - 每个营业日一条，日结后该日的报表数据固定下来"""
+
+    __tablename__ = "day_closes"
+
+    id = Column(Integer, primary_key=True, index=True, autoincrement=True)
+    business_date = Column(String(10), unique=True, index=True, nullable=False)  # YYYY-MM-DD
+    total_revenue = Column(Float, nullable=False, default=0)
+    order_count = Column(Integer, nullable=False, default=0)
+    item_count = Column(Integer, nullable=False, default=0)
+    closed_by = Column(String(100))  # desktop（定时任务）或操作员
+    closed_at = Column(DateTime, default=lambda: datetime.now(ZoneInfo("Asia/Shanghai")))
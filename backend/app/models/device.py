@@ -21,3 +21,14 @@ class Device(Base):
     created_at = Column(DateTime, default=lambda: datetime.now(ZoneInfo("Asia/Shanghai")))
 
 
+class DeviceToken(Base):
+    """设备访问 Token - 配对成功后发给设备，只保存哈希，撤销设备时删除"""
+    
+    __tablename__ = "device_tokens"
+    
+    id = Column(Integer, primary_key=True, index=True, autoincrement=True)
+    device_id = Column(String(100), index=True, nullable=False)
+    token_hash = Column(String(64), unique=True, index=True, nullable=False)  # SHA-256
+    created_at = Column(DateTime, default=lambda: datetime.now(ZoneInfo("Asia/Shanghai")))
+
+
@@ -1,11 +1,18 @@
 """安全认证模块"""
 
+import hashlib
+import logging
+import re
 import secrets
 import time
 from typing import Optional, Dict
+from urllib.parse import parse_qs
 from datetime import datetime, timedelta
 from zoneinfo import ZoneInfo
 
+from app.database import SessionLocal
+from app.models.device import DeviceToken
+
 
 class TokenManager:
     """Token 管理器（简单实现）"""
@@ -140,3 +147,110 @@ def is_local_network_ip(ip: str) -> bool:
     return False
 
 
+def hash_device_token(token: str) -> str:
+    """设备 Token 的哈希（数据库中只保存哈希）"""
+    return hashlib.sha256(token.encode()).hexdigest()
+
+
+def issue_device_token(db, device_id: str) -> str:
+    """
+    为配对成功的设备生成访问 Token
+    
+    每个设备只有一个有效 Token，重新配对时替换旧 Token。由调用方提交事务。
+    """
+    token = secrets.token_urlsafe(32)
+    db.query(DeviceToken).filter(DeviceToken.device_id == device_id).delete()
+    db.add(DeviceToken(device_id=device_id, token_hash=hash_device_token(token)))
+    return token
+
+
+def find_paired_device(token: str) -> Optional[str]:
+    """查找设备 Token 所属的设备，Token 不存在或设备已撤销时返回 None"""
+    db = SessionLocal()
+    try:
+        row = (
+            db.query(DeviceToken.device_id)
+            .filter(DeviceToken.token_hash == hash_device_token(token))
+            .first()
+        )
+        return row[0] if row else None
+    finally:
+        db.close()
+
+
+class AccessTokenMiddleware:
+    """
+    校验桌面壳程序生成的访问 Token
+    
+    请求需带 Authorization: Bearer <token>，WebSocket 和 <img> 等无法设置请求头的地址
+    使用 access_token 查询参数。健康检查和 /static 下的图片不校验。
+    局域网模式只改变监听地址，局域网内的请求同样需要出示 Token：其他收银台和手机使用配对时
+    取得的设备 Token（撤销设备后立即失效），所属设备记录在 scope["state"]["device_id"]。
+    配对接口使用一次性配对 Token 校验，不需要访问 Token。
+    """
+    
+    PUBLIC_PATHS = ("/health", "/pairing/pair")
+    PUBLIC_PREFIXES = ("/static/",)
+    
+    def __init__(self, app, token: str):
+        self.app = app
+        self.token = token.encode()
+    
+    def _presented_token(self, scope) -> Optional[bytes]:
+        for name, value in scope.get("headers") or []:
+            if name == b"authorization" and value.startswith(b"Bearer "):
+                return value[len(b"Bearer "):].strip()
+        query = parse_qs(scope.get("query_string", b"").decode("latin-1"))
+        token = query.get("access_token")
+        return token[0].encode("latin-1") if token else None
+    
+    def _allowed(self, scope) -> bool:
+        path = scope.get("path", "")
+        if path in self.PUBLIC_PATHS or path.startswith(self.PUBLIC_PREFIXES):
+            return True
+        token = self._presented_token(scope)
+        if token is None:
+            return False
+        if secrets.compare_digest(token, self.token):
+            return True
+        device_id = find_paired_device(token.decode("latin-1"))
+        if device_id is None:
+            return False
+        scope.setdefault("state", {})["device_id"] = device_id
+        return True
+    
+    async def __call__(self, scope, receive, send):
+        if scope["type"] not in ("http", "websocket") or self._allowed(scope):
+            await self.app(scope, receive, send)
+            return
+        
+        if scope["type"] == "websocket":
+            # 握手前关闭，客户端收到 403
+            await send({"type": "websocket.close", "code": 1008})
+            return
+        
+        body = '{"detail":"未授权的访问"}'.encode()
+        await send({
+            "type": "http.response.start",
+            "status": 401,
+            "headers": [
+                (b"content-type", b"application/json"),
+                (b"content-length", str(len(body)).encode()),
+            ],
+        })
+        await send({"type": "http.response.body", "body": body})
+
+
+class AccessTokenLogFilter(logging.Filter):
+    """隐藏日志中 access_token 查询参数的值（uvicorn 的访问日志和 WebSocket 日志包含完整地址）"""
+    
+    PATTERN = re.compile(r"(access_token=)[^&#\s\"']+")
+    
+    def _redact(self, value):
+        return self.PATTERN.sub(r"\1<redacted>", value) if isinstance(value, str) else value
+    
+    def filter(self, record: logging.LogRecord) -> bool:
+        record.msg = self._redact(record.msg)
+        if isinstance(record.args, tuple):
+            record.args = tuple(self._redact(arg) for arg in record.args)
+        return True
@@ -0,0 +1,252 @@
+// 辅助功能设置
+//
+// 读取系统的高对比度、减少动画和文字大小设置，前端据此调整界面，方便视力不好的店员使用。
+// 设置变化时发出 accessibility://changed 事件。
+//
+// Windows 下通过主窗口的 WM_SETTINGCHANGE 消息得到变化通知；
+// macOS 和 Linux 没有可用的通知，定期重新读取。
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::events::{self, EventKind};
+
+/// 上次读取的设置
+static CURRENT: Mutex<Option<AccessibilitySettings>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AccessibilitySettings {
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+    /// 文字缩放比例，1.0 为 100%
+    pub text_scale: f64,
+}
+
+#[cfg(target_os = "windows")]
+fn read() -> AccessibilitySettings {
+    windows::read()
+}
+
+#[cfg(target_os = "macos")]
+fn read() -> AccessibilitySettings {
+    let flag = |key: &str| {
+        std::process::Command::new("defaults")
+            .args(["read", "com.apple.universalaccess", key])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+    };
+    AccessibilitySettings {
+        high_contrast: flag("increaseContrast"),
+        reduced_motion: flag("reduceMotion"),
+        text_scale: 1.0,
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn read() -> AccessibilitySettings {
+    let get = |schema: &str, key: &str| {
+        std::process::Command::new("gsettings")
+            .args(["get", schema, key])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    AccessibilitySettings {
+        high_contrast: get("org.gnome.desktop.a11y.interface", "high-contrast").as_deref()
+            == Some("true"),
+        reduced_motion: get("org.gnome.desktop.interface", "enable-animations").as_deref()
+            == Some("false"),
+        text_scale: get("org.gnome.desktop.interface", "text-scaling-factor")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(1.0),
+    }
+}
+
+/// 当前的辅助功能设置
+pub fn current() -> AccessibilitySettings {
+    let mut current = CURRENT.lock().unwrap();
+    *current.get_or_insert_with(read)
+}
+
+/// 重新读取设置，有变化时发出事件
+fn refresh(app_handle: &tauri::AppHandle) {
+    let settings = read();
+    let changed = {
+        let mut current = CURRENT.lock().unwrap();
+        let changed = current.is_some_and(|previous| previous != settings);
+        *current = Some(settings);
+        changed
+    };
+    if changed {
+        tracing::info!("辅助功能设置已变化: {:?}", settings);
+        events::record(
+            EventKind::State,
+            "辅助功能设置变化",
+            serde_json::json!(settings),
+        );
+        let _ = app_handle.emit_all("accessibility://changed", settings);
+    }
+}
+
+pub fn start(app_handle: &tauri::AppHandle) {
+    *CURRENT.lock().unwrap() = Some(read());
+
+    #[cfg(target_os = "windows")]
+    windows::install(app_handle);
+
+    #[cfg(not(target_os = "windows"))]
+    poll(app_handle.clone());
+}
+
+#[cfg(not(target_os = "windows"))]
+fn poll(app_handle: tauri::AppHandle) {
+    const INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+    let spawned = std::thread::Builder::new()
+        .name("accessibility".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(INTERVAL);
+            refresh(&app_handle);
+        });
+    if let Err(e) = spawned {
+        tracing::error!("启动辅助功能检测线程失败: {}", e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+    use windows_sys::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows_sys::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST, WM_SETTINGCHANGE,
+        WM_SYSCOLORCHANGE, WM_THEMECHANGED,
+    };
+
+    use tauri::Manager;
+
+    use super::AccessibilitySettings;
+
+    const SUBCLASS_ID: usize = 0x534d_4131; // "SMA1"
+
+    static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn high_contrast() -> bool {
+        let mut info: HIGHCONTRASTW = unsafe { std::mem::zeroed() };
+        info.cbSize = std::mem::size_of::<HIGHCONTRASTW>() as u32;
+        let ok = unsafe {
+            SystemParametersInfoW(
+                SPI_GETHIGHCONTRAST,
+                info.cbSize,
+                &mut info as *mut HIGHCONTRASTW as *mut _,
+                0,
+            )
+        };
+        ok != 0 && info.dwFlags & HCF_HIGHCONTRASTON != 0
+    }
+
+    fn animations_enabled() -> bool {
+        let mut enabled = 1i32;
+        let ok = unsafe {
+            SystemParametersInfoW(
+                SPI_GETCLIENTAREAANIMATION,
+                0,
+                &mut enabled as *mut i32 as *mut _,
+                0,
+            )
+        };
+        ok == 0 || enabled != 0
+    }
+
+    /// “设置 → 辅助功能 → 文本大小”（100 - 225）
+    fn text_scale() -> f64 {
+        let key = wide("Software\\Microsoft\\Accessibility");
+        let name = wide("TextScaleFactor");
+        let mut value = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_DWORD,
+                std::ptr::null_mut(),
+                &mut value as *mut u32 as *mut _,
+                &mut size,
+            )
+        };
+        if status == 0 && (100..=225).contains(&value) {
+            value as f64 / 100.0
+        } else {
+            1.0
+        }
+    }
+
+    pub fn read() -> AccessibilitySettings {
+        AccessibilitySettings {
+            high_contrast: high_contrast(),
+            reduced_motion: !animations_enabled(),
+            text_scale: text_scale(),
+        }
+    }
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        _data: usize,
+    ) -> LRESULT {
+        if matches!(msg, WM_SETTINGCHANGE | WM_SYSCOLORCHANGE | WM_THEMECHANGED) {
+            if let Some(app_handle) = APP_HANDLE.get() {
+                // 读取注册表较慢，不阻塞窗口消息
+                let app_handle = app_handle.clone();
+                let spawned = std::thread::Builder::new()
+                    .name("accessibility".to_string())
+                    .spawn(move || super::refresh(&app_handle));
+                if let Err(e) = spawned {
+                    tracing::error!("启动辅助功能检测线程失败: {}", e);
+                }
+            }
+        }
+        DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+
+    /// 为主窗口安装消息钩子
+    pub fn install(app_handle: &tauri::AppHandle) {
+        let Some(window) = app_handle.get_window("main") else {
+            tracing::warn!("未找到主窗口，无法监听辅助功能设置");
+            return;
+        };
+        let hwnd = match window.hwnd() {
+            Ok(hwnd) => hwnd.0,
+            Err(e) => {
+                tracing::warn!("获取窗口句柄失败: {}", e);
+                return;
+            }
+        };
+        let _ = APP_HANDLE.set(app_handle.clone());
+
+        let installed = unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0) };
+        if installed == 0 {
+            tracing::warn!("安装窗口消息钩子失败，无法监听辅助功能设置");
+        }
+    }
+}
+
+// Tauri 命令
+
+/// 读取系统的辅助功能设置
+#[tauri::command]
+pub fn get_accessibility_settings() -> AccessibilitySettings {
+    current()
+}
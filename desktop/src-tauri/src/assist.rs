@@ -0,0 +1,292 @@
+// 远程协助诊断模式
+//
+// 门店在技术支持人员提供协助码后开启，会话期间定期把健康状态和最近的日志
+// （已清理个人信息）上传到技术支持服务，支持人员需要时才截取屏幕。
+// 会话到期或任意一方结束后自动停止，减少上门排查。
+//
+// 支持服务接口：
+// - POST {endpoint}/sessions/join            加入会话，返回 session_id、token 和有效期
+// - POST {endpoint}/sessions/{id}/diagnostics 上传诊断数据，返回支持人员的请求（例如截图）
+// - POST {endpoint}/sessions/{id}/screenshots 上传截图（PNG）
+// - POST {endpoint}/sessions/{id}/end         结束会话
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Manager, State};
+
+use crate::config::ConfigStore;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::reporting::{self, ReportErr};
+use crate::{health, http, logging, screenshot, telemetry};
+
+/// 构建时内置的支持服务地址
+const BUILTIN_ENDPOINT: Option<&str> = option_env!("SMARTMART_SUPPORT_URL");
+
+/// 每次上传的日志行数
+const LOG_TAIL_LINES: usize = 200;
+
+/// 当前会话编号，会话结束或重新开始时递增，旧的上传线程随之退出
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+struct Session {
+    id: String,
+    token: String,
+    endpoint: String,
+    started_at: DateTime<Local>,
+    expires_at: DateTime<Local>,
+    uploads: u64,
+    screenshots: u64,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportSessionStatus {
+    pub active: bool,
+    pub session_id: Option<String>,
+    pub started_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub uploads: u64,
+    pub screenshots: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JoinResponse {
+    session_id: String,
+    token: String,
+    expires_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct UploadResponse {
+    /// 支持人员的请求，例如 "screenshot"
+    requests: Vec<String>,
+    /// 支持人员已结束会话
+    end: bool,
+}
+
+fn status() -> SupportSessionStatus {
+    let session = SESSION.lock().unwrap().clone();
+    SupportSessionStatus {
+        active: session.is_some(),
+        session_id: session.as_ref().map(|s| s.id.clone()),
+        started_at: session.as_ref().map(|s| s.started_at.to_rfc3339()),
+        expires_at: session.as_ref().map(|s| s.expires_at.to_rfc3339()),
+        uploads: session.as_ref().map(|s| s.uploads).unwrap_or_default(),
+        screenshots: session.as_ref().map(|s| s.screenshots).unwrap_or_default(),
+        last_error: session.and_then(|s| s.last_error),
+    }
+}
+
+fn emit_status(app_handle: &tauri::AppHandle) {
+    let _ = app_handle.emit_all("support://session", status());
+}
+
+fn update_session(generation: u64, f: impl FnOnce(&mut Session)) {
+    if GENERATION.load(Ordering::SeqCst) != generation {
+        return;
+    }
+    if let Some(session) = SESSION.lock().unwrap().as_mut() {
+        f(session);
+    }
+}
+
+fn diagnostics(app_handle: &tauri::AppHandle) -> serde_json::Value {
+    let logs: Vec<String> = logging::recent_lines(LOG_TAIL_LINES)
+        .iter()
+        .map(|line| reporting::scrub(line))
+        .collect();
+    json!({
+        "timestamp": Local::now().to_rfc3339(),
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "health": health::collect(app_handle),
+        "logs": logs,
+    })
+}
+
+fn upload_screenshots(session: &Session) -> Result<u64, String> {
+    let url = format!("{}/sessions/{}/screenshots", session.endpoint, session.id);
+    let mut uploaded = 0;
+    for shot in screenshot::capture_all()? {
+        http::external()
+            .post(&url)
+            .set("Authorization", &format!("Bearer {}", session.token))
+            .set("Content-Type", "image/png")
+            .query("monitor", &shot.monitor)
+            .query("width", &shot.width.to_string())
+            .query("height", &shot.height.to_string())
+            .send_bytes(&shot.png)
+            .map_err(|e| format!("上传截图失败: {}", e))?;
+        uploaded += 1;
+    }
+    Ok(uploaded)
+}
+
+/// 结束会话并通知支持服务
+fn end_session(app_handle: &tauri::AppHandle, reason: &str) {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    let Some(session) = SESSION.lock().unwrap().take() else {
+        return;
+    };
+
+    let _ = http::external()
+        .post(&format!("{}/sessions/{}/end", session.endpoint, session.id))
+        .set("Authorization", &format!("Bearer {}", session.token))
+        .send_json(json!({ "reason": reason }));
+    tracing::info!("远程协助已结束: {}", reason);
+    events::record(EventKind::State, "远程协助已结束", json!({ "reason": reason }));
+    emit_status(app_handle);
+}
+
+fn run_session(app_handle: tauri::AppHandle, generation: u64, interval: Duration) {
+    loop {
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let Some(session) = SESSION.lock().unwrap().clone() else {
+            return;
+        };
+        if Local::now() >= session.expires_at {
+            end_session(&app_handle, "expired");
+            return;
+        }
+
+        let response = http::external()
+            .post(&format!(
+                "{}/sessions/{}/diagnostics",
+                session.endpoint, session.id
+            ))
+            .set("Authorization", &format!("Bearer {}", session.token))
+            .send_json(diagnostics(&app_handle))
+            .map_err(|e| format!("上传诊断数据失败: {}", e))
+            .and_then(|r| {
+                r.into_json::<UploadResponse>()
+                    .map_err(|e| format!("解析支持服务响应失败: {}", e))
+            });
+
+        match response {
+            Ok(response) => {
+                update_session(generation, |s| {
+                    s.uploads += 1;
+                    s.last_error = None;
+                });
+                if response.end {
+                    end_session(&app_handle, "ended_by_support");
+                    return;
+                }
+                if response.requests.iter().any(|r| r == "screenshot") {
+                    // 让收银员知道支持人员正在查看屏幕
+                    let _ = app_handle.emit_all("support://screenshot", ());
+                    match upload_screenshots(&session) {
+                        Ok(count) => update_session(generation, |s| s.screenshots += count),
+                        Err(e) => {
+                            tracing::warn!("{}", e);
+                            update_session(generation, |s| s.last_error = Some(e));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::warn!("{}", e);
+                update_session(generation, |s| s.last_error = Some(e));
+            }
+        }
+        emit_status(&app_handle);
+
+        std::thread::sleep(interval);
+    }
+}
+
+// Tauri 命令
+
+/// 使用技术支持提供的协助码开启远程协助
+#[tauri::command]
+pub async fn start_support_session(
+    code: String,
+    app_handle: tauri::AppHandle,
+    config: State<'_, ConfigStore>,
+) -> AppResult<SupportSessionStatus> {
+    let code = code.trim().to_ascii_uppercase();
+    if !(4..=16).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(AppError::InvalidArgument(format!(
+            "协助码格式错误: {}",
+            code
+        )))
+        .reported("start_support_session");
+    }
+
+    let settings = config.get().support;
+    let endpoint = settings
+        .endpoint
+        .filter(|e| !e.is_empty())
+        .or_else(|| BUILTIN_ENDPOINT.map(str::to_string))
+        .map(|e| e.trim_end_matches('/').to_string())
+        .ok_or_else(|| AppError::Config("未配置远程协助服务地址".to_string()))
+        .reported("start_support_session")?;
+
+    // 已有会话时先结束
+    end_session(&app_handle, "restarted");
+
+    let joined: JoinResponse = http::external()
+        .post(&format!("{}/sessions/join", endpoint))
+        .send_json(json!({
+            "code": code,
+            "app_version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+        }))
+        .map_err(|e| AppError::Network(format!("加入远程协助失败: {}", e)))
+        .and_then(|r| {
+            r.into_json()
+                .map_err(|e| AppError::Network(format!("解析支持服务响应失败: {}", e)))
+        })
+        .reported("start_support_session")?;
+
+    // 取服务端有效期和本地上限中较短的一个
+    let max_secs = settings.max_minutes.max(1) * 60;
+    let ttl_secs = joined.expires_in_secs.unwrap_or(max_secs).min(max_secs);
+    let now = Local::now();
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *SESSION.lock().unwrap() = Some(Session {
+        id: joined.session_id,
+        token: joined.token,
+        endpoint,
+        started_at: now,
+        expires_at: now + chrono::Duration::seconds(ttl_secs as i64),
+        uploads: 0,
+        screenshots: 0,
+        last_error: None,
+    });
+    tracing::info!("远程协助已开启, 有效期: {}秒", ttl_secs);
+    events::record(EventKind::State, "远程协助已开启", json!({ "ttl_secs": ttl_secs }));
+    telemetry::record_feature("support_session");
+
+    let interval = Duration::from_secs(settings.upload_interval_secs.max(5));
+    let thread_handle = app_handle.clone();
+    std::thread::Builder::new()
+        .name("support-session".into())
+        .spawn(move || run_session(thread_handle, generation, interval))
+        .map_err(|e| AppError::Internal(format!("启动远程协助线程失败: {}", e)))
+        .reported("start_support_session")?;
+
+    emit_status(&app_handle);
+    Ok(status())
+}
+
+#[tauri::command]
+pub async fn stop_support_session(app_handle: tauri::AppHandle) -> SupportSessionStatus {
+    end_session(&app_handle, "ended_by_user");
+    status()
+}
+
+#[tauri::command]
+pub fn get_support_session() -> SupportSessionStatus {
+    status()
+}
@@ -1,97 +1,1041 @@
 // Backend 进程管理模块
+//
+// 壳程序启动的 Backend 由监护线程（supervise）照看：定期检查进程是否退出并读取看门狗的健康检查结果，
+// 进程崩溃或长时间无响应时按指数退避自动重启。重启时只在取出和放回进程时持有进程锁。状态变化时发出 backend-status-changed 事件，
+// 前端据此显示“正在重新连接”，而不是让接口请求静默失败。
+//
+// 端口优先使用配置中的端口，被其他程序占用时在配置的范围内查找空闲端口。
+// 实际使用的端口通过 get_backend_port 命令和 backend://port 事件告知前端。
+//
+// 可执行文件按平台查找（Windows 为 smartmart-backend.exe，其他平台为 smartmart-backend），
+// 也可通过 SMARTMART_BACKEND_PATH 环境变量指定。
+//
+// 监听地址和数据目录来自配置（backend 分区），数据目录通过 SMARTMART_DATA_DIR 环境变量传给 Backend。
+//
+// 默认只监听 127.0.0.1。壳程序每次启动生成随机的访问 Token，通过 SMARTMART_API_TOKEN 传给 Backend，
+// Backend 拒绝不带 Token 的请求（壳程序的请求自动带上，前端通过 get_backend_credentials 取得）。
+// 多台收银台共用一个 Backend 时需在配置中开启局域网模式（backend.lan_mode），局域网模式只改变监听地址，
+// 局域网内的请求同样需要出示 Token。
 
-use std::process::{Child, Command};
-use std::sync::Mutex;
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::Manager;
 
+use crate::config::{BackendConfig, ConfigStore};
+use crate::error::{AppError, AppResult};
+use crate::reporting::ReportErr;
+use crate::watchdog::LatencyTracker;
+use crate::events::{self, EventKind};
+use crate::{backend_logs, http, instance, logging, metrics, performance, startup, system_proxy};
+
+/// 监护线程的检查间隔
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 启动后的宽限期，期间健康检查失败不视为无响应（首次启动需迁移数据库、加载模型）
+const STARTUP_GRACE: Duration = Duration::from_secs(90);
+
+/// 连续无响应超过该时长时重启 Backend
+const UNRESPONSIVE_RESTART: Duration = Duration::from_secs(30);
+
+/// 重启的退避时间（每次失败翻倍）
+const BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// 重启后稳定运行该时长即重置退避时间
+const BACKOFF_RESET: Duration = Duration::from_secs(120);
+
+/// Backend 可执行文件名
+const EXECUTABLE: &str = if cfg!(windows) {
+    "smartmart-backend.exe"
+} else {
+    "smartmart-backend"
+};
+
+/// 指定 Backend 可执行文件路径的环境变量（自定义安装位置或调试）
+const PATH_ENV: &str = "SMARTMART_BACKEND_PATH";
+
+/// 请求 Backend 退出后等待的时长，超时后强制结束
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 指定 Backend 数据目录的环境变量
+const DATA_DIR_ENV: &str = "SMARTMART_DATA_DIR";
+
+/// 传递访问 Token 的环境变量
+const TOKEN_ENV: &str = "SMARTMART_API_TOKEN";
+
+/// 记录 Backend 进程 pid 的文件（位于共享目录），用于清理壳程序崩溃后遗留的进程
+const PID_FILE: &str = "backend.pid";
+
 pub struct BackendProcess {
     child: Option<Child>,
+    host: String,
+    lan_mode: bool,
+    port: u16,
+    /// 优先端口被占用时可使用的端口
+    port_range: RangeInclusive<u16>,
+    started_at: Option<Instant>,
+    /// 壳程序负责运行 Backend（调用过 start 且未主动停止），进程退出时由监护线程重启
+    supervised: bool,
+    /// 正在重启（旧进程已取出，新进程尚未启动）
+    restarting: bool,
 }
 
+/// 启动 Backend 进程所需的设置（在进程锁之外启动时使用）
+struct Launch {
+    host: String,
+    port_range: RangeInclusive<u16>,
+}
+
+/// 从进程中取出的重启任务，由 run 在释放进程锁后停止旧进程、启动新进程
+#[must_use]
+pub struct PendingRestart {
+    child: Option<Child>,
+    old_port: u16,
+    port: u16,
+    /// 先请求 Backend 正常退出时等待的时长（None 时直接停止）
+    shutdown_timeout: Option<Duration>,
+    launch: Launch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendState {
+    /// 已启动，等待健康检查通过
+    Starting,
+    Running,
+    /// 进程在运行但健康检查失败
+    Unresponsive,
+    /// 进程已退出，等待重启
+    Restarting,
+    /// 启动失败，等待重试
+    Failed,
+    /// 未运行（开发模式下未手动启动，或已停止）
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub state: BackendState,
+    pub port: u16,
+    pub pid: Option<u32>,
+    /// 是否由壳程序启动和监护（开发模式或使用其他会话的 Backend 时为 false）
+    pub supervised: bool,
+    /// 自动重启次数
+    pub restarts: u32,
+    /// 下次重启前的等待时间（秒）
+    pub retry_in_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+static STATUS: Mutex<Option<BackendStatus>> = Mutex::new(None);
+
+/// 应用的资源目录（Tauri 打包的 resources；macOS 为 Contents/Resources，Linux 为 /usr/lib/<应用>）
+static RESOURCE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 配置的 Backend 数据目录
+static DATA_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Backend 的访问 Token（每次启动生成，连接其他会话的 Backend 时使用其 Token）
+static TOKEN: OnceLock<String> = OnceLock::new();
+
 impl BackendProcess {
-    pub fn new() -> Self {
-        Self { child: None }
-    }
-
-    pub fn start(&mut self, port: u16) -> Result<(), String> {
-        println!("🚀 启动 Backend 服务...");
-        println!("   端口: {}", port);
-
-        // 获取 backend.exe 路径（尝试多个位置）
-        let exe_dir = std::env::current_exe()
-            .map_err(|e| format!("获取程序路径失败: {}", e))?
-            .parent()
-            .ok_or("无法获取父目录")?
-            .to_path_buf();
-
-        // 尝试多个可能的路径：
-        // 1. 同级目录（开发/便携模式）
-        // 2. resources 子目录（Tauri 打包后的位置）
-        let possible_paths = [
-            exe_dir.join("smartmart-backend.exe"),
-            exe_dir.join("resources").join("smartmart-backend.exe"),
-        ];
-
-        let resource_path = possible_paths
-            .iter()
-            .find(|p| p.exists())
-            .ok_or_else(|| {
-                format!(
-                    "Backend 可执行文件不存在，已尝试路径:\n  - {:?}\n  - {:?}",
-                    possible_paths[0], possible_paths[1]
-                )
-            })?
-            .clone();
-
-        println!("   路径: {:?}", resource_path);
+    pub fn new(settings: &BackendConfig) -> Self {
+        let mut process = Self {
+            child: None,
+            host: String::new(),
+            lan_mode: false,
+            port: settings.port,
+            port_range: settings.port..=settings.port,
+            started_at: None,
+            supervised: false,
+            restarting: false,
+        };
+        process.configure(settings);
+        process
+    }
 
-        // 启动 backend 进程
-        let child = Command::new(resource_path)
-            .args(&[
-                "--host", "0.0.0.0",
-                "--port", &port.to_string(),
-            ])
-            .spawn()
-            .map_err(|e| format!("启动 Backend 失败: {}", e))?;
+    /// 使用新的设置（下次 start 时生效）
+    pub fn configure(&mut self, settings: &BackendConfig) {
+        let loopback = settings
+            .host
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+        self.host = match (settings.lan_mode, loopback) {
+            (false, false) => {
+                tracing::warn!("未开启局域网模式，Backend 只监听本机（忽略配置的地址 {}）", settings.host);
+                Ipv4Addr::LOCALHOST.to_string()
+            }
+            (true, true) => Ipv4Addr::UNSPECIFIED.to_string(),
+            _ => settings.host.clone(),
+        };
+        self.lan_mode = settings.lan_mode;
+        self.port_range = settings.port..=settings.port_range_end.max(settings.port);
+        *DATA_DIR.lock().unwrap() = settings.data_dir.as_ref().map(PathBuf::from);
+    }
 
-        self.child = Some(child);
+    /// 使用不由壳程序启动的 Backend（其他会话中运行或开发模式下手动启动）
+    pub fn attach(&mut self, port: u16) {
+        self.port = port;
+    }
+
+    /// Backend 监听的端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 是否允许局域网内的设备连接
+    pub fn lan_mode(&self) -> bool {
+        self.lan_mode
+    }
+
+    /// 由壳程序启动的 Backend 进程 pid
+    pub fn pid(&self) -> Option<u32> {
+        self.child.as_ref().map(Child::id)
+    }
+
+    /// Backend 进程已运行的时长
+    pub fn uptime(&self) -> Option<Duration> {
+        self.child.as_ref().and(self.started_at).map(|t| t.elapsed())
+    }
 
-        println!("✅ Backend 服务已启动");
+    /// 由壳程序启动的 Backend 进程是否仍在运行
+    pub fn is_running(&mut self) -> bool {
+        self.child
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+    }
+
+    /// 启动 Backend，port 被占用时使用端口范围内的其他空闲端口（只在发布模式下由 main 调用）
+    #[cfg_attr(debug_assertions, allow(dead_code))]
+    pub fn start(&mut self, port: u16) -> AppResult<()> {
+        self.supervised = true;
+        kill_orphan();
+        let (child, port) = self.launch().spawn(port)?;
+        self.install(child, port);
         Ok(())
     }
 
+    /// 开始重启：取出当前进程，返回的 PendingRestart 在释放进程锁后完成重启。
+    /// shutdown_timeout 不为 None 时先请求 Backend 正常退出。已在重启时返回 None
+    pub fn begin_restart(
+        &mut self,
+        port: u16,
+        shutdown_timeout: Option<Duration>,
+    ) -> Option<PendingRestart> {
+        if self.restarting {
+            return None;
+        }
+        self.restarting = true;
+        self.supervised = true;
+        Some(PendingRestart {
+            child: self.take_child(),
+            old_port: self.port,
+            port,
+            shutdown_timeout,
+            launch: self.launch(),
+        })
+    }
+
+    /// 请求 Backend 正常退出（处理完当前请求、关闭数据库），超时后强制结束
+    pub fn shutdown(&mut self, timeout: Duration) {
+        self.supervised = false;
+        if let Some(child) = self.take_child() {
+            stop_child(child, Some((self.port, timeout)));
+            remove_pid_file();
+        }
+    }
+
+    fn launch(&self) -> Launch {
+        Launch {
+            host: self.host.clone(),
+            port_range: self.port_range.clone(),
+        }
+    }
+
+    fn take_child(&mut self) -> Option<Child> {
+        self.started_at = None;
+        self.child.take()
+    }
+
+    /// 记录新启动的 Backend 进程
+    fn install(&mut self, child: Child, port: u16) {
+        let child_pid = child.id();
+        self.child = Some(child);
+        self.port = port;
+        self.started_at = Some(Instant::now());
+        instance::set_port(port);
+        write_pid_file(child_pid);
+
+        tracing::info!("Backend 服务已启动, pid: {}", child_pid);
+        events::record(
+            EventKind::Backend,
+            "Backend 已启动",
+            serde_json::json!({ "pid": child_pid, "port": port }),
+        );
+    }
+}
+
+impl Launch {
+    /// 启动 Backend 进程，port 被占用时使用端口范围内的其他空闲端口，返回进程和实际使用的端口
+    fn spawn(&self, port: u16) -> AppResult<(Child, u16)> {
+        let port = find_free_port(port, self.port_range.clone()).ok_or_else(|| {
+            AppError::BackendStartFailed(format!(
+                "端口 {} 和 {}-{} 都已被占用",
+                port,
+                self.port_range.start(),
+                self.port_range.end()
+            ))
+        })?;
+        tracing::info!("启动 Backend 服务, 地址: {}:{}", self.host, port);
+
+        let resolve_started = Instant::now();
+        let resource_path = resolve_executable();
+        startup::record("backend_resolve", resolve_started, resource_path.is_ok());
+        let resource_path = resource_path.map_err(AppError::BackendStartFailed)?;
+
+        tracing::info!("Backend 路径: {:?}", resource_path);
+        prepare_executable(&resource_path);
+
+        // 启动 backend 进程
+        let spawn_started = Instant::now();
+        let mut command = Command::new(&resource_path);
+        command.args([
+            "--host", &self.host,
+            "--port", &port.to_string(),
+        ]);
+        if let Some(dir) = DATA_DIR.lock().unwrap().clone() {
+            std::fs::create_dir_all(&dir).map_err(|e| {
+                AppError::BackendStartFailed(format!("创建数据目录失败: {:?} ({})", dir, e))
+            })?;
+            tracing::info!("Backend 数据目录: {:?}", dir);
+            command.env(DATA_DIR_ENV, dir);
+        }
+        command.env(TOKEN_ENV, token());
+        if let Some(level) = logging::backend_level() {
+            command.env("SMARTMART_LOG_LEVEL", level);
+        }
+        system_proxy::apply_env(&mut command);
+        // 输出写入 Backend 日志文件（不缓冲，崩溃前的输出也能保留）
+        command
+            .env("PYTHONUNBUFFERED", "1")
+            .env("PYTHONIOENCODING", "utf-8")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // 使用单独的进程组：终端的 Ctrl+C 不会直接结束 Backend，停止时由壳程序只向 Backend
+        // 发送 SIGTERM / CTRL_BREAK。Windows 下同时不弹出控制台窗口
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+            command.creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
+        }
+
+        let child = command.spawn();
+        startup::record("backend_spawn", spawn_started, child.is_ok());
+        let mut child =
+            child.map_err(|e| AppError::BackendStartFailed(format!("启动 Backend 失败: {}", e)))?;
+        startup::mark("backend_spawned");
+
+        if let Some(stdout) = child.stdout.take() {
+            backend_logs::capture("stdout", stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            backend_logs::capture("stderr", stderr);
+        }
+
+        performance::apply_backend_priority(child.id());
+        Ok((child, port))
+    }
+}
+
+impl PendingRestart {
+    /// 只停止旧进程（不持有进程锁），例如恢复数据时需要先替换数据再调用 run 启动新进程
     pub fn stop(&mut self) {
-        if let Some(mut child) = self.child.take() {
-            println!("🛑 停止 Backend 服务...");
-            let _ = child.kill();
-            let _ = child.wait();
-            println!("✅ Backend 服务已停止");
+        if let Some(child) = self.child.take() {
+            stop_child(child, self.shutdown_timeout.map(|t| (self.old_port, t)));
+            remove_pid_file();
+        }
+    }
+
+    /// 停止旧进程并启动新进程（不持有进程锁）。重启期间 Backend 被停止或由其他地方重新启动时，
+    /// 结束新启动的进程
+    pub fn run(mut self, state: &Mutex<BackendProcess>) -> AppResult<()> {
+        self.stop();
+        let result = self.launch.spawn(self.port);
+
+        let mut process = state.lock().unwrap();
+        process.restarting = false;
+        let (child, port) = result?;
+        if !process.supervised || process.child.is_some() {
+            drop(process);
+            tracing::warn!("重启期间 Backend 已被停止或重新启动，结束新启动的进程");
+            stop_child(child, None);
+            return Ok(());
         }
+        process.install(child, port);
+        Ok(())
     }
 }
 
+/// 停止取出的 Backend 进程。graceful 为 (端口, 等待时长) 时先请求 Backend 正常退出，
+/// 失败或超时后发送 SIGTERM / CTRL_BREAK，STOP_TIMEOUT 内未退出再强制结束
+fn stop_child(mut child: Child, graceful: Option<(u16, Duration)>) {
+    if let Some((port, timeout)) = graceful {
+        match request_shutdown(port) {
+            Err(e) => tracing::warn!("{}", e),
+            Ok(()) if wait_for_exit(&mut child, timeout) => {
+                tracing::info!("Backend 服务已正常退出");
+                events::record(
+                    EventKind::Backend,
+                    "Backend 已停止",
+                    serde_json::Value::Null,
+                );
+                return;
+            }
+            Ok(()) => tracing::warn!("Backend 未在 {:?} 内退出", timeout),
+        }
+    }
+
+    tracing::info!("停止 Backend 服务...");
+    if !terminate(&mut child, STOP_TIMEOUT) {
+        if let Err(e) = child.kill() {
+            tracing::warn!("结束 Backend 进程失败: {}", e);
+        }
+    }
+    let _ = child.wait();
+    tracing::info!("Backend 服务已停止");
+    events::record(
+        EventKind::Backend,
+        "Backend 已停止",
+        serde_json::Value::Null,
+    );
+}
+
+/// 等待进程退出，在 timeout 内退出时返回 true
+fn wait_for_exit(child: &mut Child, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        if !matches!(child.try_wait(), Ok(None)) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+/// 请求进程退出并等待（Unix 下发送 SIGTERM），进程在 timeout 内退出时返回 true
+#[cfg(unix)]
+fn terminate(child: &mut Child, timeout: Duration) -> bool {
+    let pid = sysinfo::Pid::from_u32(child.id());
+    let mut system = sysinfo::System::new();
+    system.refresh_process(pid);
+    let signalled = system
+        .process(pid)
+        .and_then(|process| process.kill_with(sysinfo::Signal::Term))
+        .unwrap_or(false);
+    if !signalled {
+        return false;
+    }
+    let exited = wait_for_exit(child, timeout);
+    if !exited {
+        tracing::warn!("Backend 未在 {:?} 内响应 SIGTERM", timeout);
+    }
+    exited
+}
+
+/// 请求进程退出并等待（Windows 下发送 CTRL_BREAK，uvicorn 按 SIGBREAK 正常退出），
+/// 进程在 timeout 内退出时返回 true
+#[cfg(windows)]
+fn terminate(child: &mut Child, timeout: Duration) -> bool {
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler,
+        CTRL_BREAK_EVENT,
+    };
+
+    let pid = child.id();
+    // 壳程序没有控制台：临时连接到 Backend 的隐藏控制台发送事件，期间忽略自身收到的事件。
+    // 壳程序已有控制台（开发模式）时无法连接，直接强制结束
+    let sent = unsafe {
+        if AttachConsole(pid) == 0 {
+            false
+        } else {
+            SetConsoleCtrlHandler(None, 1);
+            let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0;
+            FreeConsole();
+            SetConsoleCtrlHandler(None, 0);
+            sent
+        }
+    };
+    if !sent {
+        return false;
+    }
+    let exited = wait_for_exit(child, timeout);
+    if !exited {
+        tracing::warn!("Backend 未在 {:?} 内响应 CTRL_BREAK", timeout);
+    }
+    exited
+}
+
+#[cfg(not(any(unix, windows)))]
+fn terminate(_child: &mut Child, _timeout: Duration) -> bool {
+    false
+}
+
+fn pid_file() -> PathBuf {
+    instance::shared_dir().join(PID_FILE)
+}
+
+fn write_pid_file(pid: u32) {
+    let path = pid_file();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = std::fs::write(&path, pid.to_string()) {
+        tracing::warn!("保存 Backend pid 失败: {:?} ({})", path, e);
+    }
+}
+
+fn remove_pid_file() {
+    let _ = std::fs::remove_file(pid_file());
+}
+
+/// 结束上次遗留的 Backend 进程（壳程序崩溃或被强制结束时 Backend 仍在运行，占用端口和数据库）。
+/// 只在持有租约时启动 Backend，因此 pid 文件中仍在运行的 Backend 一定是遗留的
+#[cfg_attr(debug_assertions, allow(dead_code))]
+fn kill_orphan() {
+    let Some(pid) = std::fs::read_to_string(pid_file())
+        .ok()
+        .and_then(|content| content.trim().parse::<u32>().ok())
+    else {
+        return;
+    };
+    remove_pid_file();
+
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    system.refresh_process_specifics(
+        pid,
+        sysinfo::ProcessRefreshKind::new().with_exe(sysinfo::UpdateKind::OnlyIfNotSet),
+    );
+    // pid 可能已被其他程序复用，按可执行文件名确认（Linux 下进程名会被截断为 15 个字符）
+    let Some(process) = system.process(pid).filter(|p| {
+        p.exe()
+            .and_then(|exe| exe.file_name())
+            .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(EXECUTABLE))
+    }) else {
+        return;
+    };
+
+    tracing::warn!("发现遗留的 Backend 进程（pid {}），结束该进程", pid);
+    events::record(
+        EventKind::Backend,
+        "结束遗留的 Backend 进程",
+        serde_json::json!({ "pid": pid.as_u32() }),
+    );
+    if !process.kill() {
+        tracing::warn!("结束遗留的 Backend 进程失败");
+        return;
+    }
+    // 等待进程退出、释放端口
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(5) {
+        if !system.refresh_process_specifics(pid, sysinfo::ProcessRefreshKind::new()) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// 端口是否空闲：没有程序在监听，且 Backend 能够绑定
+fn is_port_free(port: u16) -> bool {
+    let listening = TcpStream::connect_timeout(
+        &(Ipv4Addr::LOCALHOST, port).into(),
+        Duration::from_millis(200),
+    )
+    .is_ok();
+    !listening
+        && TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).is_ok()
+        && TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+/// 优先使用 preferred，被占用时依次尝试 range 中的端口
+fn find_free_port(preferred: u16, range: RangeInclusive<u16>) -> Option<u16> {
+    let port = std::iter::once(preferred)
+        .chain(range.filter(|&p| p != preferred))
+        .find(|&p| is_port_free(p))?;
+    if port != preferred {
+        tracing::warn!("端口 {} 已被占用，改用端口 {}", preferred, port);
+    }
+    Some(port)
+}
+
+/// 记录应用的资源目录（在启动 Backend 之前调用）
+pub fn set_resource_dir(dir: Option<PathBuf>) {
+    if let Some(dir) = dir {
+        let _ = RESOURCE_DIR.set(dir);
+    }
+}
+
+/// 查找 Backend 可执行文件
+fn resolve_executable() -> Result<PathBuf, String> {
+    if let Some(path) = std::env::var_os(PATH_ENV).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(format!("{} 指定的 Backend 不存在: {:?}", PATH_ENV, path))
+        };
+    }
+
+    let exe_dir = std::env::current_exe()
+        .map_err(|e| format!("获取程序路径失败: {}", e))?
+        .parent()
+        .ok_or("无法获取父目录")?
+        .to_path_buf();
+
+    // 尝试多个可能的路径：
+    // 1. 同级目录（开发/便携模式，以及 Tauri sidecar 的位置）
+    // 2. Tauri 资源目录（打包后的位置）
+    // 3. resources 子目录
+    let mut possible_paths = vec![exe_dir.join(EXECUTABLE)];
+    if let Some(dir) = RESOURCE_DIR.get() {
+        possible_paths.push(dir.join(EXECUTABLE));
+    }
+    possible_paths.push(exe_dir.join("resources").join(EXECUTABLE));
+    possible_paths.dedup();
+
+    possible_paths
+        .iter()
+        .find(|p| p.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            let tried: Vec<String> = possible_paths
+                .iter()
+                .map(|p| format!("  - {:?}", p))
+                .collect();
+            format!(
+                "Backend 可执行文件不存在，已尝试路径:\n{}",
+                tried.join("\n")
+            )
+        })
+}
+
+/// 启动前按平台处理可执行文件：Unix 下补上执行权限（解压或复制后可能丢失），
+/// macOS 下移除下载隔离标记（否则 Gatekeeper 会阻止运行）
+fn prepare_executable(path: &std::path::Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            let mut permissions = metadata.permissions();
+            if permissions.mode() & 0o111 == 0 {
+                permissions.set_mode(permissions.mode() | 0o755);
+                if let Err(e) = std::fs::set_permissions(path, permissions) {
+                    tracing::warn!("设置 Backend 执行权限失败: {}", e);
+                }
+            }
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = crate::os::hidden_command("xattr")
+            .args(["-d", "com.apple.quarantine"])
+            .arg(path)
+            .output();
+    }
+    #[cfg(windows)]
+    let _ = path;
+}
+
+/// 监护线程最近一次检查得到的状态（尚未检查时为 None）
+pub fn last_status() -> Option<BackendStatus> {
+    STATUS.lock().unwrap().clone()
+}
+
+/// 监护线程记录的最近一次启动失败或重启原因
+pub fn last_error() -> Option<String> {
+    STATUS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|status| status.last_error.clone())
+}
+
+/// Backend 的访问 Token
+pub fn token() -> &'static str {
+    TOKEN.get_or_init(|| {
+        format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        )
+    })
+}
+
+/// 连接其他会话中运行的 Backend 时使用其保存的 Token（需在首次调用 token 之前）
+pub fn use_token(token: String) {
+    if TOKEN.set(token).is_err() {
+        tracing::warn!("Backend 访问 Token 已生成，无法替换");
+    }
+}
+
+/// Backend 数据库所在目录（配置的数据目录；未配置时 Backend 以自身所在目录为工作目录，
+/// 数据库为其中的 smartmart.db）
+pub fn database_dir() -> Option<PathBuf> {
+    if let Some(dir) = DATA_DIR.lock().unwrap().clone() {
+        return Some(dir);
+    }
+    resolve_executable()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+}
+
+/// 读取 Backend 版本（GET /），Backend 未就绪时返回 None
+pub fn fetch_version(port: u16) -> Option<String> {
+    let body: serde_json::Value = http::local()
+        .get(&format!("http://127.0.0.1:{}/", port))
+        .call()
+        .ok()?
+        .into_json()
+        .ok()?;
+    body.get("version")?.as_str().map(str::to_string)
+}
+
+/// 读取 Backend 启动各阶段的耗时（毫秒），例如数据库迁移
+pub fn fetch_startup_timings(port: u16) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let body: serde_json::Value = http::local()
+        .get(&format!("http://127.0.0.1:{}/admin/startup", port))
+        .call()
+        .ok()?
+        .into_json()
+        .ok()?;
+    body.as_object().cloned()
+}
+
+/// 请求健康检查接口，返回耗时
+pub fn check_health(port: u16) -> Result<Duration, String> {
+    let started = Instant::now();
+    http::local()
+        .get(&format!("http://127.0.0.1:{}/health", port))
+        .call()
+        .map_err(|e| format!("健康检查失败: {}", e))?;
+    Ok(started.elapsed())
+}
+
+/// 运行时调整 Backend 的日志级别（logger 为空时调整根日志器）
+pub fn set_log_level(port: u16, logger: Option<&str>, level: &str) -> AppResult<()> {
+    http::local()
+        .put(&format!("http://127.0.0.1:{}/admin/log-level", port))
+        .send_json(serde_json::json!({ "level": level, "logger": logger }))
+        .map_err(|e| AppError::BackendUnavailable(format!("调整 Backend 日志级别失败: {}", e)))?;
+    Ok(())
+}
+
+/// 请求 Backend 正常退出
+fn request_shutdown(port: u16) -> Result<(), String> {
+    http::local()
+        .post(&format!("http://127.0.0.1:{}/admin/shutdown", port))
+        .call()
+        .map_err(|e| format!("请求 Backend 退出失败: {}", e))?;
+    Ok(())
+}
+
 impl Drop for BackendProcess {
     fn drop(&mut self) {
-        self.stop();
+        self.shutdown(STOP_TIMEOUT);
+    }
+}
+
+/// 更新 Backend 状态，状态或端口变化时发出事件
+fn set_status(app_handle: &tauri::AppHandle, status: BackendStatus) {
+    let (changed, port_changed) = {
+        let mut current = STATUS.lock().unwrap();
+        let changed = current
+            .as_ref()
+            .map_or(true, |c| c.state != status.state || c.pid != status.pid);
+        let port_changed = current.as_ref().map_or(true, |c| c.port != status.port);
+        *current = Some(status.clone());
+        (changed, port_changed)
+    };
+    if port_changed {
+        tracing::info!("Backend 端口: {}", status.port);
+        let _ = app_handle.emit_all("backend://port", status.port);
+    }
+    if changed {
+        tracing::info!("Backend 状态: {:?}", status.state);
+        let _ = app_handle.emit_all("backend-status-changed", &status);
+    }
+}
+
+/// 监护线程维护的状态
+struct Supervisor {
+    restarts: u32,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+    unresponsive_since: Option<Instant>,
+    last_error: Option<String>,
+}
+
+/// 一轮检查的结果，需要重启时在释放进程锁后进行
+enum Check {
+    Done(BackendState),
+    Restart(PendingRestart, String),
+}
+
+impl Supervisor {
+    /// 重启 Backend（不持有进程锁），失败时延长下次重试的等待时间
+    fn restart(
+        &mut self,
+        state: &Mutex<BackendProcess>,
+        pending: PendingRestart,
+        reason: &str,
+    ) -> BackendState {
+        tracing::warn!("{}，重启 Backend（第 {} 次）", reason, self.restarts + 1);
+        events::record(
+            EventKind::Backend,
+            "自动重启 Backend",
+            serde_json::json!({ "reason": reason, "restarts": self.restarts + 1 }),
+        );
+        self.restarts += 1;
+        self.unresponsive_since = None;
+        metrics::increment(metrics::BACKEND_RESTARTS);
+        let result = pending.run(state);
+        // 重启后很快又崩溃时，下次等待更久
+        let delay = self.backoff;
+        self.backoff = (self.backoff * 2).min(BACKOFF_MAX);
+        match result {
+            Ok(()) => {
+                self.retry_at = None;
+                BackendState::Starting
+            }
+            Err(e) => {
+                tracing::error!("重启 Backend 失败: {}", e);
+                self.last_error = Some(e.to_string());
+                self.retry_at = Some(Instant::now() + delay);
+                BackendState::Failed
+            }
+        }
+    }
+
+    /// 需要重启时取出进程，由调用方在释放进程锁后重启
+    fn begin_restart(&mut self, process: &mut BackendProcess, reason: String) -> Check {
+        match process.begin_restart(process.port, None) {
+            Some(pending) => Check::Restart(pending, reason),
+            None => Check::Done(BackendState::Restarting),
+        }
+    }
+
+    /// probe 为看门狗最近一次健康检查的时间和结果，进程启动前的结果不算
+    fn check(
+        &mut self,
+        process: &mut BackendProcess,
+        probe: Option<(Instant, Result<Duration, String>)>,
+    ) -> Check {
+        let health = match probe {
+            Some((at, result)) if process.started_at.map_or(true, |started| at >= started) => {
+                result
+            }
+            _ => Err("尚未完成健康检查".to_string()),
+        };
+        if !process.supervised {
+            self.retry_at = None;
+            self.unresponsive_since = None;
+            return Check::Done(if health.is_ok() {
+                BackendState::Running
+            } else {
+                BackendState::Stopped
+            });
+        }
+        if process.restarting {
+            return Check::Done(BackendState::Restarting);
+        }
+
+        if process.is_running() {
+            let uptime = process.uptime().unwrap_or_default();
+            if uptime >= BACKOFF_RESET {
+                self.backoff = BACKOFF_INITIAL;
+            }
+            return match health {
+                Ok(_) => {
+                    self.unresponsive_since = None;
+                    Check::Done(BackendState::Running)
+                }
+                Err(_) if uptime < STARTUP_GRACE => Check::Done(BackendState::Starting),
+                Err(e) => {
+                    let since = *self.unresponsive_since.get_or_insert_with(Instant::now);
+                    self.last_error = Some(e);
+                    if since.elapsed() >= UNRESPONSIVE_RESTART {
+                        let reason = format!("Backend 已 {} 秒无响应", since.elapsed().as_secs());
+                        self.begin_restart(process, reason)
+                    } else {
+                        Check::Done(BackendState::Unresponsive)
+                    }
+                }
+            };
+        }
+
+        // 进程已退出或启动失败：等待退避时间后重启
+        let exited = process.child.as_mut().and_then(|child| child.try_wait().ok().flatten());
+        let retry_at = match self.retry_at {
+            Some(at) => at,
+            None => {
+                let reason = match exited {
+                    Some(status) => format!("Backend 进程已退出（{}）", status),
+                    None => "Backend 未能启动".to_string(),
+                };
+                tracing::error!("{}", reason);
+                events::record(EventKind::Error, &reason, serde_json::Value::Null);
+                self.last_error = Some(reason);
+                let at = Instant::now() + self.backoff;
+                self.retry_at = Some(at);
+                at
+            }
+        };
+        if Instant::now() >= retry_at {
+            let reason = self.last_error.clone().unwrap_or_default();
+            self.begin_restart(process, reason)
+        } else if process.child.is_some() {
+            Check::Done(BackendState::Restarting)
+        } else {
+            Check::Done(BackendState::Failed)
+        }
+    }
+}
+
+/// 启动监护线程
+pub fn supervise(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("backend-supervisor".into())
+        .spawn(move || {
+            let mut supervisor = Supervisor {
+                restarts: 0,
+                backoff: BACKOFF_INITIAL,
+                retry_at: None,
+                unresponsive_since: None,
+                last_error: None,
+            };
+            loop {
+                let state = app_handle.state::<Mutex<BackendProcess>>();
+                let probe = app_handle
+                    .state::<Mutex<LatencyTracker>>()
+                    .lock()
+                    .unwrap()
+                    .last_probe();
+                let check = supervisor.check(&mut state.lock().unwrap(), probe);
+                let backend_state = match check {
+                    Check::Done(backend_state) => backend_state,
+                    Check::Restart(pending, reason) => supervisor.restart(&state, pending, &reason),
+                };
+                let status = {
+                    let process = state.lock().unwrap();
+                    BackendStatus {
+                        state: backend_state,
+                        port: process.port,
+                        pid: process.pid(),
+                        supervised: process.supervised,
+                        restarts: supervisor.restarts,
+                        retry_in_secs: supervisor
+                            .retry_at
+                            .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+                        last_error: supervisor.last_error.clone(),
+                    }
+                };
+                set_status(&app_handle, status);
+                std::thread::sleep(SUPERVISE_INTERVAL);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::error!("启动 Backend 监护线程失败: {}", e);
     }
 }
 
 // Tauri 命令
 
+/// Backend 的运行状态（监护线程尚未检查时立即检查一次）
 #[tauri::command]
-pub fn get_backend_status() -> Result<String, String> {
-    Ok("running".to_string())
+pub async fn get_backend_status(app_handle: tauri::AppHandle) -> AppResult<BackendStatus> {
+    if let Some(status) = STATUS.lock().unwrap().clone() {
+        return Ok(status);
+    }
+    let state = app_handle.state::<Mutex<BackendProcess>>();
+    let port = state.lock().unwrap().port;
+    let healthy = check_health(port).is_ok();
+    let mut process = state.lock().unwrap();
+    let running = process.is_running();
+    Ok(BackendStatus {
+        state: match (healthy, running) {
+            (true, _) => BackendState::Running,
+            (false, true) => BackendState::Starting,
+            (false, false) if process.supervised => BackendState::Failed,
+            (false, false) => BackendState::Stopped,
+        },
+        port: process.port,
+        pid: process.pid(),
+        supervised: process.supervised,
+        restarts: 0,
+        retry_in_secs: None,
+        last_error: None,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendCredentials {
+    pub port: u16,
+    /// 请求时放在 Authorization: Bearer 头（WebSocket 和图片地址使用 access_token 查询参数）
+    pub token: String,
+    pub lan_mode: bool,
+}
+
+/// 访问 Backend 所需的端口和 Token
+#[tauri::command]
+pub fn get_backend_credentials(app_handle: tauri::AppHandle) -> BackendCredentials {
+    let process = app_handle.state::<Mutex<BackendProcess>>();
+    let process = process.lock().unwrap();
+    BackendCredentials {
+        port: process.port,
+        token: token().to_string(),
+        lan_mode: process.lan_mode,
+    }
+}
+
+/// Backend 实际监听的端口（前端据此拼接接口地址）
+#[tauri::command]
+pub fn get_backend_port(app_handle: tauri::AppHandle) -> u16 {
+    app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port
 }
 
+/// 重启 Backend（同时使用配置中最新的 Backend 设置）
 #[tauri::command]
-pub async fn restart_backend(port: u16, app_handle: tauri::AppHandle) -> Result<(), String> {
+pub async fn restart_backend(port: u16, app_handle: tauri::AppHandle) -> AppResult<()> {
+    let settings = app_handle.state::<ConfigStore>().get().backend;
     let backend_state = app_handle.state::<Mutex<BackendProcess>>();
-    let mut backend = backend_state.lock().unwrap();
-    
-    backend.stop();
-    std::thread::sleep(std::time::Duration::from_secs(1));
-    backend.start(port)?;
-    
+    let pending = {
+        let mut backend = backend_state.lock().unwrap();
+        backend.configure(&settings);
+        backend.begin_restart(port, Some(STOP_TIMEOUT))
+    };
+    let pending =
+        pending.ok_or_else(|| AppError::BackendUnavailable("Backend 正在重启".to_string()))?;
+
+    metrics::increment(metrics::BACKEND_RESTARTS);
+    pending.run(&backend_state).reported("restart_backend")?;
+
     Ok(())
 }
 
@@ -0,0 +1,191 @@
+// Backend 输出日志
+//
+// 打包后的 Backend 没有控制台，启动失败时看不到原因。壳程序接管 Backend 的 stdout / stderr，
+// 逐行写入 <日志目录>/backend 下按天轮转的 backend.YYYY-MM-DD.log（保留最近 14 天），
+// 格式与壳程序日志一致，日志查看器可以直接查询。
+//
+// 前端的实时日志查看器调用 tail_backend_logs 开启后，每行输出同时通过 backend://log 事件推送。
+//
+// 请求地址中的 access_token（访问 Token、设备 Token）在写入前隐藏。
+
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::Manager;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+use crate::logging;
+
+/// 保留的日志文件数量（按天轮转）
+const MAX_LOG_FILES: usize = 14;
+
+/// get_backend_logs 默认和最多返回的行数
+const DEFAULT_LINES: usize = 200;
+const MAX_LINES: usize = 5000;
+
+static WRITER: Mutex<Option<RollingFileAppender>> = Mutex::new(None);
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// 是否通过事件推送输出
+static TAILING: AtomicBool = AtomicBool::new(false);
+
+/// 打开日志文件（首次写入时创建）
+fn with_writer(write: impl FnOnce(&mut RollingFileAppender)) {
+    let mut writer = WRITER.lock().unwrap();
+    if writer.is_none() {
+        let Some(dir) = logging::backend_log_dir() else {
+            return;
+        };
+        let _ = std::fs::create_dir_all(&dir);
+        match RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("backend")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(&dir)
+        {
+            Ok(appender) => *writer = Some(appender),
+            Err(e) => {
+                tracing::warn!("无法创建 Backend 日志文件: {:?} ({})", dir, e);
+                return;
+            }
+        }
+    }
+    if let Some(writer) = writer.as_mut() {
+        write(writer);
+    }
+}
+
+/// 从输出行的开头识别级别（uvicorn 为 "INFO:"，Python logging 常见为 "ERROR ..."）
+fn level_of(line: &str) -> &'static str {
+    let word = line
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .find(|w| !w.is_empty())
+        .unwrap_or_default();
+    match word.to_ascii_uppercase().as_str() {
+        "TRACE" => "TRACE",
+        "DEBUG" => "DEBUG",
+        "WARN" | "WARNING" => "WARN",
+        "ERROR" | "CRITICAL" | "FATAL" | "TRACEBACK" => "ERROR",
+        _ => "INFO",
+    }
+}
+
+/// 隐藏文本中 access_token 查询参数的值
+pub fn redact_access_token(text: &str) -> Cow<'_, str> {
+    const PARAM: &str = "access_token=";
+    if !text.contains(PARAM) {
+        return Cow::Borrowed(text);
+    }
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(PARAM) {
+        let value = start + PARAM.len();
+        redacted.push_str(&rest[..value]);
+        redacted.push_str("<redacted>");
+        let end = rest[value..]
+            .find(|c: char| matches!(c, '&' | '#' | '"' | '\'') || c.is_whitespace())
+            .map_or(rest.len(), |i| value + i);
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    Cow::Owned(redacted)
+}
+
+/// 把一行输出写入日志文件并按需推送
+fn write_line(stream: &str, line: &str) {
+    let line = line.trim_end_matches(['\r', '\n']);
+    if line.is_empty() {
+        return;
+    }
+    let line = redact_access_token(line);
+    // 缩进的行（调用栈）作为上一条的续行，不加前缀
+    let formatted = if line.starts_with(char::is_whitespace) {
+        line.to_string()
+    } else {
+        format!(
+            "{} {} backend::{}: {}",
+            chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            level_of(&line),
+            stream,
+            line
+        )
+    };
+    with_writer(|writer| {
+        let _ = writeln!(writer, "{}", formatted);
+    });
+    if TAILING.load(Ordering::Relaxed) {
+        if let Some(app_handle) = APP_HANDLE.get() {
+            let _ = app_handle.emit_all("backend://log", &formatted);
+        }
+    }
+}
+
+/// 在后台线程中逐行读取 Backend 的输出（进程退出、管道关闭后线程结束）
+pub fn capture(stream: &'static str, pipe: impl Read + Send + 'static) {
+    let spawned = std::thread::Builder::new()
+        .name(format!("backend-{}", stream))
+        .spawn(move || {
+            let mut reader = BufReader::new(pipe);
+            let mut buffer = Vec::new();
+            loop {
+                buffer.clear();
+                match reader.read_until(b'\n', &mut buffer) {
+                    Ok(0) => break,
+                    Ok(_) => write_line(stream, &String::from_utf8_lossy(&buffer)),
+                    Err(e) => {
+                        tracing::warn!("读取 Backend {} 失败: {}", stream, e);
+                        break;
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::error!("启动 Backend 日志线程失败: {}", e);
+    }
+}
+
+pub fn start(app_handle: &tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle.clone());
+}
+
+/// 最新 Backend 日志文件的最后 `lines` 行
+fn recent_lines(lines: usize) -> Vec<String> {
+    let Some(dir) = logging::backend_log_dir() else {
+        return Vec::new();
+    };
+    let latest = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("backend.") && name.ends_with(".log")
+        })
+        .max_by_key(|entry| entry.file_name());
+
+    let Some(content) = latest.and_then(|entry| std::fs::read(entry.path()).ok()) else {
+        return Vec::new();
+    };
+    let content = String::from_utf8_lossy(&content);
+    let all: Vec<&str> = content.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    all[start..].iter().map(|line| line.to_string()).collect()
+}
+
+// Tauri 命令
+
+/// 读取 Backend 最近的输出（默认 200 行）
+#[tauri::command]
+pub async fn get_backend_logs(lines: Option<usize>) -> Vec<String> {
+    recent_lines(lines.unwrap_or(DEFAULT_LINES).clamp(1, MAX_LINES))
+}
+
+/// 开启或关闭 backend://log 事件推送（日志查看器打开时开启）
+#[tauri::command]
+pub fn tail_backend_logs(enabled: bool) {
+    TAILING.store(enabled, Ordering::Relaxed);
+}
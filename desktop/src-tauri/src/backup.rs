@@ -0,0 +1,555 @@
+// 数据备份与恢复
+//
+// 备份为一个 zip 压缩包，内容为 Backend 数据目录中的业务数据：
+// - smartmart.db      数据库（Backend 运行时通过 POST /admin/backup 生成一致的快照，收银不受影响）
+// - uploads/          上传的文件
+// - static/images/    商品图片
+// - manifest.json     格式版本、应用和 Backend 版本、每个文件的大小和 SHA-256
+//
+// 恢复前先完整校验压缩包（清单、文件大小和 SHA-256、数据库文件头），校验通过后自动备份当前数据
+// （pre-restore-*.zip），然后停止 Backend、替换数据并重新启动。
+//
+// 手动备份和恢复的路径必须是用户在对话框中选择的（保存位置、文件夹或备份文件），
+// 备份时不会覆盖不是 SmartMart 备份的已有文件。
+//
+// 定时任务 backup 默认不执行，在定时任务设置中填写 cron 表达式后按时备份到 backup.dir，
+// 只保留最近 backup.keep 份。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::backend::{self, BackendProcess};
+use crate::config::ConfigStore;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::reporting::ReportErr;
+use crate::{crash, day_close, dialog, http, scheduler, shutdown};
+
+/// 备份格式版本（格式不兼容时递增）
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = "smartmart.db";
+
+/// 随数据库一起备份的目录（相对于数据目录）
+const DATA_DIRS: [&str; 2] = ["uploads", "static/images"];
+
+/// 定时备份和恢复前自动备份的文件名前缀
+const SCHEDULED_PREFIX: &str = "scheduled-";
+const PRE_RESTORE_PREFIX: &str = "pre-restore-";
+
+/// 恢复前自动备份保留的份数
+const KEEP_PRE_RESTORE: usize = 3;
+
+/// SQLite 数据库文件头
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// 备份大数据库可能需要较长时间
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 恢复时等待 Backend 退出的时长
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 同一时间只执行一个备份或恢复
+static RUNNING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub created_at: String,
+    pub app_version: String,
+    pub backend_version: Option<String>,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size: u64,
+    pub created_at: String,
+    pub files: usize,
+}
+
+fn backend_port(app_handle: &tauri::AppHandle) -> u16 {
+    app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port()
+}
+
+/// Backend 数据目录
+fn data_root() -> Result<PathBuf, String> {
+    backend::database_dir().ok_or_else(|| "找不到 Backend 数据目录".to_string())
+}
+
+/// 定时备份的保存目录
+fn scheduled_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    app_handle
+        .state::<ConfigStore>()
+        .get()
+        .backup
+        .dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| day_close::backups_dir(app_handle))
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S").to_string()
+}
+
+/// 生成数据库快照：Backend 在运行时在线备份，未运行时直接复制数据库文件
+fn snapshot_database(port: u16, root: &Path, target: &Path) -> Result<(), String> {
+    if backend::check_health(port).is_ok() {
+        http::local()
+            .post(&format!("http://127.0.0.1:{}/admin/backup", port))
+            .timeout(SNAPSHOT_TIMEOUT)
+            .send_json(serde_json::json!({ "path": target }))
+            .map_err(|e| format!("备份数据库失败: {}", day_close::error_detail(e)))?;
+        return Ok(());
+    }
+    let database = root.join(DATABASE);
+    if !database.is_file() {
+        return Err(format!("数据库文件不存在: {:?}", database));
+    }
+    tracing::info!("Backend 未运行，直接复制数据库文件");
+    std::fs::copy(&database, target)
+        .map(|_| ())
+        .map_err(|e| format!("复制数据库失败: {}", e))
+}
+
+/// 把文件写入压缩包，同时计算 SHA-256
+fn add_file(
+    zip: &mut ZipWriter<File>,
+    path: &Path,
+    name: &str,
+    manifest: &mut Vec<ManifestEntry>,
+) -> Result<(), String> {
+    let mut file = File::open(path).map_err(|e| format!("读取 {:?} 失败: {}", path, e))?;
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    zip.start_file(name, options)
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取 {:?} 失败: {}", path, e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        zip.write_all(&buffer[..read])
+            .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+        size += read as u64;
+    }
+    manifest.push(ManifestEntry {
+        name: name.to_string(),
+        size,
+        sha256: hex(&hasher.finalize()),
+    });
+    Ok(())
+}
+
+/// 递归添加目录
+fn add_dir(
+    zip: &mut ZipWriter<File>,
+    dir: &Path,
+    prefix: &str,
+    manifest: &mut Vec<ManifestEntry>,
+) -> Result<(), String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            add_dir(zip, &path, &name, manifest)?;
+        } else {
+            add_file(zip, &path, &name, manifest)?;
+        }
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 备份文件路径：dest 为已存在的目录时在其中按时间命名
+fn destination(dest: &Path, prefix: &str) -> Result<PathBuf, String> {
+    if !dest.is_absolute() {
+        return Err("备份路径必须是绝对路径".to_string());
+    }
+    if dest.is_dir() {
+        return Ok(dest.join(format!("{}{}.zip", prefix, timestamp())));
+    }
+    Ok(dest.to_path_buf())
+}
+
+/// 生成备份压缩包（先写临时文件，完成后再改名）
+fn create(app_handle: &tauri::AppHandle, dest: &Path) -> Result<BackupInfo, String> {
+    let _busy = shutdown::busy("backup");
+    let root = data_root()?;
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+
+    let snapshot = dest.with_extension("db.tmp");
+    let _ = std::fs::remove_file(&snapshot);
+    snapshot_database(backend_port(app_handle), &root, &snapshot)?;
+
+    let partial = dest.with_extension("zip.tmp");
+    let result = write_archive(&partial, &snapshot, &root);
+    let _ = std::fs::remove_file(&snapshot);
+    let manifest = match result {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial, dest).map_err(|e| format!("保存备份失败: {}", e))?;
+
+    let size = std::fs::metadata(dest).map(|m| m.len()).unwrap_or_default();
+    tracing::info!("已备份数据: {:?} ({} 字节)", dest, size);
+    Ok(BackupInfo {
+        path: dest.to_string_lossy().into_owned(),
+        size,
+        created_at: manifest.created_at,
+        files: manifest.files.len(),
+    })
+}
+
+fn write_archive(path: &Path, snapshot: &Path, root: &Path) -> Result<Manifest, String> {
+    let file = File::create(path).map_err(|e| format!("创建文件失败: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let mut files = Vec::new();
+    add_file(&mut zip, snapshot, DATABASE, &mut files)?;
+    for dir in DATA_DIRS {
+        add_dir(&mut zip, &root.join(dir), dir, &mut files)?;
+    }
+
+    let manifest = Manifest {
+        format: FORMAT_VERSION,
+        created_at: chrono::Local::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        backend_version: crash::backend_version(),
+        files,
+    };
+    let content =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| format!("序列化清单失败: {}", e))?;
+    zip.start_file(MANIFEST, FileOptions::default())
+        .and_then(|_| zip.write_all(&content).map_err(Into::into))
+        .map_err(|e| format!("写入清单失败: {}", e))?;
+    zip.finish().map_err(|e| format!("写入压缩包失败: {}", e))?;
+    Ok(manifest)
+}
+
+/// 文件是否为 SmartMart 的备份（带清单的压缩包）
+fn is_backup(path: &Path) -> bool {
+    File::open(path)
+        .ok()
+        .and_then(|file| ZipArchive::new(file).ok())
+        .is_some_and(|mut archive| archive.by_name(MANIFEST).is_ok())
+}
+
+/// 校验压缩包并解压到 staging，返回清单
+fn extract_verified(src: &Path, staging: &Path) -> Result<Manifest, String> {
+    let file = File::open(src).map_err(|e| format!("读取备份失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("不是有效的备份文件: {}", e))?;
+
+    let manifest: Manifest = {
+        let entry = archive
+            .by_name(MANIFEST)
+            .map_err(|_| "备份文件缺少清单，可能不是 SmartMart 的备份".to_string())?;
+        serde_json::from_reader(entry).map_err(|e| format!("备份清单损坏: {}", e))?
+    };
+    if manifest.format > FORMAT_VERSION {
+        return Err(format!(
+            "备份由更新版本的 SmartMart（{}）生成，请先升级",
+            manifest.app_version
+        ));
+    }
+    if !manifest.files.iter().any(|f| f.name == DATABASE) {
+        return Err("备份中没有数据库".to_string());
+    }
+
+    for expected in &manifest.files {
+        let mut entry = archive
+            .by_name(&expected.name)
+            .map_err(|_| format!("备份缺少文件: {}", expected.name))?;
+        // 拒绝包含 .. 或绝对路径的文件名
+        let relative = entry
+            .enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("备份中的文件名无效: {}", expected.name))?;
+        let target = staging.join(relative);
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+
+        let mut out = File::create(&target).map_err(|e| format!("解压失败: {}", e))?;
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = entry
+                .read(&mut buffer)
+                .map_err(|e| format!("{} 已损坏: {}", expected.name, e))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            out.write_all(&buffer[..read])
+                .map_err(|e| format!("解压失败: {}", e))?;
+            size += read as u64;
+        }
+        if size != expected.size || hex(&hasher.finalize()) != expected.sha256 {
+            return Err(format!("{} 校验失败，备份文件已损坏", expected.name));
+        }
+    }
+
+    let mut header = [0u8; 16];
+    File::open(staging.join(DATABASE))
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|e| format!("读取数据库失败: {}", e))?;
+    if &header != SQLITE_HEADER {
+        return Err("备份中的数据库文件无效".to_string());
+    }
+    Ok(manifest)
+}
+
+/// 用 staging 中的数据替换数据目录中的数据库和数据目录
+fn replace_data(staging: &Path, root: &Path) -> Result<(), String> {
+    for suffix in ["", "-wal", "-shm", "-journal"] {
+        let path = root.join(format!("{}{}", DATABASE, suffix));
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("删除 {:?} 失败: {}", path, e))?;
+        }
+    }
+    std::fs::rename(staging.join(DATABASE), root.join(DATABASE))
+        .map_err(|e| format!("替换数据库失败: {}", e))?;
+
+    for dir in DATA_DIRS {
+        let target = root.join(dir);
+        if target.exists() {
+            std::fs::remove_dir_all(&target)
+                .map_err(|e| format!("删除 {:?} 失败: {}", target, e))?;
+        }
+        let source = staging.join(dir);
+        if source.exists() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            std::fs::rename(&source, &target).map_err(|e| format!("替换 {} 失败: {}", dir, e))?;
+        } else {
+            std::fs::create_dir_all(&target).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+fn restore(app_handle: &tauri::AppHandle, src: &Path) -> Result<Manifest, String> {
+    let _busy = shutdown::busy("restore_backup");
+    let root = data_root()?;
+
+    // 解压到数据目录下的临时目录（与数据在同一磁盘，替换时只需改名）
+    let staging = root.join(format!(".restore-{}", timestamp()));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging).map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let result = restore_from(app_handle, src, &staging, &root);
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn restore_from(
+    app_handle: &tauri::AppHandle,
+    src: &Path,
+    staging: &Path,
+    root: &Path,
+) -> Result<Manifest, String> {
+    let manifest = extract_verified(src, staging)?;
+
+    let state = app_handle.state::<Mutex<BackendProcess>>();
+    let (port, owned) = {
+        let process = state.lock().unwrap();
+        (process.port(), process.pid().is_some())
+    };
+    if !owned && backend::check_health(port).is_ok() {
+        return Err("Backend 不是由本程序启动的，无法在运行中恢复数据".to_string());
+    }
+
+    // 恢复失败时可以用它找回当前数据
+    let safety_dir = day_close::backups_dir(app_handle);
+    let safety = create(
+        app_handle,
+        &safety_dir.join(format!("{}{}.zip", PRE_RESTORE_PREFIX, timestamp())),
+    )
+    .map_err(|e| format!("备份当前数据失败，已取消恢复: {}", e))?;
+    day_close::prune_backups(&safety_dir, PRE_RESTORE_PREFIX, KEEP_PRE_RESTORE);
+
+    // 只在取出进程时持有进程锁；替换数据期间处于重启状态，监护线程不会用旧数据重新启动 Backend
+    let mut pending = None;
+    if owned {
+        let restart = state
+            .lock()
+            .unwrap()
+            .begin_restart(port, Some(STOP_TIMEOUT));
+        let restart = restart.ok_or_else(|| "Backend 正在重启，请稍后再试".to_string())?;
+        pending.insert(restart).stop();
+    }
+    let replaced = replace_data(staging, root);
+    if let Some(restart) = pending {
+        if let Err(e) = restart.run(&state) {
+            tracing::error!("恢复数据后启动 Backend 失败: {}", e);
+        }
+    }
+    replaced.map_err(|e| format!("{}（恢复前的数据已备份到 {}）", e, safety.path))?;
+    Ok(manifest)
+}
+
+/// 最近一次备份（定时备份目录和默认备份目录中最新的备份压缩包）
+pub fn latest(app_handle: &tauri::AppHandle) -> Option<(PathBuf, SystemTime)> {
+    [
+        scheduled_dir(app_handle),
+        day_close::backups_dir(app_handle),
+    ]
+    .iter()
+    .filter_map(|dir| std::fs::read_dir(dir).ok())
+    .flat_map(|entries| entries.flatten())
+    .filter(|entry| {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        name.ends_with(".zip")
+    })
+    .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?.modified().ok()?)))
+    .max_by_key(|(_, modified)| *modified)
+}
+
+/// 备份到 dir 中按前缀和时间命名的文件，只保留最近 keep 份（定时备份和日结使用）
+pub fn create_in(
+    app_handle: &tauri::AppHandle,
+    dir: &Path,
+    prefix: &str,
+    keep: usize,
+) -> Result<BackupInfo, String> {
+    let _running = RUNNING
+        .try_lock()
+        .map_err(|_| "正在执行其他备份或恢复".to_string())?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    let info = create(app_handle, &destination(dir, prefix)?)?;
+    day_close::prune_backups(dir, prefix, keep);
+    Ok(info)
+}
+
+/// 定时任务：备份到设置的目录
+fn run_task(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let keep = app_handle.state::<ConfigStore>().get().backup.keep;
+    let info = create_in(
+        app_handle,
+        &scheduled_dir(app_handle),
+        SCHEDULED_PREFIX,
+        keep,
+    )?;
+    Ok(format!(
+        "已备份: {} ({:.1} MB)",
+        info.path,
+        info.size as f64 / 1024.0 / 1024.0
+    ))
+}
+
+pub fn register_tasks() {
+    scheduler::register(
+        "backup",
+        "备份数据（数据库、上传文件和商品图片）",
+        None,
+        run_task,
+    );
+}
+
+// Tauri 命令
+
+/// 备份数据到 dest_path（已存在的目录时在其中按时间命名）
+#[tauri::command]
+pub async fn create_backup(
+    dest_path: String,
+    app_handle: tauri::AppHandle,
+) -> AppResult<BackupInfo> {
+    let _running = RUNNING
+        .try_lock()
+        .map_err(|_| AppError::InvalidArgument("正在执行其他备份或恢复".to_string()))?;
+    let requested = Path::new(&dest_path);
+    let chosen = dialog::is_save_selected(requested)
+        || (requested.is_dir() && dialog::is_selected(requested));
+    if !chosen {
+        return Err(AppError::InvalidArgument(
+            "只能备份到通过对话框选择的位置".to_string(),
+        ));
+    }
+    let dest = destination(requested, "smartmart-backup-").map_err(AppError::InvalidArgument)?;
+    if dest.exists() && !is_backup(&dest) {
+        return Err(AppError::InvalidArgument(format!(
+            "{} 不是 SmartMart 的备份文件，不会覆盖",
+            dest.display()
+        )));
+    }
+    let info = create(&app_handle, &dest)
+        .map_err(AppError::Io)
+        .reported("create_backup")?;
+    events::record(EventKind::State, "备份数据", serde_json::json!(info));
+    Ok(info)
+}
+
+/// 从备份恢复数据：校验通过后备份当前数据，停止 Backend、替换数据并重新启动
+#[tauri::command]
+pub async fn restore_backup(src_path: String, app_handle: tauri::AppHandle) -> AppResult<Manifest> {
+    let _running = RUNNING
+        .try_lock()
+        .map_err(|_| AppError::InvalidArgument("正在执行其他备份或恢复".to_string()))?;
+    let src = PathBuf::from(&src_path);
+    if !dialog::is_selected(&src) {
+        return Err(AppError::InvalidArgument(
+            "只能从通过对话框选择的备份文件恢复".to_string(),
+        ));
+    }
+    if !src.is_file() {
+        return Err(AppError::InvalidArgument(format!(
+            "备份文件不存在: {}",
+            src_path
+        )));
+    }
+    tracing::info!("从备份恢复数据: {:?}", src);
+    let manifest = restore(&app_handle, &src)
+        .map_err(AppError::Io)
+        .reported("restore_backup")?;
+
+    tracing::info!("数据已恢复（备份时间 {}）", manifest.created_at);
+    events::record(
+        EventKind::State,
+        "从备份恢复数据",
+        serde_json::json!({ "path": src_path, "created_at": manifest.created_at }),
+    );
+    let _ = app_handle.emit_all("backup://restored", &manifest);
+    Ok(manifest)
+}
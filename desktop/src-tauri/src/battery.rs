@@ -0,0 +1,271 @@
+// 电池和电源状态
+//
+// 笔记本 / 平板收银机拔掉电源后，电量耗尽会直接关机，正在结算的订单可能丢失。
+// 定期读取电池电量和是否接通电源，状态变化时发出 power://battery 事件，
+// 使用电池且电量低于设置的阈值时记录事件并弹出系统通知，前端据此提醒收银员尽快完成当前交易。
+//
+// Windows 下接通 / 断开电源时主窗口会收到 PBT_APMPOWERSTATUSCHANGE，由 power 模块转发立即刷新。
+
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::config::{BatteryConfig, ConfigStore};
+use crate::events::{self, EventKind};
+use crate::{notify, performance};
+
+/// 上次读取的状态
+static LAST: Mutex<Option<BatteryStatus>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatteryLevel {
+    /// 没有电池或无法读取
+    Unknown,
+    Normal,
+    Low,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatteryStatus {
+    /// 是否有电池（台式机为 false）
+    pub present: bool,
+    /// 剩余电量（0-100）
+    pub percent: Option<u8>,
+    /// 是否接通电源，无法确定时为空
+    pub ac_online: Option<bool>,
+    pub charging: bool,
+    /// 预计剩余使用时间（秒）
+    pub remaining_secs: Option<u64>,
+    /// 是否开启了省电模式
+    pub saver: bool,
+    pub level: BatteryLevel,
+}
+
+/// 系统报告的原始状态（level 由阈值计算）
+struct Reading {
+    present: bool,
+    percent: Option<u8>,
+    ac_online: Option<bool>,
+    charging: bool,
+    remaining_secs: Option<u64>,
+    saver: bool,
+}
+
+impl Reading {
+    fn none() -> Self {
+        Self {
+            present: false,
+            percent: None,
+            ac_online: None,
+            charging: false,
+            remaining_secs: None,
+            saver: false,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read() -> Reading {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return Reading::none();
+    }
+    // BatteryFlag: 128 = 没有电池，255 = 未知，8 = 正在充电
+    let present = status.BatteryFlag != 128 && status.BatteryFlag != 255;
+    Reading {
+        present,
+        percent: (present && status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent),
+        ac_online: match status.ACLineStatus {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        },
+        charging: present && status.BatteryFlag & 8 != 0,
+        remaining_secs: (status.BatteryLifeTime != u32::MAX)
+            .then_some(status.BatteryLifeTime as u64),
+        saver: status.SystemStatusFlag == 1,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read() -> Reading {
+    // 例如：
+    // Now drawing from 'Battery Power'
+    //  -InternalBattery-0 (id=1234567)	85%; discharging; 4:20 remaining present: true
+    let Ok(output) = crate::os::hidden_command("pmset")
+        .args(["-g", "batt"])
+        .output()
+    else {
+        return Reading::none();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut reading = Reading::none();
+    reading.ac_online = stdout.lines().next().map(|line| line.contains("AC Power"));
+    let Some(line) = stdout.lines().find(|line| line.contains("InternalBattery")) else {
+        return reading;
+    };
+    let fields: Vec<&str> = line
+        .split_once('\t')
+        .map(|(_, rest)| rest)
+        .unwrap_or(line)
+        .split(';')
+        .map(str::trim)
+        .collect();
+    reading.present = true;
+    reading.percent = fields
+        .first()
+        .and_then(|f| f.trim_end_matches('%').parse().ok());
+    reading.charging = fields.get(1).is_some_and(|f| *f == "charging");
+    reading.remaining_secs = fields
+        .get(2)
+        .and_then(|f| f.split_whitespace().next())
+        .and_then(|time| time.split_once(':'))
+        .and_then(|(h, m)| Some(h.parse::<u64>().ok()? * 3600 + m.parse::<u64>().ok()? * 60));
+    reading
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn read() -> Reading {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return Reading::none();
+    };
+    let mut reading = Reading::none();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let get = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        match get("type").as_deref() {
+            Some("Mains") => {
+                let online = get("online").as_deref() == Some("1");
+                reading.ac_online = Some(reading.ac_online.unwrap_or(false) || online);
+            }
+            Some("Battery") if !reading.present => {
+                reading.present = get("present").as_deref() != Some("0");
+                reading.percent = get("capacity").and_then(|c| c.parse().ok());
+                reading.charging = get("status").as_deref() == Some("Charging");
+            }
+            _ => {}
+        }
+    }
+    reading
+}
+
+fn level_for(reading: &Reading, settings: &BatteryConfig) -> BatteryLevel {
+    let Some(percent) = reading.percent.filter(|_| reading.present) else {
+        return BatteryLevel::Unknown;
+    };
+    // 接通电源时不告警
+    if reading.ac_online == Some(true) || reading.charging {
+        BatteryLevel::Normal
+    } else if percent <= settings.critical_percent {
+        BatteryLevel::Critical
+    } else if percent <= settings.low_percent {
+        BatteryLevel::Low
+    } else {
+        BatteryLevel::Normal
+    }
+}
+
+/// 读取当前的电池状态
+pub fn status(app_handle: &tauri::AppHandle) -> BatteryStatus {
+    let settings = app_handle.state::<ConfigStore>().get().battery;
+    let reading = read();
+    BatteryStatus {
+        level: level_for(&reading, &settings),
+        present: reading.present,
+        percent: reading.percent,
+        ac_online: reading.ac_online,
+        charging: reading.charging,
+        remaining_secs: reading.remaining_secs,
+        saver: reading.saver,
+    }
+}
+
+/// 读取状态，有变化时发出事件，电量变低时告警
+pub fn refresh(app_handle: &tauri::AppHandle) -> BatteryStatus {
+    let current = status(app_handle);
+    let previous = LAST.lock().unwrap().replace(current.clone());
+    if previous.as_ref() == Some(&current) {
+        return current;
+    }
+    let _ = app_handle.emit_all("power://battery", &current);
+
+    let Some(previous) = previous else {
+        return current;
+    };
+    if previous.ac_online != current.ac_online {
+        if let Some(ac_online) = current.ac_online {
+            let message = if ac_online {
+                "已接通电源"
+            } else {
+                "已断开电源，正在使用电池"
+            };
+            tracing::info!("{}: {:?}%", message, current.percent);
+            events::record(
+                EventKind::State,
+                message,
+                json!({ "percent": current.percent }),
+            );
+        }
+    }
+
+    if current.level > previous.level && current.level >= BatteryLevel::Low {
+        let percent = current.percent.unwrap_or_default();
+        tracing::warn!("电池电量低: {}%", percent);
+        events::record(
+            EventKind::State,
+            format!("电池电量低: {}%", percent),
+            json!({ "level": current.level, "remaining_secs": current.remaining_secs }),
+        );
+        let (title, body) = if current.level == BatteryLevel::Critical {
+            (
+                "电池电量严重不足",
+                format!(
+                    "剩余 {}%，设备即将关机，请立即完成当前交易并接通电源",
+                    percent
+                ),
+            )
+        } else {
+            ("电池电量低", format!("剩余 {}%，请尽快接通电源", percent))
+        };
+        notify::show(app_handle, title, &body);
+    }
+    current
+}
+
+/// 启动定期检查线程（没有电池的设备只检查一次）
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("battery".into())
+        .spawn(move || loop {
+            let settings = app_handle.state::<ConfigStore>().get().battery;
+            if settings.enabled && !refresh(&app_handle).present {
+                tracing::debug!("未检测到电池，停止检查电池状态");
+                return;
+            }
+            std::thread::sleep(performance::interval(Duration::from_secs(
+                settings.check_interval_secs.max(5),
+            )));
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动电池检查线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 读取电池电量和电源状态
+#[tauri::command]
+pub fn get_battery_status(app_handle: tauri::AppHandle) -> BatteryStatus {
+    refresh(&app_handle)
+}
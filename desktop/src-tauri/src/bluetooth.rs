@@ -0,0 +1,819 @@
+// 蓝牙外设
+//
+// 移动收银常用电池供电的蓝牙小票打印机和蓝牙电子秤，USB / 串口无法连接。目前仅支持 Windows：
+// - 经典蓝牙（SPP 串口协议）：搜索附近设备、配对（需要 PIN 时由系统弹出配对窗口），
+//   通过 RFCOMM 连接收发数据
+// - 低功耗蓝牙（BLE）：只能使用已在系统蓝牙设置中配对的设备，数据写入设备的可写特征值
+//   （优先使用常见打印机的 0x2AF1 特征值），不支持读取
+//
+// 每次收发都重新建立连接，发送完成后立即断开，不占用设备（多数蓝牙打印机同时只接受一个连接）。
+
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult};
+#[cfg(target_os = "windows")]
+use crate::events::{self, EventKind};
+use crate::wol;
+
+/// 单次最多发送的字节数
+const MAX_SEND_BYTES: usize = 1024 * 1024;
+
+/// 单次最多读取的字节数
+const MAX_READ_BYTES: usize = 64 * 1024;
+
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+const UNSUPPORTED: &str = "蓝牙外设目前仅支持 Windows";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// 经典蓝牙（SPP）
+    Classic,
+    /// 低功耗蓝牙
+    Le,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BluetoothDevice {
+    /// 蓝牙地址（AA:BB:CC:DD:EE:FF）
+    pub address: String,
+    pub name: String,
+    pub transport: Transport,
+    /// 设备类型（经典蓝牙的设备类别），例如 printer
+    pub kind: &'static str,
+    pub paired: bool,
+    pub connected: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BluetoothReadResult {
+    pub data: Vec<u8>,
+    /// 按 UTF-8 解码的内容（电子秤一般返回 ASCII 文本）
+    pub text: String,
+}
+
+fn parse_address(address: &str) -> Result<u64, String> {
+    let bytes = wol::parse_mac(address).map_err(|_| format!("蓝牙地址格式错误: {}", address))?;
+    Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn format_address(address: u64) -> String {
+    let mut bytes = [0u8; 6];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (address >> (40 - i * 8)) as u8;
+    }
+    wol::format_mac(&bytes)
+}
+
+/// 经典蓝牙设备类别中的主类别
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn device_kind(class_of_device: u32) -> &'static str {
+    match (class_of_device >> 8) & 0x1F {
+        0x01 => "computer",
+        0x02 => "phone",
+        0x04 => "audio",
+        0x05 => "peripheral",
+        // 成像设备中的打印机
+        0x06 if class_of_device & 0x80 != 0 => "printer",
+        0x06 => "imaging",
+        _ => "other",
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::sync::Once;
+    use std::time::{Duration, Instant};
+    use windows_sys::core::GUID;
+    use windows_sys::Win32::Devices::Bluetooth::{
+        BluetoothAuthenticateDeviceEx, BluetoothFindDeviceClose, BluetoothFindFirstDevice,
+        BluetoothFindNextDevice, BluetoothGATTGetCharacteristics, BluetoothGATTGetServices,
+        BluetoothGATTSetCharacteristicValue, MITMProtectionNotRequired, AF_BTH,
+        BLUETOOTH_DEVICE_INFO, BLUETOOTH_DEVICE_SEARCH_PARAMS, BLUETOOTH_GATT_FLAG_NONE,
+        BLUETOOTH_GATT_FLAG_WRITE_WITHOUT_RESPONSE, BTHPROTO_RFCOMM, BTH_LE_GATT_CHARACTERISTIC,
+        BTH_LE_GATT_SERVICE, GUID_BLUETOOTHLE_DEVICE_INTERFACE, SOCKADDR_BTH,
+    };
+    use windows_sys::Win32::Devices::DeviceAndDriverInstallation::{
+        SetupDiDestroyDeviceInfoList, SetupDiEnumDeviceInterfaces, SetupDiGetClassDevsW,
+        SetupDiGetDeviceInterfaceDetailW, SetupDiGetDeviceRegistryPropertyW, DIGCF_DEVICEINTERFACE,
+        DIGCF_PRESENT, SPDRP_FRIENDLYNAME, SP_DEVICE_INTERFACE_DATA,
+        SP_DEVICE_INTERFACE_DETAIL_DATA_W, SP_DEVINFO_DATA,
+    };
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GENERIC_READ, GENERIC_WRITE, HANDLE, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Networking::WinSock::{
+        closesocket, connect, recv, send, setsockopt, socket, WSAGetLastError, WSAStartup,
+        INVALID_SOCKET, SOCKADDR, SOCKET, SOCKET_ERROR, SOCK_STREAM, SOL_SOCKET, SO_RCVTIMEO,
+        SO_SNDTIMEO, WSADATA,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+
+    use super::{device_kind, format_address, BluetoothDevice, Transport};
+
+    /// SPP 串口服务 UUID（00001101-0000-1000-8000-00805F9B34FB）
+    const SERIAL_PORT_SERVICE: GUID = GUID::from_u128(0x00001101_0000_1000_8000_00805f9b34fb);
+
+    /// 常见 BLE 打印机的数据特征值
+    const PRINTER_CHARACTERISTIC: u16 = 0x2AF1;
+
+    /// 系统自带的 GATT 服务（通用访问、通用属性、设备信息），不用于传数据
+    const STANDARD_SERVICES: [u16; 3] = [0x1800, 0x1801, 0x180A];
+
+    /// BLE 默认 MTU 下每次写入的字节数
+    const LE_CHUNK: usize = 20;
+
+    /// 无响应写入时每包之间的间隔，避免打印机缓冲区溢出
+    const LE_CHUNK_DELAY: Duration = Duration::from_millis(5);
+
+    /// 读取到数据后，超过该时间没有新数据即认为读取完成
+    const READ_IDLE: Duration = Duration::from_millis(200);
+
+    fn from_wide(buffer: &[u16]) -> String {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    }
+
+    fn last_error() -> std::io::Error {
+        std::io::Error::last_os_error()
+    }
+
+    // 经典蓝牙
+
+    fn device_info(address: u64) -> BLUETOOTH_DEVICE_INFO {
+        let mut info: BLUETOOTH_DEVICE_INFO = unsafe { std::mem::zeroed() };
+        info.dwSize = std::mem::size_of::<BLUETOOTH_DEVICE_INFO>() as u32;
+        info.Address.Anonymous.ullLong = address;
+        info
+    }
+
+    /// 搜索经典蓝牙设备（包括已配对的设备），inquiry 为 true 时搜索附近的新设备
+    pub fn classic_devices(
+        inquiry: bool,
+        timeout: Duration,
+    ) -> Result<Vec<BluetoothDevice>, String> {
+        let params = BLUETOOTH_DEVICE_SEARCH_PARAMS {
+            dwSize: std::mem::size_of::<BLUETOOTH_DEVICE_SEARCH_PARAMS>() as u32,
+            fReturnAuthenticated: 1,
+            fReturnRemembered: 1,
+            fReturnUnknown: 1,
+            fReturnConnected: 1,
+            fIssueInquiry: inquiry as i32,
+            // 搜索时长以 1.28 秒为单位
+            cTimeoutMultiplier: (timeout.as_millis() / 1280).clamp(1, 48) as u8,
+            hRadio: 0,
+        };
+        let mut info = device_info(0);
+        let find = unsafe { BluetoothFindFirstDevice(&params, &mut info) };
+        if find == 0 {
+            // 没有蓝牙适配器或没有找到设备
+            let error = last_error();
+            return match error.raw_os_error() {
+                // ERROR_NO_MORE_ITEMS
+                Some(259) => Ok(Vec::new()),
+                _ => Err(format!("搜索蓝牙设备失败（请确认已开启蓝牙）: {}", error)),
+            };
+        }
+
+        let mut devices = Vec::new();
+        loop {
+            let address = unsafe { info.Address.Anonymous.ullLong };
+            devices.push(BluetoothDevice {
+                address: format_address(address),
+                name: from_wide(&info.szName),
+                transport: Transport::Classic,
+                kind: device_kind(info.ulClassofDevice),
+                paired: info.fAuthenticated != 0,
+                connected: info.fConnected != 0,
+            });
+            info = device_info(0);
+            if unsafe { BluetoothFindNextDevice(find, &mut info) } == 0 {
+                break;
+            }
+        }
+        unsafe { BluetoothFindDeviceClose(find) };
+        Ok(devices)
+    }
+
+    /// 与经典蓝牙设备配对，需要 PIN 时由系统弹出配对窗口
+    pub fn pair(address: u64) -> Result<(), String> {
+        let mut info = device_info(address);
+        let result = unsafe {
+            BluetoothAuthenticateDeviceEx(
+                0,
+                0,
+                &mut info,
+                std::ptr::null(),
+                MITMProtectionNotRequired,
+            )
+        };
+        match result {
+            0 => Ok(()),
+            // ERROR_NO_MORE_ITEMS：已经配对
+            259 => Ok(()),
+            // ERROR_CANCELLED
+            1223 => Err("已取消配对".to_string()),
+            code => Err(format!(
+                "配对失败: {}",
+                std::io::Error::from_raw_os_error(code as i32)
+            )),
+        }
+    }
+
+    fn winsock_error(message: &str) -> String {
+        let code = unsafe { WSAGetLastError() };
+        format!("{}: {}", message, std::io::Error::from_raw_os_error(code))
+    }
+
+    /// RFCOMM 连接，离开作用域时断开
+    struct Rfcomm(SOCKET);
+
+    impl Drop for Rfcomm {
+        fn drop(&mut self) {
+            unsafe { closesocket(self.0) };
+        }
+    }
+
+    impl Rfcomm {
+        fn connect(address: u64, timeout: Duration) -> Result<Self, String> {
+            static STARTUP: Once = Once::new();
+            STARTUP.call_once(|| {
+                let mut data: WSADATA = unsafe { std::mem::zeroed() };
+                unsafe { WSAStartup(0x0202, &mut data) };
+            });
+
+            let raw = unsafe { socket(AF_BTH as i32, SOCK_STREAM, BTHPROTO_RFCOMM as i32) };
+            if raw == INVALID_SOCKET {
+                return Err(winsock_error("创建蓝牙连接失败"));
+            }
+            let rfcomm = Rfcomm(raw);
+            rfcomm.set_timeout(SO_SNDTIMEO, timeout);
+            rfcomm.set_timeout(SO_RCVTIMEO, timeout);
+
+            let target = SOCKADDR_BTH {
+                addressFamily: AF_BTH,
+                btAddr: address,
+                serviceClassId: SERIAL_PORT_SERVICE,
+                port: 0,
+            };
+            let connected = unsafe {
+                connect(
+                    raw,
+                    &target as *const SOCKADDR_BTH as *const SOCKADDR,
+                    std::mem::size_of::<SOCKADDR_BTH>() as i32,
+                )
+            };
+            if connected == SOCKET_ERROR {
+                return Err(winsock_error(
+                    "连接蓝牙设备失败（请确认设备已开机并已配对）",
+                ));
+            }
+            Ok(rfcomm)
+        }
+
+        fn set_timeout(&self, option: i32, timeout: Duration) {
+            let millis = timeout.as_millis().min(u32::MAX as u128) as u32;
+            unsafe {
+                setsockopt(
+                    self.0,
+                    SOL_SOCKET,
+                    option,
+                    &millis as *const u32 as *const u8,
+                    std::mem::size_of::<u32>() as i32,
+                )
+            };
+        }
+
+        fn send_all(&self, data: &[u8]) -> Result<(), String> {
+            let mut sent = 0;
+            while sent < data.len() {
+                let chunk = &data[sent..];
+                let n = unsafe {
+                    send(
+                        self.0,
+                        chunk.as_ptr(),
+                        chunk.len().min(i32::MAX as usize) as i32,
+                        0,
+                    )
+                };
+                if n == SOCKET_ERROR || n == 0 {
+                    return Err(winsock_error("发送数据失败"));
+                }
+                sent += n as usize;
+            }
+            Ok(())
+        }
+
+        /// 读取数据：等待第一段数据最多 timeout，之后连续 READ_IDLE 没有新数据即结束
+        fn read(&self, max_bytes: usize, timeout: Duration) -> Result<Vec<u8>, String> {
+            let mut data = Vec::new();
+            let mut buffer = [0u8; 1024];
+            let started = Instant::now();
+            while data.len() < max_bytes {
+                let n = unsafe { recv(self.0, buffer.as_mut_ptr(), buffer.len() as i32, 0) };
+                if n > 0 {
+                    data.extend_from_slice(&buffer[..n as usize]);
+                    self.set_timeout(SO_RCVTIMEO, READ_IDLE);
+                    continue;
+                }
+                if n == 0 || !data.is_empty() {
+                    break;
+                }
+                // WSAETIMEDOUT
+                if unsafe { WSAGetLastError() } == 10060 || started.elapsed() >= timeout {
+                    return Err("读取数据超时".to_string());
+                }
+                return Err(winsock_error("读取数据失败"));
+            }
+            data.truncate(max_bytes);
+            Ok(data)
+        }
+    }
+
+    pub fn classic_send(address: u64, data: &[u8], timeout: Duration) -> Result<(), String> {
+        Rfcomm::connect(address, timeout)?.send_all(data)
+    }
+
+    pub fn classic_read(
+        address: u64,
+        request: &[u8],
+        max_bytes: usize,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, String> {
+        let rfcomm = Rfcomm::connect(address, timeout)?;
+        if !request.is_empty() {
+            rfcomm.send_all(request)?;
+        }
+        rfcomm.read(max_bytes, timeout)
+    }
+
+    // 低功耗蓝牙
+
+    struct LeDevice {
+        address: u64,
+        name: String,
+        /// 设备接口路径（以 0 结尾的 UTF-16）
+        path: Vec<u16>,
+    }
+
+    /// 从设备接口路径中取出蓝牙地址，例如 \\?\BTHLE#Dev_aabbccddeeff#...
+    fn address_from_path(path: &str) -> Option<u64> {
+        let lower = path.to_lowercase();
+        let start = lower.find("dev_")? + 4;
+        let hex = lower.get(start..start + 12)?;
+        u64::from_str_radix(hex, 16).ok()
+    }
+
+    /// 已在系统中配对的 BLE 设备
+    fn le_devices() -> Result<Vec<LeDevice>, String> {
+        let set = unsafe {
+            SetupDiGetClassDevsW(
+                &GUID_BLUETOOTHLE_DEVICE_INTERFACE,
+                std::ptr::null(),
+                0,
+                DIGCF_PRESENT | DIGCF_DEVICEINTERFACE,
+            )
+        };
+        if set == INVALID_HANDLE_VALUE {
+            return Err(format!("读取 BLE 设备列表失败: {}", last_error()));
+        }
+
+        let mut devices = Vec::new();
+        for index in 0.. {
+            let mut interface: SP_DEVICE_INTERFACE_DATA = unsafe { std::mem::zeroed() };
+            interface.cbSize = std::mem::size_of::<SP_DEVICE_INTERFACE_DATA>() as u32;
+            let found = unsafe {
+                SetupDiEnumDeviceInterfaces(
+                    set,
+                    std::ptr::null(),
+                    &GUID_BLUETOOTHLE_DEVICE_INTERFACE,
+                    index,
+                    &mut interface,
+                )
+            };
+            if found == 0 {
+                break;
+            }
+
+            let mut size = 0u32;
+            unsafe {
+                SetupDiGetDeviceInterfaceDetailW(
+                    set,
+                    &interface,
+                    std::ptr::null_mut(),
+                    0,
+                    &mut size,
+                    std::ptr::null_mut(),
+                )
+            };
+            if size == 0 {
+                continue;
+            }
+            // 按 u32 对齐分配缓冲区
+            let mut buffer = vec![0u32; (size as usize + 3) / 4];
+            let detail = buffer.as_mut_ptr() as *mut SP_DEVICE_INTERFACE_DETAIL_DATA_W;
+            // cbSize 为结构体固定部分的大小（64 位为 8，32 位为 6）
+            unsafe {
+                (*detail).cbSize = if cfg!(target_pointer_width = "64") {
+                    8
+                } else {
+                    6
+                };
+            }
+            let mut info: SP_DEVINFO_DATA = unsafe { std::mem::zeroed() };
+            info.cbSize = std::mem::size_of::<SP_DEVINFO_DATA>() as u32;
+            let ok = unsafe {
+                SetupDiGetDeviceInterfaceDetailW(
+                    set, &interface, detail, size, &mut size, &mut info,
+                )
+            };
+            if ok == 0 {
+                continue;
+            }
+            let path_units = unsafe {
+                let start = std::ptr::addr_of!((*detail).DevicePath) as *const u16;
+                let max = (size as usize - 4) / 2;
+                std::slice::from_raw_parts(start, max)
+            };
+            let path = from_wide(path_units);
+            let Some(address) = address_from_path(&path) else {
+                continue;
+            };
+
+            let mut name = [0u16; 256];
+            let named = unsafe {
+                SetupDiGetDeviceRegistryPropertyW(
+                    set,
+                    &info,
+                    SPDRP_FRIENDLYNAME,
+                    std::ptr::null_mut(),
+                    name.as_mut_ptr() as *mut u8,
+                    (name.len() * 2) as u32,
+                    std::ptr::null_mut(),
+                )
+            };
+            devices.push(LeDevice {
+                address,
+                name: if named != 0 {
+                    from_wide(&name)
+                } else {
+                    String::new()
+                },
+                path: path.encode_utf16().chain(Some(0)).collect(),
+            });
+        }
+        unsafe { SetupDiDestroyDeviceInfoList(set) };
+        Ok(devices)
+    }
+
+    pub fn paired_le_devices() -> Result<Vec<BluetoothDevice>, String> {
+        Ok(le_devices()?
+            .into_iter()
+            .map(|device| BluetoothDevice {
+                address: format_address(device.address),
+                name: device.name,
+                transport: Transport::Le,
+                kind: "other",
+                paired: true,
+                connected: false,
+            })
+            .collect())
+    }
+
+    pub fn is_le_device(address: u64) -> bool {
+        le_devices().is_ok_and(|devices| devices.iter().any(|d| d.address == address))
+    }
+
+    /// 打开的 BLE 设备，离开作用域时关闭
+    struct LeHandle(HANDLE);
+
+    impl Drop for LeHandle {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    fn short_uuid(uuid: &windows_sys::Win32::Devices::Bluetooth::BTH_LE_UUID) -> Option<u16> {
+        if uuid.IsShortUuid != 0 {
+            Some(unsafe { uuid.Value.ShortUuid })
+        } else {
+            None
+        }
+    }
+
+    fn services(handle: HANDLE) -> Result<Vec<BTH_LE_GATT_SERVICE>, String> {
+        let mut count = 0u16;
+        unsafe {
+            BluetoothGATTGetServices(
+                handle,
+                0,
+                std::ptr::null_mut(),
+                &mut count,
+                BLUETOOTH_GATT_FLAG_NONE,
+            )
+        };
+        if count == 0 {
+            return Err("读取 BLE 服务失败".to_string());
+        }
+        let mut services =
+            vec![unsafe { std::mem::zeroed::<BTH_LE_GATT_SERVICE>() }; count as usize];
+        let result = unsafe {
+            BluetoothGATTGetServices(
+                handle,
+                count,
+                services.as_mut_ptr(),
+                &mut count,
+                BLUETOOTH_GATT_FLAG_NONE,
+            )
+        };
+        if result < 0 {
+            return Err(format!("读取 BLE 服务失败: 0x{:08X}", result));
+        }
+        services.truncate(count as usize);
+        Ok(services)
+    }
+
+    fn characteristics(
+        handle: HANDLE,
+        service: &BTH_LE_GATT_SERVICE,
+    ) -> Vec<BTH_LE_GATT_CHARACTERISTIC> {
+        let mut count = 0u16;
+        unsafe {
+            BluetoothGATTGetCharacteristics(
+                handle,
+                service,
+                0,
+                std::ptr::null_mut(),
+                &mut count,
+                BLUETOOTH_GATT_FLAG_NONE,
+            )
+        };
+        if count == 0 {
+            return Vec::new();
+        }
+        let mut characteristics =
+            vec![unsafe { std::mem::zeroed::<BTH_LE_GATT_CHARACTERISTIC>() }; count as usize];
+        let result = unsafe {
+            BluetoothGATTGetCharacteristics(
+                handle,
+                service,
+                count,
+                characteristics.as_mut_ptr(),
+                &mut count,
+                BLUETOOTH_GATT_FLAG_NONE,
+            )
+        };
+        if result < 0 {
+            return Vec::new();
+        }
+        characteristics.truncate(count as usize);
+        characteristics
+    }
+
+    /// 选择用来写入数据的特征值
+    fn writable_characteristic(handle: HANDLE) -> Result<BTH_LE_GATT_CHARACTERISTIC, String> {
+        let mut candidates = Vec::new();
+        for service in services(handle)? {
+            if short_uuid(&service.ServiceUuid)
+                .is_some_and(|uuid| STANDARD_SERVICES.contains(&uuid))
+            {
+                continue;
+            }
+            candidates.extend(
+                characteristics(handle, &service)
+                    .into_iter()
+                    .filter(|c| c.IsWritable != 0 || c.IsWritableWithoutResponse != 0),
+            );
+        }
+        candidates
+            .iter()
+            .find(|c| short_uuid(&c.CharacteristicUuid) == Some(PRINTER_CHARACTERISTIC))
+            .or_else(|| candidates.first())
+            .copied()
+            .ok_or_else(|| "该 BLE 设备没有可写入数据的特征值".to_string())
+    }
+
+    pub fn le_send(address: u64, data: &[u8]) -> Result<(), String> {
+        let device = le_devices()?
+            .into_iter()
+            .find(|d| d.address == address)
+            .ok_or_else(|| "未找到该 BLE 设备，请先在系统蓝牙设置中配对".to_string())?;
+        let raw = unsafe {
+            CreateFileW(
+                device.path.as_ptr(),
+                GENERIC_READ | GENERIC_WRITE,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                std::ptr::null(),
+                OPEN_EXISTING,
+                0,
+                0,
+            )
+        };
+        if raw == INVALID_HANDLE_VALUE {
+            return Err(format!("打开 BLE 设备失败: {}", last_error()));
+        }
+        let handle = LeHandle(raw);
+
+        let characteristic = writable_characteristic(handle.0)?;
+        let without_response = characteristic.IsWritableWithoutResponse != 0;
+        let flags = if without_response {
+            BLUETOOTH_GATT_FLAG_WRITE_WITHOUT_RESPONSE
+        } else {
+            BLUETOOTH_GATT_FLAG_NONE
+        };
+        for chunk in data.chunks(LE_CHUNK) {
+            // BTH_LE_GATT_CHARACTERISTIC_VALUE：4 字节长度后跟数据
+            let mut value = Vec::with_capacity(4 + chunk.len());
+            value.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            value.extend_from_slice(chunk);
+            // 按 u32 对齐
+            let mut aligned = vec![0u32; (value.len() + 3) / 4];
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    value.as_ptr(),
+                    aligned.as_mut_ptr() as *mut u8,
+                    value.len(),
+                )
+            };
+            let result = unsafe {
+                BluetoothGATTSetCharacteristicValue(
+                    handle.0,
+                    &characteristic,
+                    aligned.as_ptr() as *const _,
+                    0,
+                    flags,
+                )
+            };
+            if result < 0 {
+                return Err(format!("写入 BLE 设备失败: 0x{:08X}", result));
+            }
+            if without_response {
+                std::thread::sleep(LE_CHUNK_DELAY);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn unsupported<T>() -> AppResult<T> {
+    Err(AppError::InvalidArgument(UNSUPPORTED.to_string()))
+}
+
+#[cfg(target_os = "windows")]
+fn transmit(address: u64, data: &[u8], timeout: std::time::Duration) -> Result<(), String> {
+    if windows::is_le_device(address) {
+        windows::le_send(address, data)
+    } else {
+        windows::classic_send(address, data, timeout)
+    }
+}
+
+/// 向蓝牙设备发送数据（供小票打印机使用），timeout 为经典蓝牙的连接超时
+pub(crate) fn send(address: &str, data: &[u8], timeout: std::time::Duration) -> Result<(), String> {
+    let address = parse_address(address)?;
+    #[cfg(target_os = "windows")]
+    {
+        transmit(address, data, timeout)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (address, data, timeout);
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+fn check_data(data: &[u8]) -> AppResult<()> {
+    if data.is_empty() {
+        return Err(AppError::InvalidArgument("发送的数据不能为空".to_string()));
+    }
+    if data.len() > MAX_SEND_BYTES {
+        return Err(AppError::InvalidArgument(format!(
+            "单次最多发送 {} KB",
+            MAX_SEND_BYTES / 1024
+        )));
+    }
+    Ok(())
+}
+
+// Tauri 命令
+
+/// 列出蓝牙设备（已配对的经典蓝牙和 BLE 设备），search 为 true 时同时搜索附近的经典蓝牙设备
+#[tauri::command]
+pub async fn list_bluetooth_devices(
+    search: Option<bool>,
+    timeout_secs: Option<u64>,
+) -> AppResult<Vec<BluetoothDevice>> {
+    #[cfg(target_os = "windows")]
+    {
+        let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(10).clamp(2, 60));
+        let mut devices = windows::classic_devices(search.unwrap_or(false), timeout)
+            .map_err(AppError::Internal)?;
+        match windows::paired_le_devices() {
+            Ok(le) => devices.extend(le),
+            Err(e) => tracing::warn!("{}", e),
+        }
+        Ok(devices)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (search, timeout_secs);
+        unsupported()
+    }
+}
+
+/// 与经典蓝牙设备配对（BLE 设备请在系统蓝牙设置中配对）
+#[tauri::command]
+pub async fn pair_bluetooth_device(address: String) -> AppResult<()> {
+    let address = parse_address(&address).map_err(AppError::InvalidArgument)?;
+    #[cfg(target_os = "windows")]
+    {
+        windows::pair(address).map_err(AppError::Internal)?;
+        tracing::info!("已配对蓝牙设备: {}", format_address(address));
+        events::record(
+            EventKind::State,
+            "配对蓝牙设备",
+            serde_json::json!({ "address": format_address(address) }),
+        );
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = address;
+        unsupported()
+    }
+}
+
+/// 向蓝牙设备发送数据（例如打印机的 ESC/POS 指令）
+#[tauri::command]
+pub async fn bluetooth_send(
+    address: String,
+    data: Vec<u8>,
+    timeout_ms: Option<u64>,
+) -> AppResult<()> {
+    let address = parse_address(&address).map_err(AppError::InvalidArgument)?;
+    check_data(&data)?;
+    #[cfg(target_os = "windows")]
+    {
+        let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(10_000).max(1000));
+        let result = transmit(address, &data, timeout);
+        events::record(
+            if result.is_ok() {
+                EventKind::Print
+            } else {
+                EventKind::Error
+            },
+            "发送数据到蓝牙设备",
+            serde_json::json!({
+                "address": format_address(address),
+                "bytes": data.len(),
+                "error": result.as_ref().err(),
+            }),
+        );
+        result.map_err(AppError::Network)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (address, timeout_ms);
+        unsupported()
+    }
+}
+
+/// 从经典蓝牙设备读取数据（例如电子秤的重量），request 不为空时先发送该请求
+#[tauri::command]
+pub async fn bluetooth_read(
+    address: String,
+    request: Option<Vec<u8>>,
+    max_bytes: Option<usize>,
+    timeout_ms: Option<u64>,
+) -> AppResult<BluetoothReadResult> {
+    let address = parse_address(&address).map_err(AppError::InvalidArgument)?;
+    #[cfg(target_os = "windows")]
+    {
+        if windows::is_le_device(address) {
+            return Err(AppError::InvalidArgument(
+                "暂不支持从 BLE 设备读取数据".to_string(),
+            ));
+        }
+        let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(3000).max(100));
+        let data = windows::classic_read(
+            address,
+            &request.unwrap_or_default(),
+            max_bytes.unwrap_or(1024).clamp(1, MAX_READ_BYTES),
+            timeout,
+        )
+        .map_err(AppError::Network)?;
+        Ok(BluetoothReadResult {
+            text: String::from_utf8_lossy(&data).into_owned(),
+            data,
+        })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (address, request, max_bytes, timeout_ms, MAX_READ_BYTES);
+        unsupported()
+    }
+}
@@ -0,0 +1,355 @@
+// 剪贴板
+//
+// 支持文字和图片：可以把手机同步工具里的商品照片直接粘贴进来，也可以把收款码图片复制出去。
+// 图片统一以 PNG（base64）与前端交换。
+//
+// 文字使用 Tauri 的剪贴板接口；图片目前只支持 Windows，读取时优先使用 PNG 格式
+// （浏览器、截图工具等会同时提供），否则转换系统的位图（CF_DIB）。
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::ClipboardManager;
+
+use crate::error::{AppError, AppResult};
+
+/// 单张图片的大小上限（解码后像素数），避免误粘贴超大图片占满内存
+const MAX_PIXELS: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardFormat {
+    Text,
+    Image,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ClipboardContent {
+    Text {
+        text: String,
+    },
+    Image {
+        /// PNG 数据（base64）
+        png_base64: String,
+        #[serde(default)]
+        width: u32,
+        #[serde(default)]
+        height: u32,
+    },
+    Empty,
+}
+
+fn image_content(png: &[u8]) -> AppResult<ClipboardContent> {
+    let (width, height) = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map(|image| (image.width(), image.height()))
+        .map_err(|e| AppError::Internal(format!("解析剪贴板图片失败: {}", e)))?;
+    Ok(ClipboardContent::Image {
+        png_base64: base64::engine::general_purpose::STANDARD.encode(png),
+        width,
+        height,
+    })
+}
+
+fn read_text(app_handle: &tauri::AppHandle) -> AppResult<ClipboardContent> {
+    let text = app_handle
+        .clipboard_manager()
+        .read_text()
+        .map_err(|e| AppError::Internal(format!("读取剪贴板失败: {}", e)))?;
+    Ok(match text {
+        Some(text) => ClipboardContent::Text { text },
+        None => ClipboardContent::Empty,
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn read_image() -> AppResult<Option<Vec<u8>>> {
+    windows::read_png().map_err(AppError::Internal)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_image() -> AppResult<Option<Vec<u8>>> {
+    Err(AppError::Internal(
+        "当前系统不支持读取剪贴板图片".to_string(),
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn write_image(png: &[u8]) -> AppResult<()> {
+    let image = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map_err(|e| AppError::InvalidArgument(format!("图片格式错误: {}", e)))?
+        .to_rgba8();
+    windows::write_png(png, &image).map_err(AppError::Internal)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn write_image(_png: &[u8]) -> AppResult<()> {
+    Err(AppError::Internal("当前系统不支持复制图片".to_string()))
+}
+
+// Tauri 命令
+
+/// 读取剪贴板，format 为空时有图片则读取图片，否则读取文字
+#[tauri::command]
+pub async fn clipboard_read(
+    format: Option<ClipboardFormat>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<ClipboardContent> {
+    if format == Some(ClipboardFormat::Text) {
+        return read_text(&app_handle);
+    }
+
+    match read_image() {
+        Ok(Some(png)) => return image_content(&png),
+        Ok(None) if format == Some(ClipboardFormat::Image) => return Ok(ClipboardContent::Empty),
+        Ok(None) => {}
+        Err(e) if format == Some(ClipboardFormat::Image) => return Err(e),
+        Err(e) => tracing::debug!("{}", e),
+    }
+    read_text(&app_handle)
+}
+
+/// 写入剪贴板
+#[tauri::command]
+pub async fn clipboard_write(
+    content: ClipboardContent,
+    app_handle: tauri::AppHandle,
+) -> AppResult<()> {
+    match content {
+        ClipboardContent::Text { text } => app_handle
+            .clipboard_manager()
+            .write_text(text)
+            .map_err(|e| AppError::Internal(format!("写入剪贴板失败: {}", e))),
+        ClipboardContent::Image { png_base64, .. } => {
+            let png = base64::engine::general_purpose::STANDARD
+                .decode(png_base64.trim())
+                .map_err(|e| AppError::InvalidArgument(format!("图片数据错误: {}", e)))?;
+            write_image(&png)
+        }
+        ClipboardContent::Empty => app_handle
+            .clipboard_manager()
+            .write_text(String::new())
+            .map_err(|e| AppError::Internal(format!("清空剪贴板失败: {}", e))),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use image::RgbaImage;
+    use windows_sys::Win32::Foundation::{GlobalFree, HGLOBAL};
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable,
+        OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows_sys::Win32::System::Memory::{
+        GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+    };
+    use windows_sys::Win32::System::Ole::CF_DIB;
+
+    /// BITMAPINFOHEADER 的大小
+    const HEADER_SIZE: usize = 40;
+    const BI_RGB: u32 = 0;
+    const BI_BITFIELDS: u32 = 3;
+
+    /// 打开剪贴板，离开作用域时关闭
+    struct Clipboard;
+
+    impl Clipboard {
+        fn open() -> Result<Self, String> {
+            // 其他程序可能正占用剪贴板，稍等重试
+            for _ in 0..10 {
+                if unsafe { OpenClipboard(0) } != 0 {
+                    return Ok(Self);
+                }
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+            Err("剪贴板正被其他程序占用".to_string())
+        }
+
+        fn get(&self, format: u32) -> Option<Vec<u8>> {
+            unsafe {
+                if IsClipboardFormatAvailable(format) == 0 {
+                    return None;
+                }
+                let handle = GetClipboardData(format) as HGLOBAL;
+                if handle.is_null() {
+                    return None;
+                }
+                let ptr = GlobalLock(handle) as *const u8;
+                if ptr.is_null() {
+                    return None;
+                }
+                let data = std::slice::from_raw_parts(ptr, GlobalSize(handle)).to_vec();
+                GlobalUnlock(handle);
+                Some(data)
+            }
+        }
+
+        fn set(&self, format: u32, data: &[u8]) -> Result<(), String> {
+            unsafe {
+                let handle = GlobalAlloc(GMEM_MOVEABLE, data.len());
+                if handle.is_null() {
+                    return Err("分配剪贴板内存失败".to_string());
+                }
+                let ptr = GlobalLock(handle) as *mut u8;
+                if ptr.is_null() {
+                    GlobalFree(handle);
+                    return Err("分配剪贴板内存失败".to_string());
+                }
+                std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+                GlobalUnlock(handle);
+
+                // 成功后内存归系统所有，失败时需要自行释放
+                if SetClipboardData(format, handle as isize) == 0 {
+                    GlobalFree(handle);
+                    return Err("写入剪贴板失败".to_string());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Clipboard {
+        fn drop(&mut self) {
+            unsafe { CloseClipboard() };
+        }
+    }
+
+    fn png_format() -> u32 {
+        let name: Vec<u16> = "PNG".encode_utf16().chain(Some(0)).collect();
+        unsafe { RegisterClipboardFormatW(name.as_ptr()) }
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u16_at(data: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+    }
+
+    /// 把 CF_DIB 位图转换为图片（支持 24 / 32 位）
+    fn dib_to_image(dib: &[u8]) -> Result<RgbaImage, String> {
+        if dib.len() < HEADER_SIZE {
+            return Err("剪贴板位图数据不完整".to_string());
+        }
+        let header_size = u32_at(dib, 0) as usize;
+        let width = u32_at(dib, 4) as i32;
+        let height = u32_at(dib, 8) as i32;
+        let bit_count = u16_at(dib, 14);
+        let compression = u32_at(dib, 16);
+
+        if width <= 0 || height == 0 {
+            return Err("剪贴板位图尺寸无效".to_string());
+        }
+        if (width as u64) * (height.unsigned_abs() as u64) > super::MAX_PIXELS {
+            return Err("剪贴板图片过大".to_string());
+        }
+        if !matches!(bit_count, 24 | 32) || !matches!(compression, BI_RGB | BI_BITFIELDS) {
+            return Err(format!(
+                "不支持的位图格式: {} 位, 压缩方式 {}",
+                bit_count, compression
+            ));
+        }
+
+        // BI_BITFIELDS 且为旧版头时，头后面跟着 3 个颜色掩码
+        let mut offset = header_size;
+        if compression == BI_BITFIELDS && header_size == HEADER_SIZE {
+            offset += 12;
+        }
+
+        let width = width as u32;
+        let rows = height.unsigned_abs();
+        let bytes_per_pixel = bit_count as usize / 8;
+        let stride = (width as usize * bit_count as usize + 31) / 32 * 4;
+        if dib.len() < offset + stride * rows as usize {
+            return Err("剪贴板位图数据不完整".to_string());
+        }
+
+        let mut image = RgbaImage::new(width, rows);
+        let mut has_alpha = false;
+        for row in 0..rows {
+            // 高度为正时数据从最底下一行开始
+            let y = if height > 0 { rows - 1 - row } else { row };
+            let line = &dib[offset + row as usize * stride..];
+            for x in 0..width {
+                let p = &line[x as usize * bytes_per_pixel..];
+                let alpha = if bytes_per_pixel == 4 { p[3] } else { 255 };
+                has_alpha |= alpha != 0;
+                image.put_pixel(x, y, image::Rgba([p[2], p[1], p[0], alpha]));
+            }
+        }
+
+        // 很多程序写入的 32 位位图 alpha 全为 0，视为不透明
+        if !has_alpha {
+            image.pixels_mut().for_each(|p| p.0[3] = 255);
+        }
+        Ok(image)
+    }
+
+    /// 把图片转换为 32 位 CF_DIB 位图
+    fn image_to_dib(image: &RgbaImage) -> Vec<u8> {
+        let (width, height) = image.dimensions();
+        let pixels = width as usize * height as usize * 4;
+
+        let mut dib = Vec::with_capacity(HEADER_SIZE + pixels);
+        dib.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        dib.extend_from_slice(&(width as i32).to_le_bytes());
+        dib.extend_from_slice(&(height as i32).to_le_bytes());
+        dib.extend_from_slice(&1u16.to_le_bytes()); // planes
+        dib.extend_from_slice(&32u16.to_le_bytes()); // bit count
+        dib.extend_from_slice(&BI_RGB.to_le_bytes());
+        dib.extend_from_slice(&(pixels as u32).to_le_bytes());
+        dib.extend_from_slice(&[0u8; 16]); // 分辨率、调色板
+
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let [r, g, b, a] = image.get_pixel(x, y).0;
+                dib.extend_from_slice(&[b, g, r, a]);
+            }
+        }
+        dib
+    }
+
+    fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+        let mut png = Vec::new();
+        image
+            .write_to(
+                &mut std::io::Cursor::new(&mut png),
+                image::ImageOutputFormat::Png,
+            )
+            .map_err(|e| format!("编码图片失败: {}", e))?;
+        Ok(png)
+    }
+
+    /// 读取剪贴板中的图片（PNG），没有图片时返回 None
+    pub fn read_png() -> Result<Option<Vec<u8>>, String> {
+        let clipboard = Clipboard::open()?;
+        if let Some(png) = clipboard.get(png_format()) {
+            return Ok(Some(png));
+        }
+        let Some(dib) = clipboard.get(CF_DIB as u32) else {
+            return Ok(None);
+        };
+        drop(clipboard);
+        encode_png(&dib_to_image(&dib)?).map(Some)
+    }
+
+    /// 写入图片，同时提供 PNG 和位图两种格式
+    pub fn write_png(png: &[u8], image: &RgbaImage) -> Result<(), String> {
+        if (image.width() as u64) * (image.height() as u64) > super::MAX_PIXELS {
+            return Err("图片过大".to_string());
+        }
+        let dib = image_to_dib(image);
+
+        let clipboard = Clipboard::open()?;
+        if unsafe { EmptyClipboard() } == 0 {
+            return Err("清空剪贴板失败".to_string());
+        }
+        clipboard.set(CF_DIB as u32, &dib)?;
+        // 不支持 PNG 格式的程序会使用位图，失败不影响
+        if let Err(e) = clipboard.set(png_format(), png) {
+            tracing::debug!("写入 PNG 格式失败: {}", e);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,789 @@
+// 应用配置模块
+//
+// 配置以 JSON 形式保存在平台应用配置目录下的 config.json。
+// 每个子系统的设置是一个独立分区，文件中缺少的字段使用默认值，
+// 因此旧版本的配置文件可以直接被新版本读取。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+
+const CONFIG_FILE: &str = "config.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub backend: BackendConfig,
+    pub crash_reporting: CrashReportingConfig,
+    pub monitoring: MonitoringConfig,
+    pub performance: PerformanceConfig,
+    pub telemetry: TelemetryConfig,
+    pub heartbeat: HeartbeatConfig,
+    pub metrics: MetricsConfig,
+    pub proxy: ProxyConfig,
+    pub support: SupportConfig,
+    pub recording: RecordingConfig,
+    pub storage: StorageConfig,
+    pub disk: DiskConfig,
+    pub battery: BatteryConfig,
+    pub instance: InstanceConfig,
+    pub launch: LaunchConfig,
+    pub import_folder: ImportFolderConfig,
+    pub speech: SpeechConfig,
+    pub scanner: ScannerConfig,
+    pub printer: PrinterConfig,
+    pub day_close: DayCloseConfig,
+    pub backup: BackupConfig,
+    pub wake_on_lan: WakeOnLanConfig,
+    /// 允许调用的外部工具（名称 → 程序），只能在配置文件中修改
+    pub integrations: BTreeMap<String, IntegrationConfig>,
+    pub scheduler: SchedulerConfig,
+    pub shortcuts: ShortcutsConfig,
+}
+
+/// Backend 启动设置（修改后重启 Backend 生效）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendConfig {
+    /// 监听地址（未开启局域网模式时只能监听本机地址）
+    pub host: String,
+    /// 局域网模式：Backend 监听局域网地址，允许其他收银台和手机连接（仍需出示 Token）
+    pub lan_mode: bool,
+    /// 优先使用的端口
+    pub port: u16,
+    /// 优先端口被占用时，在 port..=port_range_end 中查找空闲端口
+    pub port_range_end: u16,
+    /// 数据库和上传文件所在目录，留空时使用 Backend 所在目录
+    pub data_dir: Option<String>,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            lan_mode: false,
+            port: 8000,
+            port_range_end: 8020,
+            data_dir: None,
+        }
+    }
+}
+
+/// 错误上报设置（默认关闭，需用户主动开启）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashReportingConfig {
+    pub enabled: bool,
+    /// Sentry 兼容的 DSN，留空时使用构建时内置的地址
+    pub dsn: Option<String>,
+    /// 启动时发现上次的崩溃报告后是否上传
+    pub upload_consent: UploadConsent,
+}
+
+/// 上传崩溃报告的授权（用户选择“记住”后不再询问）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadConsent {
+    #[default]
+    Ask,
+    Always,
+    Never,
+}
+
+/// Backend 监控设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitoringConfig {
+    /// 健康检查间隔（秒）
+    pub probe_interval_secs: u64,
+    /// p95 延迟超过该值（毫秒）时认为 Backend 变慢
+    pub slow_threshold_ms: u64,
+    /// 参与统计的最近采样数
+    pub latency_window: usize,
+    /// 经壳程序代理的 Backend 请求超过该耗时（毫秒）时告警，0 表示不检测
+    pub long_task_threshold_ms: u64,
+}
+
+impl Default for MonitoringConfig {
+    fn default() -> Self {
+        Self {
+            probe_interval_secs: 10,
+            slow_threshold_ms: 800,
+            latency_window: 120,
+            long_task_threshold_ms: 2000,
+        }
+    }
+}
+
+/// 性能模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceLevel {
+    /// 根据 CPU 核数和内存自动选择
+    #[default]
+    Auto,
+    Standard,
+    /// 低配电脑：降低后台检查频率、减少动画
+    Low,
+}
+
+/// 性能设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerformanceConfig {
+    pub level: PerformanceLevel,
+    /// 界面使用 GPU 硬件加速（显卡驱动有问题导致花屏时关闭，重启后生效）
+    pub hardware_acceleration: bool,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            level: PerformanceLevel::Auto,
+            hardware_acceleration: true,
+        }
+    }
+}
+
+/// 匿名使用统计设置（默认关闭，需用户主动开启）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// 上传地址，留空时使用构建时内置的地址
+    pub endpoint: Option<String>,
+    /// 上传间隔（小时）
+    pub upload_interval_hours: u64,
+    /// 随机生成的安装标识，与硬件和用户无关，关闭统计时清除
+    pub install_id: Option<String>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            upload_interval_hours: 24,
+            install_id: None,
+        }
+    }
+}
+
+/// 心跳文件设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    pub enabled: bool,
+    /// 写入间隔（秒）
+    pub interval_secs: u64,
+    /// 心跳文件路径，留空时写入应用数据目录下的 heartbeat.json
+    pub path: Option<String>,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 5,
+            path: None,
+        }
+    }
+}
+
+/// 本机指标接口设置（默认关闭）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    /// 监听端口（仅 127.0.0.1）
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9464,
+        }
+    }
+}
+
+/// 外部网络代理设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    /// 手动指定的代理地址，例如 http://10.0.0.1:8080
+    pub url: Option<String>,
+    /// 不经过代理的主机（NO_PROXY 格式，例如 .corp.local）
+    pub bypass: Vec<String>,
+}
+
+/// 代理模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// 使用环境变量或系统代理设置
+    #[default]
+    Auto,
+    /// 使用 url 中的代理
+    Manual,
+    /// 不使用代理
+    Off,
+}
+
+/// 远程协助设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SupportConfig {
+    /// 技术支持服务地址，留空时使用构建时内置的地址
+    pub endpoint: Option<String>,
+    /// 单次会话最长时间（分钟）
+    pub max_minutes: u64,
+    /// 诊断数据上传间隔（秒）
+    pub upload_interval_secs: u64,
+    /// 截图时打马赛克的区域（相对于主窗口内容区域，CSS 像素）
+    pub blur_regions: Vec<BlurRegion>,
+}
+
+impl Default for SupportConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            max_minutes: 60,
+            upload_interval_secs: 15,
+            blur_regions: Vec::new(),
+        }
+    }
+}
+
+/// 截图时打马赛克的区域，坐标相对于主窗口内容区域的左上角，单位为 CSS 像素
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlurRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 录屏设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    /// 每秒帧数
+    pub fps: u32,
+    /// 最长录制时间（秒），到时自动停止
+    pub max_secs: u64,
+    /// 画面超过该宽度时缩小（像素）
+    pub max_width: u32,
+    /// 文件超过该大小（MB）时自动停止
+    pub max_mb: u64,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            fps: 4,
+            max_secs: 30,
+            max_width: 1280,
+            max_mb: 20,
+        }
+    }
+}
+
+/// 存储健康检查设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub enabled: bool,
+    /// 检查间隔（分钟）
+    pub check_interval_mins: u64,
+    /// 写入测试超过该耗时（毫秒）时告警
+    pub slow_write_ms: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_mins: 30,
+            slow_write_ms: 2000,
+        }
+    }
+}
+
+/// 磁盘空间监控设置（阈值单位为 MB）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    pub enabled: bool,
+    /// 检查间隔（分钟）
+    pub check_interval_mins: u64,
+    /// 剩余空间低于该值时记录事件
+    pub notice_mb: u64,
+    /// 剩余空间低于该值时弹出通知
+    pub warning_mb: u64,
+    /// 剩余空间低于该值时显示横幅（数据库和备份可能写入失败）
+    pub critical_mb: u64,
+    /// 额外监控的目录（例如备份目录）
+    pub extra_paths: Vec<String>,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_mins: 5,
+            notice_mb: 10 * 1024,
+            warning_mb: 5 * 1024,
+            critical_mb: 1024,
+            extra_paths: Vec::new(),
+        }
+    }
+}
+
+/// 电池电量提醒设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatteryConfig {
+    pub enabled: bool,
+    /// 检查间隔（秒）
+    pub check_interval_secs: u64,
+    /// 使用电池且电量低于等于该值（%）时提醒
+    pub low_percent: u8,
+    /// 使用电池且电量低于等于该值（%）时视为即将关机
+    pub critical_percent: u8,
+}
+
+impl Default for BatteryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_secs: 30,
+            low_percent: 20,
+            critical_percent: 10,
+        }
+    }
+}
+
+/// 多用户会话设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstanceConfig {
+    /// 其他会话已在运行时，连接其 Backend 继续使用（关闭时提示后退出）
+    pub attach_existing: bool,
+}
+
+impl Default for InstanceConfig {
+    fn default() -> Self {
+        Self {
+            attach_existing: true,
+        }
+    }
+}
+
+/// 启动方式设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    /// 只启动 Backend 和托盘图标，不打开主窗口（作为局域网主服务器使用）
+    pub headless: bool,
+    /// 关闭主窗口时隐藏到托盘，Backend 继续运行（需从托盘菜单退出）
+    pub close_to_tray: bool,
+    /// 开机自启动时传入的参数（修改后重启应用并重新开启自启动生效）
+    pub autostart_args: Vec<String>,
+}
+
+impl Default for LaunchConfig {
+    fn default() -> Self {
+        Self {
+            headless: false,
+            close_to_tray: true,
+            autostart_args: vec![crate::tray::MINIMIZED_ARG.to_string()],
+        }
+    }
+}
+
+/// 导入热文件夹设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportFolderConfig {
+    pub enabled: bool,
+    /// 供应商同步工具存放价目表的文件夹
+    pub path: Option<String>,
+    /// 检查间隔（秒）
+    pub check_interval_secs: u64,
+}
+
+impl Default for ImportFolderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            check_interval_secs: 5,
+        }
+    }
+}
+
+/// 语音播报设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeechConfig {
+    pub enabled: bool,
+    /// 默认语音，留空时使用系统默认语音
+    pub voice: Option<String>,
+    /// 语速（-10 到 10，0 为正常）
+    pub rate: i32,
+    /// 音量（0-100）
+    pub volume: u8,
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            voice: None,
+            rate: 0,
+            volume: 100,
+        }
+    }
+}
+
+/// 扫码枪全局识别设置（仅 Windows，默认关闭）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScannerConfig {
+    pub enabled: bool,
+    /// 条码最少字符数
+    pub min_length: usize,
+    /// 扫码时相邻按键的最大间隔（毫秒）
+    pub max_interval_ms: u32,
+    /// 条码必须以回车结束
+    pub require_enter: bool,
+    /// 其他窗口在前台时拦截扫码的按键，不输入到该窗口
+    pub suppress: bool,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_length: 6,
+            max_interval_ms: 35,
+            require_enter: true,
+            suppress: true,
+        }
+    }
+}
+
+/// 小票打印机设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrinterConfig {
+    /// 打印机的连接方式，未配置时为 None
+    pub connection: Option<PrinterConnection>,
+    /// 纸宽（毫米），58 或 80
+    pub paper_width_mm: u32,
+    /// 打印完成后切纸（没有切刀的打印机关闭）
+    pub cut: bool,
+    /// 钱箱接在打印机的哪个针脚：0 为 2 号针脚（常见），1 为 5 号针脚
+    pub drawer_pin: u8,
+}
+
+impl Default for PrinterConfig {
+    fn default() -> Self {
+        Self {
+            connection: None,
+            paper_width_mm: 58,
+            cut: true,
+            drawer_pin: 0,
+        }
+    }
+}
+
+/// ESC/POS 打印机的连接方式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PrinterConnection {
+    /// 网口打印机（RAW 端口，一般为 9100）
+    Network { host: String, port: u16 },
+    /// 串口打印机（Windows 为 COM3，其他系统为 /dev/ttyUSB0）
+    Serial { port: String, baud_rate: u32 },
+    /// USB 打印机：Windows 下为已安装驱动的打印机名称（以 RAW 方式发送），
+    /// 其他系统为设备文件（/dev/usb/lp0）
+    Usb { name: String },
+    /// 蓝牙打印机（Windows）
+    Bluetooth { address: String },
+}
+
+/// 自动日结设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayCloseConfig {
+    /// 打烊时间（HH:MM），到点自动日结，为空时不自动执行
+    pub closing_time: Option<String>,
+    /// 是否打印日结报表（关闭时只保存 PDF）
+    pub print_report: bool,
+    /// 日结后是否备份数据库
+    pub backup: bool,
+    /// 保留的定时备份份数
+    pub keep_backups: usize,
+}
+
+impl Default for DayCloseConfig {
+    fn default() -> Self {
+        Self {
+            closing_time: None,
+            print_report: true,
+            backup: true,
+            keep_backups: 14,
+        }
+    }
+}
+
+/// 定时备份设置（执行时间在定时任务 backup 中设置，默认不执行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// 备份保存目录，留空时使用 <应用数据目录>/backups
+    pub dir: Option<String>,
+    /// 保留的定时备份份数
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { dir: None, keep: 10 }
+    }
+}
+
+/// 网络唤醒设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeOnLanConfig {
+    /// 默认广播地址
+    pub broadcast: String,
+    pub port: u16,
+    /// 开店时唤醒的设备
+    pub devices: Vec<WakeDevice>,
+}
+
+impl Default for WakeOnLanConfig {
+    fn default() -> Self {
+        Self {
+            broadcast: "255.255.255.255".to_string(),
+            port: 9,
+            devices: Vec::new(),
+        }
+    }
+}
+
+/// 可网络唤醒的设备（网络打印机、收银终端等）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeDevice {
+    pub name: String,
+    /// MAC 地址（AA:BB:CC:DD:EE:FF）
+    pub mac: String,
+    /// 设备所在网段的广播地址，为空时使用默认广播地址
+    pub broadcast: Option<String>,
+}
+
+/// 外部工具（钱箱工具、税控桥接程序等）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntegrationConfig {
+    pub description: String,
+    /// 程序路径
+    pub program: String,
+    /// 固定参数（放在页面传入的参数之前）
+    pub args: Vec<String>,
+    /// 页面传入的每个参数都必须完整匹配该正则，为空时不允许传入参数
+    pub arg_pattern: Option<String>,
+    pub working_dir: Option<String>,
+    /// 超时时间（秒），超时后强制结束
+    pub timeout_secs: u64,
+}
+
+impl Default for IntegrationConfig {
+    fn default() -> Self {
+        Self {
+            description: String::new(),
+            program: String::new(),
+            args: Vec::new(),
+            arg_pattern: None,
+            working_dir: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// 定时任务设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    /// 按任务名覆盖 cron 表达式，空字符串表示停用该任务
+    pub tasks: BTreeMap<String, String>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tasks: BTreeMap::new(),
+        }
+    }
+}
+
+/// 全局快捷键设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutsConfig {
+    /// 动作名 → 组合键，例如 "checkout": "Ctrl+Shift+K"
+    pub bindings: BTreeMap<String, String>,
+}
+
+/// 应用数据目录（保存支持包、统计数据等运行时文件）
+pub fn data_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    app_handle
+        .path_resolver()
+        .app_data_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join("smartmart"))
+}
+
+/// 配置存储
+pub struct ConfigStore {
+    path: PathBuf,
+    config: Mutex<AppConfig>,
+}
+
+impl ConfigStore {
+    /// 从应用配置目录加载配置，文件不存在或损坏时使用默认配置
+    pub fn load(tauri_config: &tauri::Config) -> Self {
+        let dir = tauri::api::path::app_config_dir(tauri_config)
+            .unwrap_or_else(|| std::env::temp_dir().join("smartmart"));
+        let path = dir.join(CONFIG_FILE);
+
+        let config = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("配置文件解析失败，使用默认配置: {:?} ({})", path, e);
+                AppConfig::default()
+            }),
+            Err(_) => AppConfig::default(),
+        };
+
+        tracing::info!("配置文件: {:?}", path);
+        Self {
+            path,
+            config: Mutex::new(config),
+        }
+    }
+
+    /// 当前配置的副本
+    pub fn get(&self) -> AppConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// 修改配置并保存到磁盘，返回修改后的配置
+    pub fn update<F: FnOnce(&mut AppConfig)>(&self, f: F) -> AppResult<AppConfig> {
+        let mut config = self.config.lock().unwrap();
+        let mut updated = config.clone();
+        f(&mut updated);
+        self.save(&updated)?;
+        *config = updated.clone();
+        Ok(updated)
+    }
+
+    fn save(&self, config: &AppConfig) -> AppResult<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| AppError::Config(format!("创建配置目录失败: {}", e)))?;
+        }
+        let content = serde_json::to_string_pretty(config)
+            .map_err(|e| AppError::Config(format!("序列化配置失败: {}", e)))?;
+
+        // 先写临时文件再替换，避免写到一半断电导致配置损坏
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)
+            .map_err(|e| AppError::Config(format!("保存配置失败: {}", e)))?;
+        std::fs::rename(&tmp, &self.path)
+            .map_err(|e| AppError::Config(format!("保存配置失败: {}", e)))
+    }
+}
+
+/// 检查前端提交的配置，返回第一个问题
+fn validate(config: &AppConfig) -> Result<(), String> {
+    let backend = &config.backend;
+    let Ok(host) = backend.host.parse::<std::net::IpAddr>() else {
+        return Err(format!("监听地址无效: {}", backend.host));
+    };
+    if !backend.lan_mode && !host.is_loopback() {
+        return Err(format!("监听 {} 需要开启局域网模式", host));
+    }
+    if backend.port == 0 {
+        return Err("端口不能为 0".to_string());
+    }
+    if backend.port_range_end < backend.port {
+        return Err("端口范围的结束端口不能小于优先端口".to_string());
+    }
+    if let Some(dir) = &backend.data_dir {
+        if !std::path::Path::new(dir).is_absolute() {
+            return Err(format!("数据目录必须是绝对路径: {}", dir));
+        }
+    }
+    Ok(())
+}
+
+/// 保留不能通过 set_config 修改的设置：外部工具只能在配置文件中修改；文件和目录路径只能通过
+/// 对话框选择的命令修改；上报地址只能在配置文件中修改；错误上报和使用统计的开关需要立即生效，
+/// 只能通过对应的命令修改
+fn keep_restricted(config: &mut AppConfig, current: &AppConfig) {
+    config.integrations = current.integrations.clone();
+    config.backend.data_dir = current.backend.data_dir.clone();
+    config.heartbeat.path = current.heartbeat.path.clone();
+    config.backup.dir = current.backup.dir.clone();
+    config.import_folder.enabled = current.import_folder.enabled;
+    config.import_folder.path = current.import_folder.path.clone();
+    config.crash_reporting = current.crash_reporting.clone();
+    config.telemetry.enabled = current.telemetry.enabled;
+    config.telemetry.endpoint = current.telemetry.endpoint.clone();
+    config.telemetry.install_id = current.telemetry.install_id.clone();
+    config.support.endpoint = current.support.endpoint.clone();
+}
+
+// Tauri 命令
+
+/// 完整配置（设置页的高级选项）
+#[tauri::command]
+pub fn get_config(config: tauri::State<'_, ConfigStore>) -> AppConfig {
+    config.get()
+}
+
+/// 保存完整配置。路径、上报地址、外部工具和需要立即生效的开关提交的值会被忽略（见 keep_restricted）。
+/// Backend 和启动方式的设置在重启 Backend / 应用后生效
+#[tauri::command]
+pub fn set_config(
+    config: AppConfig,
+    store: tauri::State<'_, ConfigStore>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<AppConfig> {
+    use tauri::Manager;
+
+    validate(&config).map_err(AppError::InvalidArgument)?;
+    let previous = store.get();
+    let updated = store.update(|c| {
+        let mut config = config;
+        keep_restricted(&mut config, c);
+        *c = config;
+    })?;
+    if updated.backend != previous.backend {
+        tracing::info!("Backend 设置已修改，重启 Backend 后生效: {:?}", updated.backend);
+    }
+    tracing::info!("配置已保存");
+    let _ = app_handle.emit_all("config://changed", &updated);
+    Ok(updated)
+}
@@ -0,0 +1,327 @@
+// 崩溃报告模块
+//
+// 程序 panic 时把崩溃信息（错误信息、调用栈、版本、最近日志）写入
+// <日志目录>/crashes/pending。下次启动时按用户的授权自动上传报告和崩溃前后的日志，
+// 或弹窗询问（可记住选择）；处理过的报告移动到 <日志目录>/crashes
+
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::Manager;
+
+use crate::config::{ConfigStore, UploadConsent};
+use crate::log_viewer::{self, LogQuery, LogSource};
+use crate::{logging, os, reporting};
+
+/// 崩溃报告中附带的日志行数
+const LOG_TAIL_LINES: usize = 200;
+
+/// 上传时附带崩溃前多长时间的日志（分钟）
+const LOG_SLICE_BEFORE_MINUTES: i64 = 10;
+
+/// 上传时每个来源最多附带的日志条数
+const LOG_SLICE_LINES: usize = 500;
+
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+static BACKEND_VERSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// 安装 panic hook（在日志系统初始化之后调用）
+pub fn install(log_dir: &Path) {
+    let _ = CRASH_DIR.set(log_dir.join("crashes"));
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "未知错误".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "未知位置".to_string());
+
+        tracing::error!("程序崩溃: {} ({})", message, location);
+
+        match write_report(&message, &location) {
+            Ok(path) => tracing::error!("崩溃报告已保存: {:?}", path),
+            Err(e) => tracing::error!("保存崩溃报告失败: {}", e),
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// 记录 Backend 版本，写入之后的崩溃报告
+pub fn set_backend_version(version: String) {
+    if let Ok(mut current) = BACKEND_VERSION.lock() {
+        *current = Some(version);
+    }
+}
+
+/// 已知的 Backend 版本
+pub fn backend_version() -> Option<String> {
+    BACKEND_VERSION.lock().ok().and_then(|v| v.clone())
+}
+
+/// 崩溃报告目录
+pub fn crash_dir() -> Option<&'static Path> {
+    CRASH_DIR.get().map(PathBuf::as_path)
+}
+
+fn pending_dir() -> Option<PathBuf> {
+    crash_dir().map(|dir| dir.join("pending"))
+}
+
+fn write_report(message: &str, location: &str) -> Result<PathBuf, String> {
+    let dir = pending_dir().ok_or("崩溃报告目录未初始化")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+
+    let now = chrono::Local::now();
+    let backend_version = backend_version().unwrap_or_else(|| "未知".to_string());
+    let thread = std::thread::current();
+
+    let mut report = String::new();
+    let _ = writeln!(report, "SmartMart 崩溃报告");
+    let _ = writeln!(report, "时间: {}", now.to_rfc3339());
+    let _ = writeln!(report, "应用版本: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Backend 版本: {}", backend_version);
+    let _ = writeln!(
+        report,
+        "操作系统: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(report, "线程: {}", thread.name().unwrap_or("<未命名>"));
+    let _ = writeln!(report, "位置: {}", location);
+    let _ = writeln!(report, "信息: {}", message);
+    let _ = writeln!(report);
+    let _ = writeln!(report, "== 调用栈 ==");
+    let _ = writeln!(report, "{}", Backtrace::force_capture());
+    let _ = writeln!(report, "== 最近日志 ==");
+    for line in logging::recent_lines(LOG_TAIL_LINES) {
+        let _ = writeln!(report, "{}", line);
+    }
+
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, report).map_err(|e| format!("写入文件失败: {}", e))?;
+    Ok(path)
+}
+
+/// 上次运行留下的、尚未提示过的崩溃报告
+pub fn pending_reports() -> Vec<PathBuf> {
+    let Some(dir) = pending_dir() else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// 把报告从 pending 移到已提示目录
+fn mark_seen(reports: &[PathBuf]) {
+    let Some(dir) = crash_dir() else {
+        return;
+    };
+
+    for report in reports {
+        if let Some(name) = report.file_name() {
+            if let Err(e) = std::fs::rename(report, dir.join(name)) {
+                tracing::warn!("移动崩溃报告失败: {:?} ({})", report, e);
+            }
+        }
+    }
+}
+
+/// 报告文件名中的崩溃时间（crash-YYYYMMDD-HHMMSS.txt）
+fn report_time(report: &Path) -> Option<chrono::DateTime<chrono::Local>> {
+    let stem = report.file_stem()?.to_string_lossy();
+    let time = stem.strip_prefix("crash-")?;
+    let naive = chrono::NaiveDateTime::parse_from_str(time, "%Y%m%d-%H%M%S").ok()?;
+    naive.and_local_timezone(chrono::Local).single()
+}
+
+/// 崩溃前后一段时间的壳程序和 Backend 日志
+fn log_slice(report: &Path) -> String {
+    let Some(time) = report_time(report) else {
+        return String::new();
+    };
+
+    let mut slice = String::new();
+    for (source, title) in [
+        (LogSource::Shell, "壳程序"),
+        (LogSource::Backend, "Backend"),
+    ] {
+        let query = LogQuery {
+            source,
+            since: Some((time - chrono::Duration::minutes(LOG_SLICE_BEFORE_MINUTES)).to_rfc3339()),
+            until: Some((time + chrono::Duration::minutes(1)).to_rfc3339()),
+            page_size: LOG_SLICE_LINES,
+            ..Default::default()
+        };
+        let Ok(page) = log_viewer::query(&query) else {
+            continue;
+        };
+
+        let _ = writeln!(slice, "== {}日志 ==", title);
+        for entry in page.entries.iter().rev() {
+            let _ = writeln!(
+                slice,
+                "{} {} {}: {}",
+                entry.timestamp.as_deref().unwrap_or_default(),
+                entry.level,
+                entry.target.as_deref().unwrap_or_default(),
+                entry.message
+            );
+        }
+    }
+    slice
+}
+
+/// 在后台上传崩溃报告，成功的报告移出 pending
+fn upload_reports(app_handle: &tauri::AppHandle, reports: Vec<PathBuf>) {
+    let settings = app_handle.state::<ConfigStore>().get().crash_reporting;
+    let spawned = std::thread::Builder::new()
+        .name("crash-upload".into())
+        .spawn(move || {
+            for report in reports {
+                let content = match std::fs::read_to_string(&report) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        tracing::warn!("读取崩溃报告失败: {:?} ({})", report, e);
+                        continue;
+                    }
+                };
+                let name = report
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                match reporting::upload_crash_report(
+                    &settings,
+                    &name,
+                    &content,
+                    &log_slice(&report),
+                ) {
+                    Ok(()) => {
+                        tracing::info!("崩溃报告已上传: {}", name);
+                        mark_seen(std::slice::from_ref(&report));
+                    }
+                    // 保留在 pending 中，下次启动时重试
+                    Err(e) => tracing::warn!("上传崩溃报告失败: {} ({})", name, e),
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动崩溃报告上传线程失败: {}", e);
+    }
+}
+
+fn remember_consent(app_handle: &tauri::AppHandle, consent: UploadConsent) {
+    let config = app_handle.state::<ConfigStore>();
+    if let Err(e) = config.update(|c| c.crash_reporting.upload_consent = consent) {
+        tracing::warn!("{}", e);
+    }
+}
+
+/// 启动时检查上次运行是否崩溃：按用户授权自动上传，或询问用户
+pub fn check_previous(app_handle: &tauri::AppHandle) {
+    let reports = pending_reports();
+    if reports.is_empty() {
+        return;
+    }
+
+    tracing::warn!("发现 {} 份未提交的崩溃报告", reports.len());
+
+    match app_handle
+        .state::<ConfigStore>()
+        .get()
+        .crash_reporting
+        .upload_consent
+    {
+        UploadConsent::Always => upload_reports(app_handle, reports),
+        UploadConsent::Never => ask_open_folder(app_handle, reports),
+        UploadConsent::Ask => ask_upload(app_handle, reports),
+    }
+}
+
+/// 询问是否上传，并询问是否记住选择
+fn ask_upload(app_handle: &tauri::AppHandle, reports: Vec<PathBuf>) {
+    let window = app_handle.get_window("main");
+    let message = format!(
+        "SmartMart 上次运行时异常退出，已生成 {} 份崩溃报告。\n\n是否将报告和相关日志上传给技术支持？上传前会清理个人信息。",
+        reports.len()
+    );
+
+    let app_handle = app_handle.clone();
+    tauri::api::dialog::ask(
+        window.as_ref(),
+        "SmartMart 崩溃报告",
+        message,
+        move |upload| {
+            if upload {
+                upload_reports(&app_handle, reports);
+            } else {
+                mark_seen(&reports);
+            }
+
+            let window = app_handle.get_window("main");
+            let consent = if upload {
+                UploadConsent::Always
+            } else {
+                UploadConsent::Never
+            };
+            let remember = if upload {
+                "以后出现崩溃时自动上传，不再询问？"
+            } else {
+                "以后不再询问是否上传崩溃报告？"
+            };
+            let app_handle = app_handle.clone();
+            tauri::api::dialog::ask(
+                window.as_ref(),
+                "SmartMart 崩溃报告",
+                remember,
+                move |yes| {
+                    if yes {
+                        remember_consent(&app_handle, consent);
+                    }
+                },
+            );
+        },
+    );
+}
+
+/// 不上传时提示打开报告所在文件夹
+fn ask_open_folder(app_handle: &tauri::AppHandle, reports: Vec<PathBuf>) {
+    let window = app_handle.get_window("main");
+    let message = format!(
+        "SmartMart 上次运行时异常退出，已生成 {} 份崩溃报告。\n\n是否打开报告所在文件夹，以便发送给技术支持？",
+        reports.len()
+    );
+
+    tauri::api::dialog::ask(
+        window.as_ref(),
+        "SmartMart 崩溃报告",
+        message,
+        move |open_folder| {
+            mark_seen(&reports);
+            if open_folder {
+                if let Some(dir) = crash_dir() {
+                    if let Err(e) = os::open_path(dir) {
+                        tracing::warn!("{}", e);
+                    }
+                }
+            }
+        },
+    );
+}
@@ -0,0 +1,150 @@
+// cron 表达式
+//
+// 支持标准的 5 段格式：分 时 日 月 周，例如 "30 22 * * *"（每天 22:30）、
+// "0 */4 * * 1-5"（工作日每 4 小时）。每段支持 *、列表（1,15）、范围（1-5）和步长（*/10、8-18/2），
+// 周日可写作 0 或 7。另外支持 @hourly、@daily、@weekly、@monthly 简写。
+//
+// 与常见实现一致：日和周都不是 * 时，满足其一即触发。
+
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Timelike};
+
+/// 查找下次触发时间时最多向后查找的天数
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_any: bool,
+    weekdays_any: bool,
+}
+
+/// 解析一段，返回匹配值的位图
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("无效的步长: {}", part))?;
+                if step == 0 {
+                    return Err(format!("无效的步长: {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = start.parse().map_err(|_| format!("无效的范围: {}", part))?;
+            let end = end.parse().map_err(|_| format!("无效的范围: {}", part))?;
+            (start, end)
+        } else {
+            let value = range.parse().map_err(|_| format!("无效的值: {}", part))?;
+            // 单个值带步长（例如 5/15）等价于 5-最大值/15
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("超出范围 {}-{}: {}", min, max, part));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "cron 表达式应为 5 段（分 时 日 月 周）: {}",
+                expression
+            ));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        // 7 和 0 都表示周日
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            days_any: day == "*",
+            weekdays_any: weekday == "*",
+        })
+    }
+
+    fn matches_day(&self, time: &DateTime<Local>) -> bool {
+        let day = self.days & (1 << time.day()) != 0;
+        let weekday = self.weekdays & (1 << time.weekday().num_days_from_sunday()) != 0;
+        match (self.days_any, self.weekdays_any) {
+            (true, true) => true,
+            (false, true) => day,
+            (true, false) => weekday,
+            (false, false) => day || weekday,
+        }
+    }
+
+    /// 给定时间（精确到分钟）是否触发
+    pub fn matches(&self, time: &DateTime<Local>) -> bool {
+        self.minutes & (1 << time.minute()) != 0
+            && self.hours & (1 << time.hour()) != 0
+            && self.months & (1 << time.month()) != 0
+            && self.matches_day(time)
+    }
+
+    /// after 之后（不含）的下一次触发时间
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = Local
+            .with_ymd_and_hms(
+                after.year(),
+                after.month(),
+                after.day(),
+                after.hour(),
+                after.minute(),
+                0,
+            )
+            .earliest()?
+            + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        let mut time = start;
+        while time < limit {
+            if self.months & (1 << time.month()) == 0 || !self.matches_day(&time) {
+                // 跳到次日零点
+                let next_day = time.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                time = Local.from_local_datetime(&next_day).earliest()?;
+                continue;
+            }
+            if self.hours & (1 << time.hour()) == 0 {
+                time = time - Duration::minutes(time.minute() as i64) + Duration::hours(1);
+                continue;
+            }
+            if self.matches(&time) {
+                return Some(time);
+            }
+            time += Duration::minutes(1);
+        }
+        None
+    }
+}
@@ -0,0 +1,259 @@
+// 自动日结
+//
+// 营业结束后需要依次完成：日结结账、打印日结报表（Z 报表）、备份数据。店员经常忘记，
+// 因此在设置的打烊时间（day_close.closing_time）由定时任务 day_close 自动执行：
+// 1. 调用 Backend 的 POST /reports/day_close 记录日结（同一营业日重复执行不会重复记录）
+// 2. 生成日结报表 PDF，print_report 开启时同时打印
+// 3. backup 开启时备份数据到 <应用数据目录>/backups（与手动备份相同的压缩包，可以直接恢复），
+//    只保留最近 keep_backups 份
+//
+// 某一步失败不影响后续步骤；有步骤失败时弹出系统通知，结果发出 dayclose://finished 事件。
+// 日结的营业日期为执行当天的日期，与 Backend 按自然日统计的报表一致。
+
+use chrono::Timelike;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::backend::BackendProcess;
+use crate::config::{self, ConfigStore, DayCloseConfig};
+use crate::events::{self, EventKind};
+use crate::{backup, http, notify, report_pdf, scheduler, shutdown};
+
+/// 定时备份文件名前缀
+const BACKUP_PREFIX: &str = "nightly-";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub step: &'static str,
+    pub ok: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayCloseResult {
+    /// 营业日期
+    pub date: String,
+    pub ok: bool,
+    pub steps: Vec<StepResult>,
+}
+
+/// 备份目录
+pub fn backups_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join("backups")
+}
+
+/// 当前时间对应的营业日期（Backend 按自然日统计）
+fn business_date() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// 打烊时间（HH:MM）转为每天执行的 cron 表达式
+fn cron_for(closing_time: &str) -> Option<String> {
+    let time = chrono::NaiveTime::parse_from_str(closing_time.trim(), "%H:%M").ok()?;
+    Some(format!("{} {} * * *", time.minute(), time.hour()))
+}
+
+fn backend_port(app_handle: &tauri::AppHandle) -> u16 {
+    app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port()
+}
+
+/// 读取 Backend 返回的错误说明
+pub fn error_detail(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(status, response) => response
+            .into_json::<serde_json::Value>()
+            .ok()
+            .and_then(|body| {
+                body.get("detail")
+                    .and_then(|d| d.as_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| format!("HTTP {}", status)),
+        e => e.to_string(),
+    }
+}
+
+fn close_day(port: u16, date: &str) -> Result<String, String> {
+    let result: serde_json::Value = http::local()
+        .post(&format!("http://127.0.0.1:{}/reports/day_close", port))
+        .query("date", date)
+        .call()
+        .map_err(|e| format!("日结失败: {}", error_detail(e)))?
+        .into_json()
+        .map_err(|e| format!("解析日结结果失败: {}", e))?;
+
+    let revenue = result
+        .get("total_revenue")
+        .and_then(|v| v.as_f64())
+        .unwrap_or_default();
+    let orders = result
+        .get("order_count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or_default();
+    let already = result
+        .get("already_closed")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    Ok(format!(
+        "{}销售额 {:.2}，订单 {} 笔",
+        if already { "已日结过，" } else { "" },
+        revenue,
+        orders
+    ))
+}
+
+fn print_report(app_handle: &tauri::AppHandle, date: &str, print: bool) -> Result<String, String> {
+    let report = report_pdf::render_and_record(app_handle, date, print)?;
+    if print && !report.printed {
+        return Err(format!("报表已保存但打印失败: {}", report.path));
+    }
+    Ok(if report.printed {
+        format!("已打印并保存: {}", report.path)
+    } else {
+        format!("已保存: {}", report.path)
+    })
+}
+
+/// 删除 dir 中以 prefix 开头的多余旧备份，保留最近 keep 份
+pub fn prune_backups(dir: &Path, prefix: &str, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(prefix))
+        })
+        .collect();
+    // 文件名带日期和时间，按名称排序即按时间排序
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep.max(1));
+    for path in backups.into_iter().take(excess) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!("删除旧备份失败: {:?} ({})", path, e);
+        }
+    }
+}
+
+fn backup(app_handle: &tauri::AppHandle, keep: usize) -> Result<String, String> {
+    let _busy = shutdown::busy("nightly_backup");
+    let info = backup::create_in(app_handle, &backups_dir(app_handle), BACKUP_PREFIX, keep)?;
+    Ok(format!(
+        "已备份: {} ({:.1} MB)",
+        info.path,
+        info.size as f64 / 1024.0 / 1024.0
+    ))
+}
+
+fn step(step: &'static str, result: Result<String, String>) -> StepResult {
+    match result {
+        Ok(message) => StepResult {
+            step,
+            ok: true,
+            message,
+        },
+        Err(message) => {
+            tracing::warn!("日结步骤 {} 失败: {}", step, message);
+            StepResult {
+                step,
+                ok: false,
+                message,
+            }
+        }
+    }
+}
+
+/// 依次执行日结的各个步骤
+fn run(app_handle: &tauri::AppHandle, settings: &DayCloseConfig) -> DayCloseResult {
+    let date = business_date();
+    let port = backend_port(app_handle);
+    tracing::info!("开始日结: {}", date);
+
+    let mut steps = vec![step("close_day", close_day(port, &date))];
+    steps.push(step(
+        "z_report",
+        print_report(app_handle, &date, settings.print_report),
+    ));
+    if settings.backup {
+        steps.push(step("backup", backup(app_handle, settings.keep_backups)));
+    }
+
+    let result = DayCloseResult {
+        ok: steps.iter().all(|s| s.ok),
+        date,
+        steps,
+    };
+    events::record(
+        if result.ok {
+            EventKind::State
+        } else {
+            EventKind::Error
+        },
+        if result.ok {
+            "日结完成"
+        } else {
+            "日结未全部完成"
+        },
+        serde_json::json!(result),
+    );
+    if !result.ok {
+        let failed: Vec<&str> = result
+            .steps
+            .iter()
+            .filter(|s| !s.ok)
+            .map(|s| s.message.as_str())
+            .collect();
+        notify::show(
+            app_handle,
+            "自动日结未完成",
+            &format!("{}\n请手动完成日结", failed.join("\n")),
+        );
+    }
+    let _ = app_handle.emit_all("dayclose://finished", &result);
+    result
+}
+
+/// 定时任务：自动日结
+fn run_task(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let settings = app_handle.state::<ConfigStore>().get().day_close;
+    let result = run(app_handle, &settings);
+    let summary = result
+        .steps
+        .iter()
+        .map(|s| s.message.as_str())
+        .collect::<Vec<_>>()
+        .join("；");
+    if result.ok {
+        Ok(summary)
+    } else {
+        Err(summary)
+    }
+}
+
+/// 按设置的打烊时间登记日结定时任务（打烊时间为空或格式错误时默认不执行）
+pub fn register_tasks(config: &ConfigStore) {
+    let closing_time = config.get().day_close.closing_time;
+    let cron = closing_time.as_deref().and_then(|time| {
+        let cron = cron_for(time);
+        if cron.is_none() {
+            tracing::warn!("打烊时间格式错误（应为 HH:MM）: {}", time);
+        }
+        cron
+    });
+    // 任务在整个运行期间都需要默认的 cron 表达式
+    let cron: Option<&'static str> = cron.map(|cron| &*Box::leak(cron.into_boxed_str()));
+    scheduler::register(
+        "day_close",
+        "自动日结（结账、打印日结报表、备份数据）",
+        cron,
+        run_task,
+    );
+}
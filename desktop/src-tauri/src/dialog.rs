@@ -0,0 +1,133 @@
+// 文件对话框
+//
+// 导入、导出和备份位置等设置通过系统原生对话框选择路径，不再手动输入。
+// 用户在对话框中选择的路径会被记下，只有这些路径可以交给壳程序读写（例如 save_text_file、导入商品），
+// 避免页面直接读写任意文件。打开的文件和文件夹、保存位置分开记录：
+// 写入文件只接受保存对话框选择的位置，避免覆盖用户只是选来打开或导入的文件。
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::api::dialog::blocking::FileDialogBuilder;
+use tauri::Manager;
+
+use crate::error::{AppError, AppResult};
+
+/// 最多记住多少个选择过的路径
+const MAX_SELECTED: usize = 20;
+
+/// 用户通过 pick_file / pick_folder 选择过的路径
+static SELECTED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// 用户通过 save_file 选择过的保存位置
+static SAVE_SELECTED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileFilter {
+    /// 显示名称，例如 "CSV 文件"
+    pub name: String,
+    /// 扩展名（不含点），例如 ["csv"]
+    pub extensions: Vec<String>,
+}
+
+fn remember(list: &Mutex<Vec<PathBuf>>, path: &Path) {
+    let mut selected = list.lock().unwrap();
+    selected.retain(|p| p != path);
+    selected.push(path.to_path_buf());
+    if selected.len() > MAX_SELECTED {
+        selected.remove(0);
+    }
+}
+
+/// 路径是否由用户在打开文件或文件夹的对话框中选择
+pub fn is_selected(path: &Path) -> bool {
+    SELECTED.lock().unwrap().iter().any(|p| p == path)
+}
+
+/// 路径是否由用户在保存对话框中选择
+pub fn is_save_selected(path: &Path) -> bool {
+    SAVE_SELECTED.lock().unwrap().iter().any(|p| p == path)
+}
+
+fn builder(
+    app_handle: &tauri::AppHandle,
+    filters: &[FileFilter],
+    directory: Option<&str>,
+) -> FileDialogBuilder {
+    let mut builder = FileDialogBuilder::new();
+    // 以主窗口为父窗口，对话框关闭前不能操作主窗口
+    if let Some(window) = app_handle.get_window("main") {
+        builder = builder.set_parent(&window);
+    }
+    for filter in filters {
+        let extensions: Vec<&str> = filter.extensions.iter().map(String::as_str).collect();
+        builder = builder.add_filter(&filter.name, &extensions);
+    }
+    if let Some(directory) = directory.filter(|d| !d.is_empty()) {
+        builder = builder.set_directory(directory);
+    }
+    builder
+}
+
+fn finish(list: &Mutex<Vec<PathBuf>>, path: Option<PathBuf>) -> Option<String> {
+    let path = path?;
+    remember(list, &path);
+    Some(path.to_string_lossy().into_owned())
+}
+
+// Tauri 命令
+
+/// 选择一个文件，用户取消时返回 null
+#[tauri::command]
+pub async fn pick_file(
+    filters: Option<Vec<FileFilter>>,
+    directory: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Option<String> {
+    let filters = filters.unwrap_or_default();
+    finish(
+        &SELECTED,
+        builder(&app_handle, &filters, directory.as_deref()).pick_file(),
+    )
+}
+
+/// 选择一个文件夹，用户取消时返回 null
+#[tauri::command]
+pub async fn pick_folder(
+    directory: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Option<String> {
+    finish(
+        &SELECTED,
+        builder(&app_handle, &[], directory.as_deref()).pick_folder(),
+    )
+}
+
+/// 选择保存位置，用户取消时返回 null
+#[tauri::command]
+pub async fn save_file(
+    default_name: Option<String>,
+    filters: Option<Vec<FileFilter>>,
+    app_handle: tauri::AppHandle,
+) -> Option<String> {
+    let filters = filters.unwrap_or_default();
+    let mut builder = builder(&app_handle, &filters, None);
+    if let Some(name) = default_name.as_deref().filter(|n| !n.is_empty()) {
+        builder = builder.set_file_name(name);
+    }
+    finish(&SAVE_SELECTED, builder.save_file())
+}
+
+/// 把文字内容写入 save_file 选择的位置（例如导出的 CSV）
+#[tauri::command]
+pub async fn save_text_file(path: String, content: String) -> AppResult<()> {
+    let path = PathBuf::from(path);
+    if !is_save_selected(&path) {
+        return Err(AppError::InvalidArgument(
+            "只能保存到通过保存对话框选择的位置".to_string(),
+        ));
+    }
+    std::fs::write(&path, content).map_err(|e| AppError::Io(format!("保存文件失败: {}", e)))?;
+    tracing::info!("已保存文件: {:?}", path);
+    Ok(())
+}
@@ -0,0 +1,216 @@
+// 磁盘空间
+//
+// 查询某个路径所在分区的容量，供健康检查和存储告警使用。
+//
+// 监控线程定期检查应用数据、数据库和备份目录所在分区的剩余空间，低于设置的阈值时逐级告警：
+// - notice    记录事件
+// - warning   另外弹出系统通知
+// - critical  另外由前端显示无法关闭的横幅，直到空间恢复
+// 级别变化时发出 disk://space 事件，payload 为该分区的状态。磁盘写满是数据库突然报错的首要原因。
+
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::Disks;
+use tauri::Manager;
+
+use crate::config::{self, ConfigStore, DiskConfig};
+use crate::events::{self, EventKind};
+use crate::{backend, notify, performance};
+
+const MB: u64 = 1024 * 1024;
+
+/// 各分区上次的告警级别（按挂载点）
+static LEVELS: Mutex<BTreeMap<String, SpaceLevel>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpaceLevel {
+    Ok,
+    Notice,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeStatus {
+    /// 位于该分区上的受监控目录
+    pub paths: Vec<String>,
+    pub space: DiskSpace,
+    pub level: SpaceLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskSpace {
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl DiskSpace {
+    /// 可用空间占比（0-100）
+    pub fn available_percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.available_bytes as f64 * 100.0 / self.total_bytes as f64
+    }
+}
+
+/// 路径所在分区的容量（取挂载点最长匹配的分区）
+pub fn space_for(path: &Path) -> Option<DiskSpace> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DiskSpace {
+            mount_point: disk.mount_point().to_string_lossy().into_owned(),
+            total_bytes: disk.total_space(),
+            available_bytes: disk.available_space(),
+        })
+}
+
+/// 挂载点对应的设备名（例如 /dev/sda1）
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn device_for(mount_point: &str) -> Option<String> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .find(|disk| disk.mount_point() == Path::new(mount_point))
+        .map(|disk| disk.name().to_string_lossy().into_owned())
+}
+
+/// 以 GB 为单位显示
+pub fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+/// 按设置的阈值判断剩余空间的告警级别
+pub fn level_for(space: &DiskSpace, settings: &DiskConfig) -> SpaceLevel {
+    let available = space.available_bytes;
+    if available < settings.critical_mb * MB {
+        SpaceLevel::Critical
+    } else if available < settings.warning_mb * MB {
+        SpaceLevel::Warning
+    } else if available < settings.notice_mb * MB {
+        SpaceLevel::Notice
+    } else {
+        SpaceLevel::Ok
+    }
+}
+
+/// 需要监控的目录：应用数据目录、数据库目录和设置中的额外目录（例如备份目录）
+fn watched_paths(app_handle: &tauri::AppHandle, settings: &DiskConfig) -> Vec<PathBuf> {
+    let mut paths = vec![config::data_dir(app_handle)];
+    paths.extend(backend::database_dir());
+    paths.extend(settings.extra_paths.iter().map(PathBuf::from));
+    paths
+}
+
+/// 检查所有受监控目录所在分区（同一分区只返回一项）
+pub fn check(app_handle: &tauri::AppHandle) -> Vec<VolumeStatus> {
+    let settings = app_handle.state::<ConfigStore>().get().disk;
+    let mut volumes: Vec<VolumeStatus> = Vec::new();
+    for path in watched_paths(app_handle, &settings) {
+        let Some(space) = space_for(&path) else {
+            continue;
+        };
+        let path = path.to_string_lossy().into_owned();
+        if let Some(volume) = volumes
+            .iter_mut()
+            .find(|v| v.space.mount_point == space.mount_point)
+        {
+            volume.paths.push(path);
+            continue;
+        }
+        let level = level_for(&space, &settings);
+        let message = format!(
+            "{} 剩余 {}（{:.0}%）",
+            space.mount_point,
+            format_gb(space.available_bytes),
+            space.available_percent()
+        );
+        volumes.push(VolumeStatus {
+            paths: vec![path],
+            space,
+            level,
+            message,
+        });
+    }
+    volumes
+}
+
+/// 记录各分区的级别，变化时发出事件，变差时按级别告警
+fn update(app_handle: &tauri::AppHandle, volumes: &[VolumeStatus]) {
+    for volume in volumes {
+        let previous = LEVELS
+            .lock()
+            .unwrap()
+            .insert(volume.space.mount_point.clone(), volume.level)
+            .unwrap_or(SpaceLevel::Ok);
+        if previous == volume.level {
+            continue;
+        }
+        let _ = app_handle.emit_all("disk://space", volume);
+        if volume.level < previous {
+            tracing::info!("磁盘空间已恢复: {}", volume.message);
+            continue;
+        }
+
+        tracing::warn!("磁盘空间不足 ({:?}): {}", volume.level, volume.message);
+        events::record(
+            EventKind::State,
+            format!("磁盘空间不足: {}", volume.message),
+            json!({ "level": volume.level, "available_bytes": volume.space.available_bytes }),
+        );
+        match volume.level {
+            SpaceLevel::Critical => notify::show(
+                app_handle,
+                "磁盘空间严重不足",
+                &format!("{}，数据库可能无法写入，请立即清理磁盘", volume.message),
+            ),
+            SpaceLevel::Warning => notify::show(
+                app_handle,
+                "磁盘空间不足",
+                &format!("{}，请尽快清理磁盘", volume.message),
+            ),
+            SpaceLevel::Notice | SpaceLevel::Ok => {}
+        }
+    }
+}
+
+/// 启动定期检查线程
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("disk-monitor".into())
+        .spawn(move || loop {
+            let settings = app_handle.state::<ConfigStore>().get().disk;
+            if settings.enabled {
+                update(&app_handle, &check(&app_handle));
+            }
+            std::thread::sleep(performance::interval(Duration::from_secs(
+                settings.check_interval_mins.max(1) * 60,
+            )));
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动磁盘空间检查线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 立即检查受监控目录所在分区的剩余空间
+#[tauri::command]
+pub async fn get_disk_space(app_handle: tauri::AppHandle) -> Vec<VolumeStatus> {
+    let volumes = check(&app_handle);
+    update(&app_handle, &volumes);
+    volumes
+}
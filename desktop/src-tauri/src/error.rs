@@ -0,0 +1,92 @@
+// 命令错误类型
+//
+// 所有 Tauri 命令统一返回 AppError，序列化为
+// `{ "code": "BACKEND_UNAVAILABLE", "message_key": "error.backend_unavailable", "detail": "..." }`，
+// 前端根据 code 分支处理、根据 message_key 显示本地化文案，detail 为便于排查的原始信息。
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum AppError {
+    /// Backend 未运行或请求失败
+    BackendUnavailable(String),
+    /// Backend 进程启动失败
+    BackendStartFailed(String),
+    /// 参数错误
+    InvalidArgument(String),
+    /// 配置读写失败
+    Config(String),
+    /// 文件读写失败
+    Io(String),
+    /// 访问外部服务失败
+    Network(String),
+    /// 子系统尚未初始化
+    NotInitialized(String),
+    /// 开机自启动设置失败
+    Autostart(String),
+    /// 错误上报初始化失败
+    CrashReporting(String),
+    /// 其他内部错误
+    Internal(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// 稳定的错误码，前端据此分支处理
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BackendUnavailable(_) => "BACKEND_UNAVAILABLE",
+            AppError::BackendStartFailed(_) => "BACKEND_START_FAILED",
+            AppError::InvalidArgument(_) => "INVALID_ARGUMENT",
+            AppError::Config(_) => "CONFIG_ERROR",
+            AppError::Io(_) => "IO_ERROR",
+            AppError::Network(_) => "NETWORK_ERROR",
+            AppError::NotInitialized(_) => "NOT_INITIALIZED",
+            AppError::Autostart(_) => "AUTOSTART_FAILED",
+            AppError::CrashReporting(_) => "CRASH_REPORTING_FAILED",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// 前端文案的翻译键
+    pub fn message_key(&self) -> String {
+        format!("error.{}", self.code().to_ascii_lowercase())
+    }
+
+    /// 原始错误信息
+    pub fn detail(&self) -> &str {
+        match self {
+            AppError::BackendUnavailable(detail)
+            | AppError::BackendStartFailed(detail)
+            | AppError::InvalidArgument(detail)
+            | AppError::Config(detail)
+            | AppError::Io(detail)
+            | AppError::Network(detail)
+            | AppError::NotInitialized(detail)
+            | AppError::Autostart(detail)
+            | AppError::CrashReporting(detail)
+            | AppError::Internal(detail) => detail,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.detail())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message_key", &self.message_key())?;
+        state.serialize_field("detail", self.detail())?;
+        state.end()
+    }
+}
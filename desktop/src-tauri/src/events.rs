@@ -0,0 +1,96 @@
+// 最近事件记录
+//
+// 在内存中保留最近几百条壳程序事件（状态变化、扫码、打印、错误等），
+// 出问题后诊断页面可以直接查看“刚才发生了什么”，无需翻日志。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 保留的事件数
+const CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// Backend 启动、停止、变慢等
+    Backend,
+    /// 程序状态变化（设置修改、会话开关等）
+    State,
+    Scan,
+    Print,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub id: u64,
+    pub timestamp: String,
+    pub kind: EventKind,
+    pub message: String,
+    pub data: Value,
+}
+
+struct History {
+    next_id: u64,
+    events: VecDeque<Event>,
+}
+
+static HISTORY: Mutex<History> = Mutex::new(History {
+    next_id: 1,
+    events: VecDeque::new(),
+});
+
+/// 记录一条事件
+pub fn record(kind: EventKind, message: impl Into<String>, data: Value) {
+    let Ok(mut history) = HISTORY.lock() else {
+        return;
+    };
+    let id = history.next_id;
+    history.next_id += 1;
+    history.events.push_back(Event {
+        id,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        kind,
+        message: message.into(),
+        data,
+    });
+    while history.events.len() > CAPACITY {
+        history.events.pop_front();
+    }
+}
+
+/// 最近的事件（最新的在前）
+pub fn recent(limit: usize, kind: Option<EventKind>, after_id: Option<u64>) -> Vec<Event> {
+    let Ok(history) = HISTORY.lock() else {
+        return Vec::new();
+    };
+    history
+        .events
+        .iter()
+        .rev()
+        .filter(|e| kind.map_or(true, |kind| e.kind == kind))
+        .filter(|e| after_id.map_or(true, |id| e.id > id))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+// Tauri 命令
+
+/// 查询最近的事件，after_id 用于增量获取
+#[tauri::command]
+pub fn get_recent_events(
+    limit: Option<usize>,
+    kind: Option<EventKind>,
+    after_id: Option<u64>,
+) -> Vec<Event> {
+    recent(limit.unwrap_or(CAPACITY), kind, after_id)
+}
+
+/// 前端记录事件（例如扫码）
+#[tauri::command]
+pub fn record_event(kind: EventKind, message: String, data: Option<Value>) {
+    record(kind, message, data.unwrap_or(Value::Null));
+}
@@ -0,0 +1,92 @@
+// 用默认程序打开文件
+//
+// 导出的 PDF / 表格、备份文件夹等可以直接用系统默认程序打开。
+// 为防止页面借此运行任意程序，只允许打开：
+// - 应用数据目录、日志目录、Backend 数据目录下的文件
+// - 用户通过文件对话框选择过的文件或文件夹（及文件夹中的文件）
+// 并且只能打开文件夹和 ALLOWED_EXTENSIONS 中的文档、表格、图片、压缩包和日志。
+// 打开的是检查过的规范化路径，而不是页面传入的原始路径。
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, AppResult};
+use crate::{backend, config, dialog, logging, os};
+
+/// 允许打开的文件扩展名
+const ALLOWED_EXTENSIONS: [&str; 8] = ["pdf", "csv", "xlsx", "json", "png", "zip", "txt", "log"];
+
+/// 允许打开的目录
+fn allowed_roots(app_handle: &tauri::AppHandle) -> Vec<PathBuf> {
+    let mut roots = vec![config::data_dir(app_handle)];
+    roots.extend(logging::log_dir().map(Path::to_path_buf));
+    roots.extend(backend::database_dir());
+    roots
+        .into_iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect()
+}
+
+/// path 为规范化后的路径，requested 为页面传入的原始路径（对话框记录的是原始路径）
+fn is_allowed(app_handle: &tauri::AppHandle, path: &Path, requested: &Path) -> bool {
+    let selected = |p: &Path| {
+        dialog::is_selected(p)
+            || dialog::is_save_selected(p)
+            || p.parent().is_some_and(dialog::is_selected)
+    };
+    let plain = !requested
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir));
+    if selected(path) || (plain && selected(requested)) {
+        return true;
+    }
+    allowed_roots(app_handle)
+        .iter()
+        .any(|root| path.starts_with(root))
+}
+
+/// 去掉 Windows 规范化路径的 \\?\ 前缀（资源管理器不识别这种路径）
+fn simplified(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let text = path.to_string_lossy();
+        if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+            return PathBuf::from(format!(r"\\{}", rest));
+        }
+        if let Some(rest) = text.strip_prefix(r"\\?\") {
+            return PathBuf::from(rest);
+        }
+    }
+    path.to_path_buf()
+}
+
+// Tauri 命令
+
+/// 用系统默认程序打开文件或文件夹
+#[tauri::command]
+pub async fn open_path(path: String, app_handle: tauri::AppHandle) -> AppResult<()> {
+    let requested = PathBuf::from(&path);
+    let path = requested
+        .canonicalize()
+        .map_err(|e| AppError::InvalidArgument(format!("路径不存在: {} ({})", path, e)))?;
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !path.is_dir() && !ALLOWED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(AppError::InvalidArgument(format!(
+            "不允许打开此类文件: {:?}",
+            path
+        )));
+    }
+
+    if !is_allowed(&app_handle, &path, &requested) {
+        tracing::warn!("拒绝打开不在允许范围内的路径: {:?}", path);
+        return Err(AppError::InvalidArgument(format!(
+            "只能打开应用数据、导出或备份目录中的文件: {:?}",
+            requested
+        )));
+    }
+
+    os::open_path(&simplified(&path)).map_err(AppError::Io)
+}
@@ -0,0 +1,136 @@
+// 前端错误转发
+//
+// 前端捕获的 JS 异常、未处理的 Promise 拒绝和 console.error/warn 通过
+// log_frontend_error 写入壳程序日志（模块名 smartmart_desktop::frontend），
+// 与 Rust 和 Backend 日志位于同一时间线，便于还原一次故障的完整经过。
+
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::events::{self, EventKind};
+
+/// 每分钟最多记录的前端日志条数，避免循环报错刷满日志
+const MAX_PER_MINUTE: u32 = 60;
+
+/// 单条内容的最大长度
+const MAX_MESSAGE_LEN: usize = 4000;
+
+static RATE: Mutex<Option<(Instant, u32, u32)>> = Mutex::new(None);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontendLevel {
+    Error,
+    Warn,
+    Info,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FrontendError {
+    #[serde(default = "default_level")]
+    pub level: FrontendLevel,
+    /// 来源：error / unhandledrejection / console
+    #[serde(default)]
+    pub kind: Option<String>,
+    pub message: String,
+    #[serde(default)]
+    pub stack: Option<String>,
+    /// 出错的脚本地址和位置
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub column: Option<u32>,
+    /// 出错时的页面路由
+    #[serde(default)]
+    pub route: Option<String>,
+}
+
+fn default_level() -> FrontendLevel {
+    FrontendLevel::Error
+}
+
+/// 是否在限流范围内；进入新的一分钟时报告上一分钟丢弃的条数
+fn allow() -> bool {
+    let mut rate = RATE.lock().unwrap();
+    let now = Instant::now();
+    let (started, count, dropped) = rate.get_or_insert((now, 0, 0));
+
+    if now.duration_since(*started) >= Duration::from_secs(60) {
+        if *dropped > 0 {
+            tracing::warn!(target: "smartmart_desktop::frontend", "前端日志过多，已丢弃 {} 条", dropped);
+        }
+        *started = now;
+        *count = 0;
+        *dropped = 0;
+    }
+    if *count >= MAX_PER_MINUTE {
+        *dropped += 1;
+        return false;
+    }
+    *count += 1;
+    true
+}
+
+fn truncate(text: &str) -> &str {
+    match text.char_indices().nth(MAX_MESSAGE_LEN) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+// Tauri 命令
+
+/// 前端错误写入壳程序日志
+#[tauri::command]
+pub fn log_frontend_error(payload: FrontendError) {
+    if !allow() {
+        return;
+    }
+
+    let kind = payload.kind.as_deref().unwrap_or("error");
+    let location = match (&payload.source, payload.line) {
+        (Some(source), Some(line)) => {
+            format!(
+                " ({}:{}:{})",
+                source,
+                line,
+                payload.column.unwrap_or_default()
+            )
+        }
+        (Some(source), None) => format!(" ({})", source),
+        _ => String::new(),
+    };
+    let route = payload
+        .route
+        .as_deref()
+        .map(|r| format!(" [{}]", r))
+        .unwrap_or_default();
+    let mut message = format!(
+        "[{}]{} {}{}",
+        kind,
+        route,
+        truncate(&payload.message),
+        location
+    );
+    if let Some(stack) = payload.stack.as_deref().filter(|s| !s.is_empty()) {
+        message.push('\n');
+        message.push_str(truncate(stack));
+    }
+
+    match payload.level {
+        FrontendLevel::Error => {
+            tracing::error!(target: "smartmart_desktop::frontend", "{}", message);
+            events::record(
+                EventKind::Error,
+                truncate(&payload.message),
+                json!({ "source": "frontend", "kind": kind, "route": payload.route }),
+            );
+        }
+        FrontendLevel::Warn => tracing::warn!(target: "smartmart_desktop::frontend", "{}", message),
+        FrontendLevel::Info => tracing::info!(target: "smartmart_desktop::frontend", "{}", message),
+    }
+}
@@ -0,0 +1,241 @@
+// 健康状态汇总
+//
+// 把 Backend、备份、磁盘、打印机、同步和授权的状态汇总为一个结构，
+// 供首页的红绿灯卡片使用。同步为已配对的手机等设备；尚未接入的授权返回 unknown，
+// 无法判断的项同样返回 unknown，不影响总体状态。
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::backend::{self, BackendProcess};
+use crate::config::{self, ConfigStore};
+use crate::{backup, disk, http, printer, storage};
+use crate::watchdog::LatencyTracker;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    Green,
+    Yellow,
+    Red,
+    Unknown,
+}
+
+impl HealthLevel {
+    fn severity(self) -> u8 {
+        match self {
+            HealthLevel::Unknown => 0,
+            HealthLevel::Green => 1,
+            HealthLevel::Yellow => 2,
+            HealthLevel::Red => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub level: HealthLevel,
+    pub message: String,
+    pub detail: Value,
+}
+
+impl HealthCheck {
+    fn new(level: HealthLevel, message: impl Into<String>, detail: Value) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            detail,
+        }
+    }
+
+    fn unknown(message: impl Into<String>) -> Self {
+        Self::new(HealthLevel::Unknown, message, Value::Null)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSummary {
+    /// 各项中最严重的状态（unknown 不参与）
+    pub overall: HealthLevel,
+    pub checked_at: String,
+    pub backend: HealthCheck,
+    pub backup: HealthCheck,
+    pub disk: HealthCheck,
+    pub printer: HealthCheck,
+    pub sync: HealthCheck,
+    pub license: HealthCheck,
+}
+
+fn backend_check(app_handle: &tauri::AppHandle) -> HealthCheck {
+    let (port, pid, exited) = {
+        let state = app_handle.state::<Mutex<BackendProcess>>();
+        let mut process = state.lock().unwrap();
+        let exited = process.pid().is_some() && !process.is_running();
+        (process.port(), process.pid(), exited)
+    };
+    let threshold_ms = app_handle.state::<ConfigStore>().get().monitoring.slow_threshold_ms;
+    let latency = app_handle
+        .state::<Mutex<LatencyTracker>>()
+        .lock()
+        .unwrap()
+        .stats(threshold_ms);
+
+    let health = backend::check_health(port);
+    let detail = json!({
+        "port": port,
+        "pid": pid,
+        "version": crate::crash::backend_version(),
+        "response_ms": health.as_ref().ok().map(|d| d.as_millis() as u64),
+        "p95_ms": latency.p95_ms,
+    });
+
+    match health {
+        Err(_) if exited => HealthCheck::new(HealthLevel::Red, "Backend 进程已退出", detail),
+        Err(e) => HealthCheck::new(HealthLevel::Red, e, detail),
+        Ok(_) if latency.slow => HealthCheck::new(HealthLevel::Yellow, "Backend 响应变慢", detail),
+        Ok(_) => HealthCheck::new(HealthLevel::Green, "运行正常", detail),
+    }
+}
+
+/// 最近一次备份超过 36 小时提示，超过 7 天告警
+fn backup_check(app_handle: &tauri::AppHandle) -> HealthCheck {
+    let Some((path, modified)) = backup::latest(app_handle) else {
+        return HealthCheck::unknown("尚未备份");
+    };
+    let age = modified.elapsed().unwrap_or_default();
+    let hours = age.as_secs() / 3600;
+    let detail = json!({ "path": path, "age_hours": hours });
+    let message = if hours < 1 {
+        "最近一次备份在 1 小时内".to_string()
+    } else if hours < 48 {
+        format!("最近一次备份在 {} 小时前", hours)
+    } else {
+        format!("最近一次备份在 {} 天前", hours / 24)
+    };
+    let level = match hours {
+        0..=35 => HealthLevel::Green,
+        36..=167 => HealthLevel::Yellow,
+        _ => HealthLevel::Red,
+    };
+    HealthCheck::new(level, message, detail)
+}
+
+fn disk_check(app_handle: &tauri::AppHandle) -> HealthCheck {
+    let data_dir = config::data_dir(app_handle);
+    let Some(space) = disk::space_for(&data_dir) else {
+        return HealthCheck::unknown("无法获取磁盘空间");
+    };
+
+    let settings = app_handle.state::<ConfigStore>().get().disk;
+    let level = match disk::level_for(&space, &settings) {
+        disk::SpaceLevel::Critical => HealthLevel::Red,
+        disk::SpaceLevel::Warning => HealthLevel::Yellow,
+        disk::SpaceLevel::Notice | disk::SpaceLevel::Ok => HealthLevel::Green,
+    };
+    let message = format!(
+        "{} 剩余 {}（{:.0}%）",
+        space.mount_point,
+        disk::format_gb(space.available_bytes),
+        space.available_percent()
+    );
+
+    // 磁盘健康检查发现问题时优先显示
+    let storage = storage::last();
+    let detail = json!({ "space": space, "storage": storage });
+    match storage {
+        Some(storage) if storage.level.severity() > level.severity() => {
+            HealthCheck::new(storage.level, storage.message, detail)
+        }
+        _ => HealthCheck::new(level, message, detail),
+    }
+}
+
+fn printer_check(app_handle: &tauri::AppHandle) -> HealthCheck {
+    let settings = app_handle.state::<ConfigStore>().get().printer;
+    if settings.connection.is_none() {
+        return HealthCheck::unknown("未配置打印机");
+    }
+    let last = printer::last_result();
+    let detail = json!({ "connection": settings.connection, "last": last });
+    match last {
+        None => HealthCheck::new(HealthLevel::Unknown, "今天尚未打印", detail),
+        Some(result) if result.ok => HealthCheck::new(HealthLevel::Green, "打印机正常", detail),
+        Some(result) => HealthCheck::new(
+            HealthLevel::Red,
+            format!("上次打印失败: {}", result.error.unwrap_or_default()),
+            detail,
+        ),
+    }
+}
+
+/// 已配对的设备（手机等通过配对码连接，经 WebSocket 同步数据）
+fn sync_check(app_handle: &tauri::AppHandle, backend: &HealthCheck) -> HealthCheck {
+    if backend.level == HealthLevel::Red {
+        return HealthCheck::unknown("Backend 未运行，无法获取配对设备");
+    }
+    let port = app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port();
+    let devices = http::local()
+        .get(&format!("http://127.0.0.1:{}/pairing/devices", port))
+        .call()
+        .ok()
+        .and_then(|response| response.into_json::<Vec<Value>>().ok());
+    let Some(devices) = devices else {
+        return HealthCheck::unknown("无法获取配对设备");
+    };
+    let paired = devices
+        .iter()
+        .filter(|device| device["authenticated"].as_bool() == Some(true))
+        .count();
+    let detail = json!({ "paired_devices": paired, "devices": devices.len() });
+    if paired == 0 {
+        return HealthCheck::new(HealthLevel::Unknown, "未配对设备", detail);
+    }
+    HealthCheck::new(
+        HealthLevel::Green,
+        format!("已配对 {} 台设备", paired),
+        detail,
+    )
+}
+
+fn license_check() -> HealthCheck {
+    HealthCheck::unknown("未启用授权")
+}
+
+pub fn collect(app_handle: &tauri::AppHandle) -> HealthSummary {
+    let backend = backend_check(app_handle);
+    let backup = backup_check(app_handle);
+    let disk = disk_check(app_handle);
+    let printer = printer_check(app_handle);
+    let sync = sync_check(app_handle, &backend);
+    let license = license_check();
+
+    let overall = [&backend, &backup, &disk, &printer, &sync, &license]
+        .iter()
+        .map(|check| check.level)
+        .max_by_key(|level| level.severity())
+        .unwrap_or(HealthLevel::Unknown);
+
+    HealthSummary {
+        overall,
+        checked_at: chrono::Local::now().to_rfc3339(),
+        backend,
+        backup,
+        disk,
+        printer,
+        sync,
+        license,
+    }
+}
+
+// Tauri 命令
+
+#[tauri::command]
+pub async fn get_health_summary(app_handle: tauri::AppHandle) -> HealthSummary {
+    collect(&app_handle)
+}
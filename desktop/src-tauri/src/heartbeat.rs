@@ -0,0 +1,137 @@
+// 心跳文件
+//
+// 定期把时间戳和健康状态写入 JSON 文件，门店监控代理 / RMM 工具只需检查
+// 文件的修改时间和内容即可发现失去响应的收银终端，无需专门对接。
+// 文件超过 interval_secs 的数倍未更新即可认为程序已停止。
+//
+// 心跳只使用监护线程和看门狗缓存的 Backend 状态，不额外请求 Backend 或检查磁盘、打印机，
+// 完整的健康检查仍由 get_health_summary 按需执行。
+
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+use crate::backend::{self, BackendState, BackendStatus};
+use crate::config::{self, ConfigStore, HeartbeatConfig};
+use crate::crash;
+use crate::health::HealthLevel;
+use crate::watchdog::{LatencyStats, LatencyTracker};
+
+const HEARTBEAT_FILE: &str = "heartbeat.json";
+
+/// 心跳文件路径（未配置时位于应用数据目录）
+pub fn heartbeat_path(app_handle: &tauri::AppHandle, settings: &HeartbeatConfig) -> PathBuf {
+    settings
+        .path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| config::data_dir(app_handle).join(HEARTBEAT_FILE))
+}
+
+/// 由缓存的状态得出 Backend 的健康等级和说明
+fn backend_level(status: Option<&BackendStatus>, latency: &LatencyStats) -> (HealthLevel, String) {
+    let Some(status) = status else {
+        return (HealthLevel::Unknown, "尚未检查".to_string());
+    };
+    let error = |fallback: &str| {
+        status
+            .last_error
+            .clone()
+            .unwrap_or_else(|| fallback.to_string())
+    };
+    match status.state {
+        BackendState::Running if latency.slow => {
+            (HealthLevel::Yellow, "Backend 响应变慢".to_string())
+        }
+        BackendState::Running => (HealthLevel::Green, "运行正常".to_string()),
+        BackendState::Starting => (HealthLevel::Yellow, "Backend 正在启动".to_string()),
+        BackendState::Unresponsive => (HealthLevel::Red, error("Backend 无响应")),
+        BackendState::Restarting | BackendState::Failed => {
+            (HealthLevel::Red, error("Backend 已停止"))
+        }
+        BackendState::Stopped => (HealthLevel::Red, "Backend 未运行".to_string()),
+    }
+}
+
+fn write_heartbeat(
+    app_handle: &tauri::AppHandle,
+    settings: &HeartbeatConfig,
+    started: SystemTime,
+    sequence: u64,
+) -> Result<(), String> {
+    let status = backend::last_status();
+    let threshold_ms = app_handle
+        .state::<ConfigStore>()
+        .get()
+        .monitoring
+        .slow_threshold_ms;
+    let latency = app_handle
+        .state::<Mutex<LatencyTracker>>()
+        .lock()
+        .unwrap()
+        .stats(threshold_ms);
+    let (level, message) = backend_level(status.as_ref(), &latency);
+    let now = SystemTime::now();
+    let payload = json!({
+        "timestamp": chrono::Local::now().to_rfc3339(),
+        "unix_time": now.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default(),
+        "sequence": sequence,
+        "interval_secs": settings.interval_secs,
+        "pid": std::process::id(),
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "backend_version": crash::backend_version(),
+        "uptime_secs": now.duration_since(started).map(|d| d.as_secs()).unwrap_or_default(),
+        "status": level,
+        "checks": { "backend": level },
+        "backend_state": status.as_ref().map(|s| s.state),
+        "backend_restarts": status.as_ref().map_or(0, |s| s.restarts),
+        "backend_message": message,
+        "backend_p95_ms": latency.p95_ms,
+    });
+
+    let path = heartbeat_path(app_handle, settings);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建心跳目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&payload)
+        .map_err(|e| format!("序列化心跳失败: {}", e))?;
+
+    // 先写临时文件再替换，监控工具不会读到写了一半的文件
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content).map_err(|e| format!("写入心跳文件失败: {}", e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("写入心跳文件失败: {}", e))
+}
+
+/// 启动心跳线程
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("heartbeat".into())
+        .spawn(move || {
+            let started = SystemTime::now();
+            let mut sequence = 0;
+            let mut failing = false;
+            loop {
+                let settings = app_handle.state::<ConfigStore>().get().heartbeat;
+                if settings.enabled {
+                    sequence += 1;
+                    match write_heartbeat(&app_handle, &settings, started, sequence) {
+                        Ok(()) => failing = false,
+                        // 只在第一次失败时记录，避免每隔几秒刷一条日志
+                        Err(e) if !failing => {
+                            failing = true;
+                            tracing::warn!("{}", e);
+                        }
+                        Err(_) => {}
+                    }
+                }
+                std::thread::sleep(Duration::from_secs(settings.interval_secs.max(1)));
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动心跳线程失败: {}", e);
+    }
+}
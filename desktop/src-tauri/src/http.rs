@@ -0,0 +1,49 @@
+// HTTP 客户端
+//
+// 壳程序使用同步的 ureq 客户端，可以在后台线程和 panic hook 中安全调用，
+// 不依赖 tokio 运行时
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::{backend, system_proxy};
+
+/// 为访问 Backend 的请求带上访问 Token
+#[allow(clippy::result_large_err)] // 签名由 ureq 的 Middleware 决定
+fn authorize(
+    request: ureq::Request,
+    next: ureq::MiddlewareNext,
+) -> Result<ureq::Response, ureq::Error> {
+    next.handle(request.set("Authorization", &format!("Bearer {}", backend::token())))
+}
+
+/// 访问本机 Backend 的客户端设置
+pub fn backend_builder() -> ureq::AgentBuilder {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(2))
+        .middleware(authorize)
+}
+
+/// 访问本机 Backend 的客户端（超时较短）
+pub fn local() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| backend_builder().timeout(Duration::from_secs(5)).build())
+}
+
+/// 访问外部服务（上报、统计等）的客户端，检测到代理时经过代理访问
+pub fn external() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        let mut builder = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(10))
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("SmartMart-Desktop/", env!("CARGO_PKG_VERSION")));
+        if let Some(url) = &system_proxy::current().url {
+            match ureq::Proxy::new(url) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => tracing::warn!("代理地址无效: {} ({})", url, e),
+            }
+        }
+        builder.build()
+    })
+}
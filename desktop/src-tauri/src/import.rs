@@ -0,0 +1,308 @@
+// 拖放导入
+//
+// 把文件拖到窗口上时，由壳程序识别文件类型并发出 import://dropped 事件，前端据此打开对应的导入流程
+// （例如把供应商价目表拖进来直接导入商品）。拖动经过窗口时发出 import://hover，移开时发出 import://cancelled。
+//
+// 识别的类型：
+// - csv      价目表 / 商品表（.csv，Backend 只接受逗号分隔的 CSV），附带表头、分隔符、行数和编码
+// - backup   SmartMart 备份（.smartbak）
+// - image    商品图片（.png、.jpg、.jpeg、.webp、.bmp、.gif），附带图片尺寸
+// - unknown  其他文件和文件夹
+//
+// 出于安全考虑，导入命令只接受最近一次拖放进来的文件，或通过文件对话框选择的文件。
+
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{FileDropEvent, Manager, WindowEvent};
+
+use crate::backend::BackendProcess;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::{dialog, http};
+
+/// CSV 最多读取多少字节用于统计行数
+const CSV_SCAN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 最近一次拖放的文件
+static DROPPED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DroppedKind {
+    Csv,
+    Backup,
+    Image,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvInfo {
+    pub delimiter: String,
+    pub columns: Vec<String>,
+    /// 数据行数（不含表头），文件过大时为已读取部分的行数
+    pub rows: u64,
+    pub truncated: bool,
+    /// 是否为 UTF-8 编码（Backend 只支持 UTF-8，其他编码需先转换）
+    pub utf8: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DroppedFile {
+    pub path: String,
+    pub name: String,
+    pub kind: DroppedKind,
+    pub size: u64,
+    pub csv: Option<CsvInfo>,
+    pub image: Option<ImageInfo>,
+    /// 读取文件内容出错时的说明
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DropPayload {
+    /// 所有文件类型相同时为该类型，否则为 unknown
+    pub kind: DroppedKind,
+    pub files: Vec<DroppedFile>,
+}
+
+fn kind_of(path: &Path) -> DroppedKind {
+    if path.is_dir() {
+        return DroppedKind::Unknown;
+    }
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "csv" => DroppedKind::Csv,
+        "smartbak" => DroppedKind::Backup,
+        "png" | "jpg" | "jpeg" | "webp" | "bmp" | "gif" => DroppedKind::Image,
+        _ => DroppedKind::Unknown,
+    }
+}
+
+/// 读取表头并统计行数
+fn inspect_csv(path: &Path) -> Result<CsvInfo, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut reader = BufReader::new(file.take(CSV_SCAN_BYTES));
+
+    let mut header = Vec::new();
+    reader
+        .read_until(b'\n', &mut header)
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    let mut utf8 = std::str::from_utf8(&header).is_ok();
+    let header = String::from_utf8_lossy(&header);
+    let header = header.trim_start_matches('\u{feff}').trim_end();
+
+    let delimiter = [',', '\t', ';', '|']
+        .into_iter()
+        .max_by_key(|d| header.matches(*d).count())
+        .filter(|d| header.contains(*d))
+        .unwrap_or(',');
+    let columns = header
+        .split(delimiter)
+        .map(|column| column.trim().trim_matches('"').to_string())
+        .collect();
+
+    let mut rows = 0;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                utf8 &= std::str::from_utf8(&line).is_ok();
+                if !line.iter().all(u8::is_ascii_whitespace) {
+                    rows += 1;
+                }
+            }
+            Err(e) => return Err(format!("读取文件失败: {}", e)),
+        }
+    }
+
+    Ok(CsvInfo {
+        delimiter: delimiter.to_string(),
+        columns,
+        rows,
+        truncated: size > CSV_SCAN_BYTES,
+        utf8,
+    })
+}
+
+fn inspect(path: &Path) -> DroppedFile {
+    let kind = kind_of(path);
+    let mut file = DroppedFile {
+        path: path.to_string_lossy().into_owned(),
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        kind,
+        size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        csv: None,
+        image: None,
+        error: None,
+    };
+
+    match kind {
+        DroppedKind::Csv => match inspect_csv(path) {
+            Ok(info) => file.csv = Some(info),
+            Err(e) => file.error = Some(e),
+        },
+        DroppedKind::Image => {
+            // 只读取文件头中的尺寸，文件损坏时不附带尺寸
+            if let Ok((width, height)) = image::image_dimensions(path) {
+                file.image = Some(ImageInfo { width, height });
+            }
+        }
+        DroppedKind::Backup | DroppedKind::Unknown => {}
+    }
+    file
+}
+
+fn on_dropped(app_handle: tauri::AppHandle, paths: Vec<PathBuf>) {
+    *DROPPED.lock().unwrap() = paths.clone();
+
+    // CSV 可能较大，在后台线程中读取
+    let spawned = std::thread::Builder::new()
+        .name("import-inspect".into())
+        .spawn(move || {
+            let files: Vec<DroppedFile> = paths.iter().map(|path| inspect(path)).collect();
+            let kind = match files.first() {
+                Some(first) if files.iter().all(|f| f.kind == first.kind) => first.kind,
+                _ => DroppedKind::Unknown,
+            };
+
+            tracing::info!("拖放文件: {:?} ({} 个)", kind, files.len());
+            events::record(
+                EventKind::State,
+                "拖放文件",
+                serde_json::json!({
+                    "kind": kind,
+                    "names": files.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+                }),
+            );
+            let _ = app_handle.emit_all("import://dropped", DropPayload { kind, files });
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动文件识别线程失败: {}", e);
+    }
+}
+
+/// 处理窗口的拖放事件
+pub fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
+    let WindowEvent::FileDrop(event) = event else {
+        return;
+    };
+    let app_handle = window.app_handle();
+    match event {
+        FileDropEvent::Hovered(paths) => {
+            let _ = app_handle.emit_all(
+                "import://hover",
+                serde_json::json!({ "count": paths.len() }),
+            );
+        }
+        FileDropEvent::Dropped(paths) => on_dropped(app_handle, paths.clone()),
+        FileDropEvent::Cancelled => {
+            let _ = app_handle.emit_all("import://cancelled", ());
+        }
+        _ => {}
+    }
+}
+
+/// 以 multipart/form-data 上传文件
+fn upload_csv(port: u16, path: &Path) -> AppResult<serde_json::Value> {
+    let content = std::fs::read(path).map_err(|e| AppError::Io(format!("读取文件失败: {}", e)))?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "import.csv".to_string());
+    upload_csv_content(port, &name, &content)
+}
+
+/// 把 CSV 内容上传到 Backend 的商品导入接口（name 须以 .csv 结尾）。
+/// Backend 拒绝文件内容时返回 InvalidArgument，连接失败、未授权或暂时不可用时返回 BackendUnavailable
+pub(crate) fn upload_csv_content(
+    port: u16,
+    name: &str,
+    content: &[u8],
+) -> AppResult<serde_json::Value> {
+    let name = name.replace('"', "");
+    let boundary = format!("smartmart-{}", uuid::Uuid::new_v4().simple());
+
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: text/csv\r\n\r\n",
+        boundary, name
+    )
+    .into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    let response = http::local()
+        .post(&format!("http://127.0.0.1:{}/products/import_csv", port))
+        .set(
+            "Content-Type",
+            &format!("multipart/form-data; boundary={}", boundary),
+        )
+        .send_bytes(&body);
+
+    match response {
+        Ok(response) => response
+            .into_json()
+            .map_err(|e| AppError::BackendUnavailable(format!("解析导入结果失败: {}", e))),
+        Err(ureq::Error::Status(status @ (401 | 403 | 502 | 503 | 504), _)) => Err(
+            AppError::BackendUnavailable(format!("导入失败: Backend 返回 {}", status)),
+        ),
+        Err(ureq::Error::Status(_, response)) => {
+            let detail = response
+                .into_json::<serde_json::Value>()
+                .ok()
+                .and_then(|body| {
+                    body.get("detail")
+                        .and_then(|d| d.as_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| "未知错误".to_string());
+            Err(AppError::InvalidArgument(format!("导入失败: {}", detail)))
+        }
+        Err(e) => Err(AppError::BackendUnavailable(format!("导入失败: {}", e))),
+    }
+}
+
+// Tauri 命令
+
+/// 把拖放进来（或对话框选择）的 CSV 导入为商品，返回 Backend 的导入结果
+#[tauri::command]
+pub async fn import_dropped_csv(
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> AppResult<serde_json::Value> {
+    let path = PathBuf::from(path);
+    if !DROPPED.lock().unwrap().contains(&path) && !dialog::is_selected(&path) {
+        return Err(AppError::InvalidArgument(
+            "只能导入拖放或通过对话框选择的文件".to_string(),
+        ));
+    }
+    if kind_of(&path) != DroppedKind::Csv {
+        return Err(AppError::InvalidArgument("文件不是 CSV 格式".to_string()));
+    }
+
+    let port = app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port();
+    let result = upload_csv(port, &path)?;
+    tracing::info!("已导入拖放的 CSV: {:?}", path);
+    Ok(result)
+}
@@ -0,0 +1,507 @@
+// 导入热文件夹
+//
+// 供应商的同步工具会把价目表放到固定的文件夹中。在设置中指定该文件夹后，壳程序定期检查其中的
+// CSV / XLSX 文件，文件写入完成（两次检查之间大小和修改时间不变）后自动导入为商品：
+// - 导入成功的文件移到 done 子文件夹
+// - 导入失败或有错误行的文件移到 error 子文件夹，并在旁边写入同名的 .txt 说明原因
+// 移动后的文件名带上处理时间，避免重名。Backend 不可用时保留文件，下次检查时再导入。
+//
+// XLSX 只读取第一个工作表，转换为 CSV 后交给 Backend 的 CSV 导入接口。
+// 每处理一个文件发出 import://folder 事件并记录到事件日志，失败时弹出系统通知。
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+
+use crate::backend::{self, BackendProcess};
+use crate::config::ConfigStore;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::{dialog, import, notify, shutdown};
+
+const DONE_DIR: &str = "done";
+const ERROR_DIR: &str = "error";
+
+/// 保留的最近处理记录数
+const MAX_RECENT: usize = 20;
+
+/// 尚未写入完成的文件：路径 -> (大小, 修改时间)
+static PENDING: Mutex<Option<HashMap<PathBuf, (u64, SystemTime)>>> = Mutex::new(None);
+
+/// 最近处理的文件（新的在前）
+static RECENT: Mutex<VecDeque<ProcessedFile>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessedFile {
+    pub name: String,
+    /// 移动后的路径
+    pub path: Option<String>,
+    pub success: bool,
+    /// 导入成功的行数
+    pub imported: u64,
+    /// 有错误的行数
+    pub failed: u64,
+    pub error: Option<String>,
+    pub processed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFolderStatus {
+    pub enabled: bool,
+    pub path: Option<String>,
+    /// 等待写入完成的文件数
+    pub pending: usize,
+    pub recent: Vec<ProcessedFile>,
+}
+
+fn is_importable(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // 跳过 Excel 的锁文件（~$xxx.xlsx）和隐藏文件
+    if name.starts_with("~$") || name.starts_with('.') || !path.is_file() {
+        return false;
+    }
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    extension == "csv" || extension == "xlsx"
+}
+
+/// 列出写入完成的文件（第一次看到或仍在变化的文件等下一次检查）
+fn stable_files(folder: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut current = HashMap::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if !is_importable(&path) {
+            continue;
+        }
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            current.insert(path, (metadata.len(), modified));
+        }
+    }
+
+    let mut pending = PENDING.lock().unwrap();
+    let previous = pending.replace(current.clone()).unwrap_or_default();
+    let mut stable: Vec<PathBuf> = current
+        .into_iter()
+        .filter(|(path, state)| previous.get(path) == Some(state))
+        .map(|(path, _)| path)
+        .collect();
+    stable.sort();
+    stable
+}
+
+// XLSX 转 CSV
+
+fn compiled(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+fn unescape_xml(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    compiled(&ENTITY, r"&(#x[0-9a-fA-F]+|#[0-9]+|amp|lt|gt|quot|apos);")
+        .replace_all(text, |caps: &regex::Captures| match &caps[1] {
+            "amp" => "&".to_string(),
+            "lt" => "<".to_string(),
+            "gt" => ">".to_string(),
+            "quot" => "\"".to_string(),
+            "apos" => "'".to_string(),
+            code => {
+                let value = match code.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code[1..].parse().ok(),
+                };
+                value
+                    .and_then(char::from_u32)
+                    .map(String::from)
+                    .unwrap_or_default()
+            }
+        })
+        .into_owned()
+}
+
+/// 拼接元素中所有 <t> 的文本（富文本会拆成多段，注音 <rPh> 不计入）
+fn text_of(xml: &str) -> String {
+    static PHONETIC: OnceLock<Regex> = OnceLock::new();
+    static TEXT: OnceLock<Regex> = OnceLock::new();
+    let xml = compiled(&PHONETIC, r"(?s)<rPh\b.*?</rPh>").replace_all(xml, "");
+    compiled(&TEXT, r"(?s)<t(?:\s[^>]*)?>(.*?)</t>")
+        .captures_iter(&xml)
+        .map(|caps| unescape_xml(&caps[1]))
+        .collect()
+}
+
+fn read_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// 第一个工作表在压缩包中的路径
+fn first_sheet<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>) -> String {
+    static SHEET: OnceLock<Regex> = OnceLock::new();
+    static RELATIONSHIP: OnceLock<Regex> = OnceLock::new();
+    let fallback = "xl/worksheets/sheet1.xml".to_string();
+
+    let Some(workbook) = read_entry(archive, "xl/workbook.xml") else {
+        return fallback;
+    };
+    let Some(id) = compiled(&SHEET, r#"<sheet\b[^>]*\br:id="([^"]+)""#)
+        .captures(&workbook)
+        .map(|caps| caps[1].to_string())
+    else {
+        return fallback;
+    };
+    let Some(rels) = read_entry(archive, "xl/_rels/workbook.xml.rels") else {
+        return fallback;
+    };
+    compiled(&RELATIONSHIP, r"<Relationship\b[^>]*>")
+        .find_iter(&rels)
+        .map(|m| m.as_str())
+        .find(|tag| tag.contains(&format!("Id=\"{}\"", id)))
+        .and_then(|tag| tag.split("Target=\"").nth(1))
+        .and_then(|rest| rest.split('"').next())
+        .map(|target| match target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("xl/{}", target),
+        })
+        .unwrap_or(fallback)
+}
+
+/// 列字母转序号（A -> 0）
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: String = reference
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    Some(
+        letters
+            .to_ascii_uppercase()
+            .bytes()
+            .fold(0, |index, b| index * 26 + (b - b'A' + 1) as usize)
+            - 1,
+    )
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 把 XLSX 的第一个工作表转换为 CSV
+fn xlsx_to_csv(path: &Path) -> Result<Vec<u8>, String> {
+    static SHARED: OnceLock<Regex> = OnceLock::new();
+    static ROW: OnceLock<Regex> = OnceLock::new();
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    static TYPE: OnceLock<Regex> = OnceLock::new();
+    static VALUE: OnceLock<Regex> = OnceLock::new();
+
+    let file = std::fs::File::open(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("不是有效的 XLSX 文件: {}", e))?;
+
+    let shared: Vec<String> = read_entry(&mut archive, "xl/sharedStrings.xml")
+        .map(|xml| {
+            compiled(&SHARED, r"(?s)<si>(.*?)</si>")
+                .captures_iter(&xml)
+                .map(|caps| text_of(&caps[1]))
+                .collect()
+        })
+        .unwrap_or_default();
+    let sheet_name = first_sheet(&mut archive);
+    let sheet = read_entry(&mut archive, &sheet_name)
+        .ok_or_else(|| format!("XLSX 中没有工作表: {}", sheet_name))?;
+
+    let mut csv = String::new();
+    for row in compiled(&ROW, r"(?s)<row\b[^>]*?(?:/>|>(.*?)</row>)").captures_iter(&sheet) {
+        let mut cells: Vec<String> = Vec::new();
+        let content = row.get(1).map(|m| m.as_str()).unwrap_or_default();
+        for cell in compiled(&CELL, r"(?s)<c\b([^>]*?)(?:/>|>(.*?)</c>)").captures_iter(content) {
+            let attributes = &cell[1];
+            let body = cell.get(2).map(|m| m.as_str()).unwrap_or_default();
+            let raw = compiled(&VALUE, r"(?s)<v>(.*?)</v>")
+                .captures(body)
+                .map(|caps| unescape_xml(&caps[1]))
+                .unwrap_or_default();
+            let value = match compiled(&TYPE, r#"\bt="(\w+)""#)
+                .captures(attributes)
+                .map(|caps| caps[1].to_string())
+                .as_deref()
+            {
+                Some("s") => raw
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| shared.get(index).cloned())
+                    .unwrap_or_default(),
+                Some("inlineStr") => text_of(body),
+                Some("b") => (if raw == "1" { "TRUE" } else { "FALSE" }).to_string(),
+                _ => raw,
+            };
+            let index = compiled(&REFERENCE, r#"\br="([A-Za-z]+)\d+""#)
+                .captures(attributes)
+                .and_then(|caps| column_index(&caps[1]))
+                .unwrap_or(cells.len());
+            if index >= cells.len() {
+                cells.resize(index + 1, String::new());
+            }
+            cells[index] = value;
+        }
+        if cells.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let line: Vec<String> = cells.iter().map(|cell| csv_field(cell)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+    if csv.is_empty() {
+        return Err("第一个工作表为空".to_string());
+    }
+    Ok(csv.into_bytes())
+}
+
+// 导入和归档
+
+/// 导入文件，返回 (成功行数, 错误行数, 错误说明)。Backend 不可用时返回 BackendUnavailable
+fn import_file(port: u16, path: &Path) -> AppResult<(u64, u64, Vec<String>)> {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "import".to_string());
+    let is_xlsx = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx"));
+    let content = if is_xlsx {
+        xlsx_to_csv(path).map_err(AppError::InvalidArgument)?
+    } else {
+        std::fs::read(path).map_err(|e| AppError::Io(format!("读取文件失败: {}", e)))?
+    };
+
+    let result = import::upload_csv_content(port, &format!("{}.csv", stem), &content)?;
+    let count = |key: &str| result.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let errors = result
+        .get("errors")
+        .and_then(|e| e.as_array())
+        .map(|errors| {
+            errors
+                .iter()
+                .filter_map(|e| e.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((count("success_count"), count("error_count"), errors))
+}
+
+/// 把文件移到 done / error 子文件夹，返回新路径
+fn archive(folder: &Path, path: &Path, subfolder: &str, report: Option<&str>) -> Option<PathBuf> {
+    let dir = folder.join(subfolder);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::warn!("创建文件夹失败: {:?} ({})", dir, e);
+        return None;
+    }
+    let stem = path.file_stem()?.to_string_lossy().into_owned();
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let target = dir.join(format!("{}-{}.{}", stem, stamp, extension));
+    if let Err(e) = std::fs::rename(path, &target) {
+        tracing::warn!("移动已处理的文件失败: {:?} ({})", path, e);
+        return None;
+    }
+    if let Some(report) = report {
+        let _ = std::fs::write(target.with_extension("txt"), report);
+    }
+    Some(target)
+}
+
+fn process(app_handle: &tauri::AppHandle, folder: &Path, port: u16, path: &Path) {
+    let _busy = shutdown::busy("import_folder");
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    tracing::info!("自动导入: {:?}", path);
+
+    let result = import_file(port, path);
+    // 导入过程中 Backend 不可用（例如正在重启）时保留文件，下次检查时再导入
+    if let Err(AppError::BackendUnavailable(e)) = &result {
+        tracing::warn!("Backend 不可用，下次检查时再导入 {}: {}", name, e);
+        return;
+    }
+    let (imported, failed, error, report) = match &result {
+        Ok((imported, 0, _)) => (*imported, 0, None, None),
+        Ok((imported, failed, errors)) => (
+            *imported,
+            *failed,
+            Some(format!("{} 行导入失败", failed)),
+            Some(format!(
+                "成功 {} 行，失败 {} 行\r\n\r\n{}\r\n",
+                imported,
+                failed,
+                errors.join("\r\n")
+            )),
+        ),
+        Err(e) => (
+            0,
+            0,
+            Some(e.detail().to_string()),
+            Some(format!("{}\r\n", e.detail())),
+        ),
+    };
+    let success = error.is_none();
+    let subfolder = if success { DONE_DIR } else { ERROR_DIR };
+    let moved = archive(folder, path, subfolder, report.as_deref());
+
+    let processed = ProcessedFile {
+        name: name.clone(),
+        path: moved.map(|p| p.to_string_lossy().into_owned()),
+        success,
+        imported,
+        failed,
+        error: error.clone(),
+        processed_at: chrono::Local::now().to_rfc3339(),
+    };
+    {
+        let mut recent = RECENT.lock().unwrap();
+        recent.push_front(processed.clone());
+        recent.truncate(MAX_RECENT);
+    }
+
+    match &error {
+        None => {
+            tracing::info!("自动导入完成: {} ({} 行)", name, imported);
+            events::record(
+                EventKind::State,
+                format!("自动导入: {}", name),
+                json!({ "imported": imported }),
+            );
+        }
+        Some(e) => {
+            tracing::warn!("自动导入失败: {} ({})", name, e);
+            events::record(
+                EventKind::Error,
+                format!("自动导入失败: {}", name),
+                json!({ "imported": imported, "failed": failed, "error": e }),
+            );
+            notify::show(
+                app_handle,
+                "自动导入失败",
+                &format!("{}: {}，文件已移到 {} 文件夹", name, e, ERROR_DIR),
+            );
+        }
+    }
+    let _ = app_handle.emit_all("import://folder", processed);
+}
+
+/// 检查一次热文件夹
+fn scan(app_handle: &tauri::AppHandle, folder: &Path) {
+    let files = stable_files(folder);
+    if files.is_empty() {
+        return;
+    }
+    let port = app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port();
+    // Backend 不可用时保留文件，下次再导入
+    if let Err(e) = backend::check_health(port) {
+        tracing::debug!("Backend 不可用，暂不自动导入: {}", e);
+        return;
+    }
+    for path in files {
+        if path.exists() {
+            process(app_handle, folder, port, &path);
+        }
+    }
+}
+
+/// 启动热文件夹检查线程
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("import-folder".into())
+        .spawn(move || loop {
+            let settings = app_handle.state::<ConfigStore>().get().import_folder;
+            match settings.path.as_deref().filter(|p| !p.is_empty()) {
+                Some(folder) if settings.enabled => scan(&app_handle, Path::new(folder)),
+                _ => {
+                    PENDING.lock().unwrap().take();
+                }
+            }
+            std::thread::sleep(Duration::from_secs(settings.check_interval_secs.max(2)));
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动热文件夹检查线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 查看热文件夹设置和最近处理的文件
+#[tauri::command]
+pub fn get_import_folder_status(config: tauri::State<'_, ConfigStore>) -> ImportFolderStatus {
+    let settings = config.get().import_folder;
+    ImportFolderStatus {
+        enabled: settings.enabled,
+        path: settings.path,
+        pending: PENDING
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|pending| pending.len())
+            .unwrap_or(0),
+        recent: RECENT.lock().unwrap().iter().cloned().collect(),
+    }
+}
+
+/// 设置热文件夹（须通过文件夹对话框选择），传入空值时停用
+#[tauri::command]
+pub fn set_import_folder(
+    path: Option<String>,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<ImportFolderStatus> {
+    let path = path.filter(|p| !p.is_empty());
+    if let Some(path) = &path {
+        let folder = PathBuf::from(path);
+        if !dialog::is_selected(&folder) {
+            return Err(AppError::InvalidArgument(
+                "只能使用通过对话框选择的文件夹".to_string(),
+            ));
+        }
+        if !folder.is_dir() {
+            return Err(AppError::InvalidArgument(format!("文件夹不存在: {}", path)));
+        }
+    }
+    config.update(|c| {
+        c.import_folder.enabled = path.is_some();
+        c.import_folder.path = path.clone();
+    })?;
+    PENDING.lock().unwrap().take();
+    tracing::info!("热文件夹已设置为: {:?}", path);
+    Ok(get_import_folder_status(config))
+}
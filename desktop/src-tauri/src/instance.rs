@@ -0,0 +1,369 @@
+// 多用户会话保护
+//
+// 门店电脑常由多个 Windows 账户共用（快速切换用户、远程桌面），每个会话都启动 SmartMart 时
+// 会各自启动一个 Backend，抢占同一个端口并同时写入数据库。
+//
+// 启动 Backend 之前先获取全机唯一的租约（Windows 下为 Global 命名空间的互斥量，跨会话可见），
+// 并把持有者信息写入公共目录下的 instance.json（其他平台为本用户私有目录，租约只在本用户内生效）：
+// - 获取成功：正常启动 Backend
+// - 已被其他会话持有：对方的 Backend 可用且设置允许时直接连接使用，否则提示后退出
+//
+// 互斥量随进程退出自动释放，instance.json 只用于显示持有者，不需要清理。
+//
+// Backend 的访问 Token 不写入 instance.json，而是单独保存在 backend.token 中，
+// 文件权限只允许交互登录的用户（Windows）或本用户（其他平台）读取，其他会话连接时从中读取。
+//
+// 同一会话中重复启动由单实例插件处理（激活已有窗口后退出），这里不再提示。
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::backend;
+
+const LEASE_FILE: &str = "instance.json";
+
+/// 保存 Backend 访问 Token 的文件（与 instance.json 同目录，限制读取权限）
+const TOKEN_FILE: &str = "backend.token";
+
+/// 本进程的会话状态
+static STATE: Mutex<Option<InstanceInfo>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lease {
+    pub pid: u32,
+    /// Windows 会话 ID
+    pub session_id: Option<u32>,
+    pub user: Option<String>,
+    pub port: u16,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceInfo {
+    /// 是否连接的是其他会话中运行的 Backend
+    pub attached: bool,
+    /// 租约持有者（attached 时为其他会话）
+    pub owner: Option<Lease>,
+}
+
+/// 保存租约等信息的目录（Windows 下为全机共享的公共目录，其他平台为本用户私有目录）
+pub fn shared_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    let dir = std::env::var_os("ProgramData")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+        .join("SmartMart");
+    #[cfg(unix)]
+    let dir = unix::private_dir().to_path_buf();
+    dir
+}
+
+fn lease_path() -> PathBuf {
+    shared_dir().join(LEASE_FILE)
+}
+
+fn current_user() -> Option<String> {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .ok()
+}
+
+fn read_lease() -> Option<Lease> {
+    let content = std::fs::read_to_string(lease_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_lease(lease: &Lease) {
+    let path = lease_path();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let written = serde_json::to_string_pretty(lease)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        tracing::warn!("保存会话租约失败: {:?} ({})", path, e);
+    }
+}
+
+/// 保存 Backend 访问 Token（先重新创建文件并限制权限，再写入 Token）
+fn write_token(token: &str) {
+    let path = shared_dir().join(TOKEN_FILE);
+    // 其他用户留下的文件无法修改权限，先删除
+    let _ = std::fs::remove_file(&path);
+    let written = std::fs::File::create(&path).and_then(|mut file| {
+        restrict_token_file(&path)?;
+        file.write_all(token.as_bytes())
+    });
+    if let Err(e) = written {
+        tracing::warn!("保存 Backend 访问 Token 失败: {:?} ({})", path, e);
+    }
+}
+
+/// 读取租约持有者保存的 Backend 访问 Token
+pub fn read_token() -> Option<String> {
+    let path = shared_dir().join(TOKEN_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(token) => Some(token.trim().to_string()).filter(|token| !token.is_empty()),
+        Err(e) => {
+            tracing::warn!("读取 Backend 访问 Token 失败: {:?} ({})", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn restrict_token_file(path: &Path) -> std::io::Result<()> {
+    windows::restrict_file(path)
+}
+
+#[cfg(unix)]
+fn restrict_token_file(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io::{Error, ErrorKind};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+
+    static PRIVATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+    /// 本用户私有的目录：优先使用 $XDG_RUNTIME_DIR，其次是临时目录下按 uid 区分的目录，
+    /// 已存在但不属于本用户的目录不使用（临时目录所有用户可写，可能被他人抢先创建），
+    /// 都不可用时使用主目录下的 .smartmart
+    pub fn private_dir() -> &'static Path {
+        PRIVATE_DIR.get_or_init(|| {
+            // SAFETY: getuid 没有前置条件，总是成功
+            let uid = unsafe { libc::getuid() };
+            let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+                .map(PathBuf::from)
+                .filter(|dir| dir.is_absolute())
+                .map(|dir| dir.join("SmartMart"));
+            let temp_dir = std::env::temp_dir().join(format!("SmartMart-{}", uid));
+            for dir in runtime_dir.into_iter().chain(Some(temp_dir)) {
+                match ensure_private(&dir, uid) {
+                    Ok(()) => return dir,
+                    Err(e) => tracing::warn!("不使用目录 {:?}: {}", dir, e),
+                }
+            }
+            let home_dir = std::env::var_os("HOME")
+                .map(PathBuf::from)
+                .unwrap_or_default()
+                .join(".smartmart");
+            if let Err(e) = std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(&home_dir)
+            {
+                tracing::warn!("创建目录失败: {:?} ({})", home_dir, e);
+            }
+            home_dir
+        })
+    }
+
+    /// 创建权限为 0700 的目录；已存在时检查是否为本用户所有的目录（不跟随符号链接），
+    /// 是则收紧权限，否则返回错误
+    fn ensure_private(dir: &Path, uid: u32) -> std::io::Result<()> {
+        match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        let metadata = std::fs::symlink_metadata(dir)?;
+        if !metadata.is_dir() || metadata.uid() != uid {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!("不是本用户所有的目录 (uid {})", metadata.uid()),
+            ));
+        }
+        if metadata.mode() & 0o077 != 0 {
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows_sys::Win32::Foundation::{
+        GetLastError, LocalFree, ERROR_ACCESS_DENIED, ERROR_ALREADY_EXISTS,
+    };
+    use windows_sys::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows_sys::Win32::Security::{
+        SetFileSecurityW, DACL_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR,
+    };
+    use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+    use windows_sys::Win32::System::Threading::CreateMutexW;
+
+    const MUTEX_NAME: &str = "Global\\SmartMart-Backend";
+
+    /// Token 文件的权限：不继承 ProgramData 的“所有用户可读”，只允许 SYSTEM、管理员、
+    /// 文件所有者完全控制，交互登录的用户（包括远程桌面）读写
+    const TOKEN_FILE_SDDL: &str = "D:P(A;;FA;;;SY)(A;;FA;;;BA)(A;;FA;;;OW)(A;;FRFWSD;;;IU)";
+
+    pub fn restrict_file(path: &Path) -> std::io::Result<()> {
+        let sddl: Vec<u16> = TOKEN_FILE_SDDL.encode_utf16().chain(Some(0)).collect();
+        let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut descriptor: PSECURITY_DESCRIPTOR = std::ptr::null_mut();
+        let ok = unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                sddl.as_ptr(),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ok = unsafe { SetFileSecurityW(path.as_ptr(), DACL_SECURITY_INFORMATION, descriptor) };
+        let result = if ok == 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        };
+        unsafe { LocalFree(descriptor) };
+        result
+    }
+
+    /// 创建全局互斥量，已被其他进程持有时返回 false（句柄在进程结束前不关闭）
+    pub fn try_lock() -> bool {
+        let name: Vec<u16> = MUTEX_NAME.encode_utf16().chain(Some(0)).collect();
+        let handle = unsafe { CreateMutexW(std::ptr::null(), 0, name.as_ptr()) };
+        let error = unsafe { GetLastError() };
+        // 其他用户创建的互斥量无权打开，同样说明已有实例
+        if handle == 0 {
+            return error != ERROR_ACCESS_DENIED;
+        }
+        error != ERROR_ALREADY_EXISTS
+    }
+
+    pub fn session_id() -> Option<u32> {
+        let mut session = 0;
+        let ok = unsafe { ProcessIdToSessionId(std::process::id(), &mut session) };
+        (ok != 0).then_some(session)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn try_lock() -> bool {
+    windows::try_lock()
+}
+
+/// 其他平台：租约文件中的进程仍在运行即视为已被持有
+#[cfg(not(target_os = "windows"))]
+fn try_lock() -> bool {
+    let Some(lease) = read_lease() else {
+        return true;
+    };
+    let system = sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::new().with_processes(sysinfo::ProcessRefreshKind::new()),
+    );
+    lease.pid == std::process::id() || system.process(sysinfo::Pid::from_u32(lease.pid)).is_none()
+}
+
+#[cfg(target_os = "windows")]
+fn session_id() -> Option<u32> {
+    windows::session_id()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn session_id() -> Option<u32> {
+    None
+}
+
+/// 获取 Backend 租约，已被其他会话持有时返回持有者信息
+pub fn acquire(port: u16) -> Result<(), Option<Lease>> {
+    if !try_lock() {
+        let owner = read_lease();
+        tracing::warn!("其他会话已在运行 SmartMart: {:?}", owner);
+        return Err(owner);
+    }
+
+    let lease = Lease {
+        pid: std::process::id(),
+        session_id: session_id(),
+        user: current_user(),
+        port,
+        started_at: chrono::Local::now().to_rfc3339(),
+    };
+    write_lease(&lease);
+    write_token(backend::token());
+    *STATE.lock().unwrap() = Some(InstanceInfo {
+        attached: false,
+        owner: Some(lease),
+    });
+    Ok(())
+}
+
+/// 更新租约中的端口（Backend 因端口被占用改用其他端口时），
+/// 其他会话据此连接本会话的 Backend
+pub fn set_port(port: u16) {
+    let mut state = STATE.lock().unwrap();
+    let Some(lease) = state
+        .as_mut()
+        .filter(|info| !info.attached)
+        .and_then(|info| info.owner.as_mut())
+    else {
+        return;
+    };
+    if lease.port != port {
+        lease.port = port;
+        write_lease(lease);
+    }
+}
+
+/// 记录已连接到其他会话的 Backend
+pub fn attach(owner: Option<Lease>) {
+    tracing::info!("连接其他会话中运行的 Backend: {:?}", owner);
+    *STATE.lock().unwrap() = Some(InstanceInfo {
+        attached: true,
+        owner,
+    });
+}
+
+/// 租约持有者是否为当前会话（同一用户重复启动）
+pub fn is_current_session(owner: &Lease) -> bool {
+    match (owner.session_id, session_id()) {
+        (Some(owner), Some(current)) => owner == current,
+        _ => owner.user.is_some() && owner.user == current_user(),
+    }
+}
+
+/// 提示已有其他会话在运行（在退出前调用）
+pub fn refuse(owner: Option<&Lease>) {
+    let holder = match owner {
+        Some(Lease {
+            user: Some(user), ..
+        }) => format!("用户「{}」", user),
+        _ => "其他用户".to_string(),
+    };
+    let message = format!(
+        "{}已在这台电脑上运行 SmartMart。\n\n同一台电脑同时只能运行一个 SmartMart 服务，\
+         请先在该用户的会话中退出 SmartMart，或切换到该用户继续使用。",
+        holder
+    );
+    tracing::error!("{}", message);
+    tauri::api::dialog::blocking::message(None::<&tauri::Window>, "SmartMart 已在运行", message);
+}
+
+// Tauri 命令
+
+/// 查看是否连接的是其他会话中运行的 Backend
+#[tauri::command]
+pub fn get_instance_info() -> InstanceInfo {
+    STATE.lock().unwrap().clone().unwrap_or(InstanceInfo {
+        attached: false,
+        owner: None,
+    })
+}
@@ -0,0 +1,185 @@
+// 外部工具集成
+//
+// 钱箱工具、税控桥接程序等第三方命令行工具只能通过设置中的 integrations 登记后调用，
+// 页面只能传入工具名和参数，不能指定要运行的程序：
+// - program 和固定参数 args 由设置决定
+// - 页面传入的参数默认不允许，设置了 arg_pattern 时每个参数都必须完整匹配该正则
+// - 超过 timeout_secs 仍未结束时强制结束
+//
+// 运行结果（退出码、标准输出和标准错误）返回给页面，并记录到事件日志。
+
+use regex::Regex;
+use serde::Serialize;
+use std::io::Read;
+use std::process::{Child, Stdio};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::config::{ConfigStore, IntegrationConfig};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::os;
+
+/// 每个输出流最多保留的字节数
+const MAX_OUTPUT_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationInfo {
+    pub name: String,
+    pub description: String,
+    /// 是否允许传入参数
+    pub accepts_args: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationResult {
+    /// 退出码，被强制结束时为空
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    pub timed_out: bool,
+}
+
+/// 检查页面传入的参数
+fn check_args(integration: &IntegrationConfig, args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        return Ok(());
+    }
+    let Some(pattern) = integration.arg_pattern.as_deref().filter(|p| !p.is_empty()) else {
+        return Err("该工具不接受参数".to_string());
+    };
+    let pattern =
+        Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| format!("参数规则无效: {}", e))?;
+    match args.iter().find(|arg| !pattern.is_match(arg)) {
+        Some(arg) => Err(format!("参数不符合规则: {}", arg)),
+        None => Ok(()),
+    }
+}
+
+/// 在后台线程中读取输出流，避免输出过多时子进程阻塞
+fn read_stream<R: Read + Send + 'static>(stream: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(stream) = stream {
+            let mut stream = stream.take(MAX_OUTPUT_BYTES);
+            let _ = stream.read_to_end(&mut buffer);
+            // 丢弃超出部分，让子进程能继续写入
+            let _ = std::io::copy(&mut stream.into_inner(), &mut std::io::sink());
+        }
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+/// 等待子进程结束，超时后强制结束
+fn wait(child: &mut Child, timeout: Duration) -> (Option<i32>, bool) {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return (status.code(), false),
+            Ok(None) if started.elapsed() < timeout => {
+                std::thread::sleep(Duration::from_millis(50))
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return (None, true);
+            }
+            Err(e) => {
+                tracing::warn!("等待外部工具失败: {}", e);
+                return (None, false);
+            }
+        }
+    }
+}
+
+fn run(
+    name: &str,
+    integration: &IntegrationConfig,
+    args: &[String],
+) -> Result<IntegrationResult, String> {
+    let mut command = os::hidden_command(&integration.program);
+    command
+        .args(&integration.args)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = integration.working_dir.as_deref().filter(|d| !d.is_empty()) {
+        command.current_dir(dir);
+    }
+
+    let started = Instant::now();
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("启动 {} 失败: {}", name, e))?;
+    let stdout = read_stream(child.stdout.take());
+    let stderr = read_stream(child.stderr.take());
+    let (exit_code, timed_out) = wait(
+        &mut child,
+        Duration::from_secs(integration.timeout_secs.max(1)),
+    );
+
+    Ok(IntegrationResult {
+        exit_code,
+        success: exit_code == Some(0),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        timed_out,
+    })
+}
+
+// Tauri 命令
+
+/// 列出设置中登记的外部工具
+#[tauri::command]
+pub fn list_integrations(config: tauri::State<'_, ConfigStore>) -> Vec<IntegrationInfo> {
+    config
+        .get()
+        .integrations
+        .into_iter()
+        .map(|(name, integration)| IntegrationInfo {
+            name,
+            description: integration.description,
+            accepts_args: integration.arg_pattern.is_some_and(|p| !p.is_empty()),
+        })
+        .collect()
+}
+
+/// 运行登记的外部工具，返回退出码和输出
+#[tauri::command]
+pub async fn run_integration(
+    name: String,
+    args: Option<Vec<String>>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<IntegrationResult> {
+    let args = args.unwrap_or_default();
+    let integration = app_handle
+        .state::<ConfigStore>()
+        .get()
+        .integrations
+        .remove(&name)
+        .ok_or_else(|| AppError::InvalidArgument(format!("未登记的外部工具: {}", name)))?;
+    check_args(&integration, &args).map_err(AppError::InvalidArgument)?;
+
+    tracing::info!("运行外部工具: {} {:?}", name, args);
+    let result = run(&name, &integration, &args).map_err(AppError::Internal)?;
+    if result.timed_out {
+        tracing::warn!("外部工具 {} 超时，已强制结束", name);
+    } else if !result.success {
+        tracing::warn!("外部工具 {} 退出码: {:?}", name, result.exit_code);
+    }
+    events::record(
+        EventKind::State,
+        format!("运行外部工具: {}", name),
+        serde_json::json!({
+            "args": args,
+            "exit_code": result.exit_code,
+            "timed_out": result.timed_out,
+            "duration_ms": result.duration_ms,
+        }),
+    );
+    Ok(result)
+}
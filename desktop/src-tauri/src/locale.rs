@@ -0,0 +1,472 @@
+// 区域设置
+//
+// 读取系统的语言、时区和数字格式，前端和小票模板据此显示日期和金额，
+// 门店无需手动配置即可与当地习惯一致。
+//
+// Windows 上直接读取用户的区域设置（控制面板中修改的分隔符等也会生效），
+// 其他系统根据语言标签推断常见的默认值。
+//
+// 旧版 WebView 的 Intl 在不同门店的电脑上结果不一致，金额和数字的格式化、
+// 以及解析收银员输入的金额也由这里统一处理（format_money / format_number / parse_amount）。
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{AppError, AppResult};
+
+/// 区域设置的缓存时间（格式化金额时避免每次都读取系统设置）
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+static CACHE: Mutex<Option<(Instant, LocaleInfo)>> = Mutex::new(None);
+
+/// 金额的正数格式，"$" 为货币符号，"n" 为数字（与 Windows 的 ICURRENCY 编号一致）
+const POSITIVE_PATTERNS: [&str; 4] = ["$n", "n$", "$ n", "n $"];
+
+/// 金额的负数格式（与 Windows 的 INEGCURR 编号一致）
+const NEGATIVE_PATTERNS: [&str; 16] = [
+    "($n)", "-$n", "$-n", "$n-", "(n$)", "-n$", "n-$", "n$-", "-n $", "-$ n", "n $-", "$ n-",
+    "$ -n", "n- $", "($ n)", "(n $)",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleInfo {
+    /// BCP 47 语言标签，例如 "zh-CN"
+    pub locale: String,
+    /// 用户首选的语言列表
+    pub languages: Vec<String>,
+    /// 国家或地区代码，例如 "CN"
+    pub region: Option<String>,
+    /// IANA 时区，例如 "Asia/Shanghai"
+    pub timezone: Option<String>,
+    /// Windows 时区名称，例如 "China Standard Time"
+    pub windows_timezone: Option<String>,
+    /// 当前与 UTC 的时差（分钟）
+    pub utc_offset_minutes: i32,
+    /// 每周第一天，0 为星期日（与 JS Date.getDay 一致）
+    pub first_day_of_week: u8,
+    pub decimal_separator: String,
+    pub grouping_separator: String,
+    /// 数字分组大小，例如 [3] 表示每三位分组
+    pub grouping: Vec<u8>,
+    pub currency_symbol: Option<String>,
+    /// ISO 4217 货币代码，例如 "CNY"
+    pub currency_code: Option<String>,
+    /// 金额小数位数
+    pub currency_digits: u8,
+    /// 金额正数格式编号（见 POSITIVE_PATTERNS）
+    pub currency_positive_pattern: u8,
+    /// 金额负数格式编号（见 NEGATIVE_PATTERNS）
+    pub currency_negative_pattern: u8,
+    pub negative_sign: String,
+    /// 系统的短日期格式，例如 "yyyy/M/d"
+    pub short_date_format: Option<String>,
+    /// 系统的时间格式，例如 "H:mm:ss"
+    pub time_format: Option<String>,
+}
+
+/// 语言标签中的地区部分（"zh-Hans-CN" → "CN"）
+fn region_of(locale: &str) -> Option<String> {
+    locale
+        .split(['-', '_'])
+        .skip(1)
+        .find(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|part| part.to_ascii_uppercase())
+}
+
+/// 常见地区的货币（代码、符号、小数位数）
+pub(crate) fn currency_of(region: &str) -> Option<(&'static str, &'static str, u8)> {
+    Some(match region {
+        "CN" => ("CNY", "¥", 2),
+        "HK" => ("HKD", "HK$", 2),
+        "MO" => ("MOP", "MOP$", 2),
+        "TW" => ("TWD", "NT$", 2),
+        "SG" => ("SGD", "S$", 2),
+        "MY" => ("MYR", "RM", 2),
+        "JP" => ("JPY", "¥", 0),
+        "KR" => ("KRW", "₩", 0),
+        "US" => ("USD", "$", 2),
+        "CA" => ("CAD", "$", 2),
+        "AU" => ("AUD", "$", 2),
+        "GB" => ("GBP", "£", 2),
+        "DE" | "FR" | "IT" | "ES" | "NL" | "AT" | "BE" | "PT" | "FI" | "IE" => ("EUR", "€", 2),
+        _ => return None,
+    })
+}
+
+/// 每周从星期日开始的地区（CLDR），其余按星期一处理
+const SUNDAY_FIRST: [&str; 22] = [
+    "BR", "CA", "CN", "HK", "IL", "IN", "JP", "KR", "MO", "MX", "PH", "PK", "PT", "SA", "SG", "TH",
+    "TW", "US", "ZA", "ID", "CO", "PE",
+];
+
+/// 小数点为逗号的语言
+const COMMA_DECIMAL: [&str; 14] = [
+    "de", "fr", "es", "it", "pt", "nl", "ru", "pl", "tr", "sv", "da", "fi", "cs", "id",
+];
+
+/// 根据语言标签推断格式（非 Windows 系统，或读取系统设置失败时）
+fn guess(locale: &str) -> LocaleInfo {
+    let region = region_of(locale);
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let comma = COMMA_DECIMAL.contains(&language.as_str());
+    let currency = region.as_deref().and_then(currency_of);
+
+    LocaleInfo {
+        locale: locale.to_string(),
+        languages: sys_locale::get_locales().collect(),
+        first_day_of_week: match region.as_deref() {
+            Some(region) if SUNDAY_FIRST.contains(&region) => 0,
+            _ => 1,
+        },
+        region,
+        timezone: iana_time_zone::get_timezone().ok(),
+        windows_timezone: None,
+        utc_offset_minutes: chrono::Local::now().offset().local_minus_utc() / 60,
+        decimal_separator: if comma { "," } else { "." }.to_string(),
+        grouping_separator: if comma { "." } else { "," }.to_string(),
+        grouping: vec![3],
+        currency_symbol: currency.map(|(_, symbol, _)| symbol.to_string()),
+        currency_code: currency.map(|(code, _, _)| code.to_string()),
+        currency_digits: currency.map(|(_, _, digits)| digits).unwrap_or(2),
+        currency_positive_pattern: if comma { 3 } else { 0 },
+        currency_negative_pattern: if comma { 8 } else { 1 },
+        negative_sign: "-".to_string(),
+        short_date_format: None,
+        time_format: None,
+    }
+}
+
+/// 解析分组规则（"3;2;0" → [3, 2]）
+#[cfg(target_os = "windows")]
+fn parse_grouping(grouping: &str) -> Vec<u8> {
+    let groups: Vec<u8> = grouping
+        .split(';')
+        .filter_map(|size| size.trim().parse().ok())
+        .filter(|size| *size > 0)
+        .collect();
+    if groups.is_empty() {
+        vec![3]
+    } else {
+        groups
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use windows_sys::Win32::Globalization::{GetLocaleInfoEx, GetUserDefaultLocaleName};
+    use windows_sys::Win32::System::Time::{
+        GetDynamicTimeZoneInformation, DYNAMIC_TIME_ZONE_INFORMATION,
+    };
+
+    fn from_wide(buffer: &[u16]) -> String {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    }
+
+    /// 用户的区域名称（以 0 结尾的 UTF-16，用于 GetLocaleInfoEx）
+    pub fn user_locale() -> Option<Vec<u16>> {
+        let mut buffer = [0u16; 85];
+        let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+        (len > 1).then(|| buffer[..len as usize].to_vec())
+    }
+
+    pub fn name(locale: &[u16]) -> String {
+        from_wide(locale)
+    }
+
+    pub fn info(locale: &[u16], lctype: u32) -> Option<String> {
+        let mut buffer = [0u16; 128];
+        let len = unsafe {
+            GetLocaleInfoEx(
+                locale.as_ptr(),
+                lctype,
+                buffer.as_mut_ptr(),
+                buffer.len() as i32,
+            )
+        };
+        (len > 0)
+            .then(|| from_wide(&buffer))
+            .filter(|s| !s.is_empty())
+    }
+
+    pub fn timezone_key() -> Option<String> {
+        let mut info: DYNAMIC_TIME_ZONE_INFORMATION = unsafe { std::mem::zeroed() };
+        // 返回 TIME_ZONE_ID_INVALID (0xFFFFFFFF) 表示失败
+        if unsafe { GetDynamicTimeZoneInformation(&mut info) } == u32::MAX {
+            return None;
+        }
+        Some(from_wide(&info.TimeZoneKeyName)).filter(|s| !s.is_empty())
+    }
+}
+
+/// 当前的区域设置
+#[cfg(target_os = "windows")]
+pub fn current() -> LocaleInfo {
+    use windows_sys::Win32::Globalization::{
+        LOCALE_ICURRDIGITS, LOCALE_ICURRENCY, LOCALE_IFIRSTDAYOFWEEK, LOCALE_INEGCURR,
+        LOCALE_SCURRENCY, LOCALE_SDECIMAL, LOCALE_SGROUPING, LOCALE_SINTLSYMBOL,
+        LOCALE_SNEGATIVESIGN, LOCALE_SSHORTDATE, LOCALE_STHOUSAND, LOCALE_STIMEFORMAT,
+    };
+
+    let Some(locale) = windows::user_locale() else {
+        return guess(&sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string()));
+    };
+    let mut info = guess(&windows::name(&locale));
+    let get = |lctype| windows::info(&locale, lctype);
+
+    // Windows 的 0 为星期一
+    if let Some(day) = get(LOCALE_IFIRSTDAYOFWEEK).and_then(|d| d.parse::<u8>().ok()) {
+        info.first_day_of_week = (day + 1) % 7;
+    }
+    if let Some(separator) = get(LOCALE_SDECIMAL) {
+        info.decimal_separator = separator;
+    }
+    if let Some(separator) = get(LOCALE_STHOUSAND) {
+        info.grouping_separator = separator;
+    }
+    if let Some(grouping) = get(LOCALE_SGROUPING) {
+        info.grouping = parse_grouping(&grouping);
+    }
+    if let Some(symbol) = get(LOCALE_SCURRENCY) {
+        info.currency_symbol = Some(symbol);
+    }
+    if let Some(code) = get(LOCALE_SINTLSYMBOL) {
+        info.currency_code = Some(code);
+    }
+    if let Some(digits) = get(LOCALE_ICURRDIGITS).and_then(|d| d.parse().ok()) {
+        info.currency_digits = digits;
+    }
+    if let Some(pattern) = get(LOCALE_ICURRENCY).and_then(|p| p.parse().ok()) {
+        info.currency_positive_pattern = pattern;
+    }
+    if let Some(pattern) = get(LOCALE_INEGCURR).and_then(|p| p.parse().ok()) {
+        info.currency_negative_pattern = pattern;
+    }
+    if let Some(sign) = get(LOCALE_SNEGATIVESIGN) {
+        info.negative_sign = sign;
+    }
+    info.short_date_format = get(LOCALE_SSHORTDATE);
+    info.time_format = get(LOCALE_STIMEFORMAT);
+    info.windows_timezone = windows::timezone_key();
+    info
+}
+
+/// 当前的区域设置
+#[cfg(not(target_os = "windows"))]
+pub fn current() -> LocaleInfo {
+    guess(&sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string()))
+}
+
+/// 缓存的区域设置（最多 CACHE_TTL 前读取）
+fn cached() -> LocaleInfo {
+    let mut cache = CACHE.lock().unwrap();
+    match cache.as_ref() {
+        Some((read_at, info)) if read_at.elapsed() < CACHE_TTL => info.clone(),
+        _ => {
+            let info = current();
+            *cache = Some((Instant::now(), info.clone()));
+            info
+        }
+    }
+}
+
+/// 按分组规则插入分组分隔符，最后一个分组大小重复使用（[3, 2] → 12,34,567）
+fn group_digits(digits: &str, grouping: &[u8], separator: &str) -> String {
+    let mut groups = Vec::new();
+    let mut rest = digits;
+    let mut sizes = grouping.iter().copied().filter(|size| *size > 0);
+    let mut size = sizes.next().unwrap_or(3) as usize;
+    while rest.len() > size {
+        let (head, tail) = rest.split_at(rest.len() - size);
+        groups.push(tail);
+        rest = head;
+        size = sizes.next().map(usize::from).unwrap_or(size);
+    }
+    groups.push(rest);
+    groups.reverse();
+    groups.join(separator)
+}
+
+/// 格式化数字的绝对值（四舍五入到 digits 位小数）
+fn format_abs(value: f64, digits: u8, info: &LocaleInfo) -> String {
+    let fixed = format!("{:.*}", digits as usize, value.abs());
+    let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let integer = group_digits(integer, &info.grouping, &info.grouping_separator);
+    if fraction.is_empty() {
+        integer
+    } else {
+        format!("{}{}{}", integer, info.decimal_separator, fraction)
+    }
+}
+
+/// 先按小数位数取整，避免 1.005 之类的浮点误差被格式化成 1.00
+fn round(value: f64, digits: u8) -> f64 {
+    let factor = 10f64.powi(digits as i32);
+    // 1.005 * 100 = 100.49999...，先截到 6 位小数再取整
+    let scaled: f64 = format!("{:.6}", value * factor)
+        .parse()
+        .unwrap_or(value * factor);
+    let rounded = scaled.round() / factor;
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+fn check_finite(value: f64) -> AppResult<()> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidArgument(format!("无效的数字: {}", value)))
+    }
+}
+
+/// 按区域设置格式化金额
+pub fn format_money_with(
+    amount: f64,
+    symbol: Option<&str>,
+    digits: Option<u8>,
+    info: &LocaleInfo,
+) -> String {
+    let digits = digits.unwrap_or(info.currency_digits).min(8);
+    let amount = round(amount, digits);
+    let number = format_abs(amount, digits, info);
+    let symbol = symbol
+        .or(info.currency_symbol.as_deref())
+        .unwrap_or_default();
+
+    let pattern = if amount < 0.0 {
+        NEGATIVE_PATTERNS
+            .get(info.currency_negative_pattern as usize)
+            .unwrap_or(&NEGATIVE_PATTERNS[1])
+    } else {
+        POSITIVE_PATTERNS
+            .get(info.currency_positive_pattern as usize)
+            .unwrap_or(&POSITIVE_PATTERNS[0])
+    };
+
+    let mut formatted = String::new();
+    for c in pattern.chars() {
+        match c {
+            '$' => formatted.push_str(symbol),
+            'n' => formatted.push_str(&number),
+            '-' => formatted.push_str(&info.negative_sign),
+            c => formatted.push(c),
+        }
+    }
+    formatted.trim().to_string()
+}
+
+/// 按区域设置格式化数字
+pub fn format_number_with(value: f64, digits: u8, info: &LocaleInfo) -> String {
+    let digits = digits.min(8);
+    let value = round(value, digits);
+    let number = format_abs(value, digits, info);
+    if value < 0.0 {
+        format!("{}{}", info.negative_sign, number)
+    } else {
+        number
+    }
+}
+
+/// 解析输入的金额
+///
+/// 支持全角数字、货币符号和单位（¥、元等）、空格、括号或尾部负号表示负数，
+/// 同时出现 "." 和 "," 时以最后出现的为小数点，只出现一种时结合区域设置和位数判断。
+pub fn parse_amount_with(text: &str, info: &LocaleInfo) -> Result<f64, String> {
+    let mut negative = false;
+    let mut cleaned = String::new();
+    for c in text.trim().chars() {
+        // 全角字符转为半角
+        let c = match c {
+            '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+            '．' | '。' => '.',
+            '，' => ',',
+            '－' | '−' => '-',
+            '（' => '(',
+            '）' => ')',
+            c => c,
+        };
+        match c {
+            '0'..='9' | '.' | ',' => cleaned.push(c),
+            '-' | '(' | ')' => negative = true,
+            c if info.decimal_separator.contains(c) => cleaned.push('.'),
+            // 分组分隔符（空格、撇号等）、货币符号和单位
+            _ => {}
+        }
+    }
+    if cleaned.is_empty() {
+        return Err(format!("无法识别的金额: {}", text));
+    }
+
+    let last_dot = cleaned.rfind('.');
+    let last_comma = cleaned.rfind(',');
+    let decimal = match (last_dot, last_comma) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(position), None) | (None, Some(position)) => {
+            let separator = cleaned.as_bytes()[position] as char;
+            let count = cleaned.matches(separator).count();
+            let after = cleaned.len() - position - 1;
+            let locale_decimal = info.decimal_separator.starts_with(separator);
+            // 出现多次的只能是分组分隔符；正好三位小数且与区域设置的小数点不同时也按分组处理
+            if count > 1 || (after == 3 && !locale_decimal) {
+                None
+            } else {
+                Some(position)
+            }
+        }
+        (None, None) => None,
+    };
+
+    let number: String = cleaned
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            '0'..='9' => Some(c),
+            _ if Some(i) == decimal => Some('.'),
+            _ => None,
+        })
+        .collect();
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("无法识别的金额: {}", text))?;
+    Ok(if negative { -value } else { value })
+}
+
+// Tauri 命令
+
+/// 读取系统的语言、时区和数字格式
+#[tauri::command]
+pub fn get_locale_info() -> LocaleInfo {
+    current()
+}
+
+/// 格式化金额，symbol 和 digits 为空时使用区域设置的货币符号和小数位数
+#[tauri::command]
+pub fn format_money(amount: f64, symbol: Option<String>, digits: Option<u8>) -> AppResult<String> {
+    check_finite(amount)?;
+    Ok(format_money_with(
+        amount,
+        symbol.as_deref(),
+        digits,
+        &cached(),
+    ))
+}
+
+/// 格式化数字（默认保留 2 位小数）
+#[tauri::command]
+pub fn format_number(value: f64, digits: Option<u8>) -> AppResult<String> {
+    check_finite(value)?;
+    Ok(format_number_with(value, digits.unwrap_or(2), &cached()))
+}
+
+/// 解析收银员输入的金额，例如 "¥1,234.50"、"１２.５"、"(3.00)"、"1.234,50"
+#[tauri::command]
+pub fn parse_amount(text: String) -> AppResult<f64> {
+    parse_amount_with(&text, &cached()).map_err(AppError::InvalidArgument)
+}
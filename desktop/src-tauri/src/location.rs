@@ -0,0 +1,195 @@
+// 门店位置
+//
+// 首次运行的设置向导调用 detect_location 预填门店的时区、货币和税务地区，减少因默认值不对
+// 打印出错误小票的情况。只在用户同意后由前端调用，不会自动执行：
+// - 优先使用系统的国家或地区设置（Windows 的“国家或地区”，其他系统取语言标签中的地区）
+//   和系统时区
+// - 系统没有地区设置且允许联网查询时，根据公网 IP 查询所在地区（会向 IP_LOOKUP_URL 发送请求）
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+use crate::{http, locale};
+
+/// 根据公网 IP 查询位置的服务
+const IP_LOOKUP_URL: &str = "https://ipapi.co/json/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocationSource {
+    /// 系统的地区设置
+    System,
+    /// 公网 IP 查询
+    Ip,
+}
+
+/// 门店的默认设置
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreDefaults {
+    pub source: LocationSource,
+    /// 国家或地区代码，例如 "CN"
+    pub country: String,
+    /// 省 / 州代码（仅 IP 查询时提供），例如 "CA"
+    pub subdivision: Option<String>,
+    pub city: Option<String>,
+    /// IANA 时区，例如 "Asia/Shanghai"
+    pub timezone: Option<String>,
+    /// ISO 4217 货币代码
+    pub currency_code: Option<String>,
+    pub currency_symbol: Option<String>,
+    pub currency_digits: u8,
+    /// 税务地区：国家代码，销售税按州计算的国家为“国家-州”，例如 "US-CA"
+    pub tax_region: String,
+}
+
+/// ipapi.co 的查询结果
+#[derive(Debug, Deserialize)]
+struct IpLocation {
+    country_code: Option<String>,
+    region_code: Option<String>,
+    city: Option<String>,
+    timezone: Option<String>,
+    currency: Option<String>,
+    #[serde(default)]
+    error: bool,
+    reason: Option<String>,
+}
+
+/// 销售税按州 / 省计算的国家
+const SUBDIVISION_TAX: [&str; 3] = ["US", "CA", "IN"];
+
+fn tax_region(country: &str, subdivision: Option<&str>) -> String {
+    match subdivision {
+        Some(subdivision) if SUBDIVISION_TAX.contains(&country) => {
+            format!("{}-{}", country, subdivision)
+        }
+        _ => country.to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn system_country() -> Option<String> {
+    use windows_sys::Win32::Globalization::{
+        GetGeoInfoW, GetUserDefaultGeoName, GetUserGeoID, GEOCLASS_NATION, GEO_ISO2,
+    };
+
+    let from_wide = |buffer: &[u16]| {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    };
+    let mut buffer = [0u16; 16];
+    // Windows 10 1709 及以上
+    let mut len = unsafe { GetUserDefaultGeoName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    if len <= 1 {
+        let id = unsafe { GetUserGeoID(GEOCLASS_NATION) };
+        // GEOID_NOT_AVAILABLE
+        if id == -1 {
+            return None;
+        }
+        len = unsafe { GetGeoInfoW(id, GEO_ISO2, buffer.as_mut_ptr(), buffer.len() as i32, 0) };
+    }
+    // “001” 表示全球（未设置）
+    Some(from_wide(&buffer))
+        .filter(|_| len > 1)
+        .filter(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|code| code.to_ascii_uppercase())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn system_country() -> Option<String> {
+    None
+}
+
+/// 根据系统设置得到默认值
+fn from_system() -> Option<StoreDefaults> {
+    let info = locale::current();
+    let country = system_country().or(info.region.clone())?;
+    // 系统地区与区域格式的地区不同时，货币以系统地区为准
+    let (currency_code, currency_symbol, currency_digits) =
+        if info.region.as_ref() == Some(&country) {
+            (
+                info.currency_code,
+                info.currency_symbol,
+                info.currency_digits,
+            )
+        } else {
+            match locale::currency_of(&country) {
+                Some((code, symbol, digits)) => {
+                    (Some(code.to_string()), Some(symbol.to_string()), digits)
+                }
+                None => (None, None, 2),
+            }
+        };
+    Some(StoreDefaults {
+        source: LocationSource::System,
+        tax_region: tax_region(&country, None),
+        country,
+        subdivision: None,
+        city: None,
+        timezone: info.timezone,
+        currency_code,
+        currency_symbol,
+        currency_digits,
+    })
+}
+
+/// 根据公网 IP 查询默认值
+fn from_ip() -> Result<StoreDefaults, String> {
+    let location: IpLocation = http::external()
+        .get(IP_LOOKUP_URL)
+        .call()
+        .map_err(|e| format!("查询位置失败: {}", e))?
+        .into_json()
+        .map_err(|e| format!("解析位置失败: {}", e))?;
+    if location.error {
+        return Err(format!(
+            "查询位置失败: {}",
+            location.reason.unwrap_or_default()
+        ));
+    }
+    let country = location
+        .country_code
+        .filter(|code| code.len() == 2)
+        .map(|code| code.to_ascii_uppercase())
+        .ok_or_else(|| "未能确定所在地区".to_string())?;
+    let currency = locale::currency_of(&country);
+    Ok(StoreDefaults {
+        source: LocationSource::Ip,
+        tax_region: tax_region(&country, location.region_code.as_deref()),
+        country,
+        subdivision: location.region_code,
+        city: location.city,
+        timezone: location.timezone,
+        currency_code: location
+            .currency
+            .or(currency.map(|(code, _, _)| code.to_string())),
+        currency_symbol: currency.map(|(_, symbol, _)| symbol.to_string()),
+        currency_digits: currency.map(|(_, _, digits)| digits).unwrap_or(2),
+    })
+}
+
+// Tauri 命令
+
+/// 检测门店位置，返回设置向导预填的时区、货币和税务地区。
+/// allow_ip_lookup 为 true 时，系统没有地区设置则根据公网 IP 查询
+#[tauri::command]
+pub async fn detect_location(allow_ip_lookup: Option<bool>) -> AppResult<StoreDefaults> {
+    if let Some(defaults) = from_system() {
+        tracing::info!(
+            "根据系统设置检测到门店地区: {} ({:?})",
+            defaults.country,
+            defaults.timezone
+        );
+        return Ok(defaults);
+    }
+    if !allow_ip_lookup.unwrap_or(false) {
+        return Err(AppError::Config("系统没有设置国家或地区".to_string()));
+    }
+    let defaults = from_ip().map_err(AppError::Network)?;
+    tracing::info!(
+        "根据公网 IP 检测到门店地区: {} ({:?})",
+        defaults.tax_region,
+        defaults.timezone
+    );
+    Ok(defaults)
+}
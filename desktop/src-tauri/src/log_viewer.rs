@@ -0,0 +1,280 @@
+// 日志查询
+//
+// 供前端日志查看器使用：按来源（壳程序 / Backend）、级别、时间范围和关键字
+// 查询日志文件，分页返回结构化条目（最新的在前），前端无需直接访问文件系统。
+//
+// 日志行格式为 `<时间> <级别> <模块>: <内容>`，不以时间开头的行（例如调用栈）
+// 视为上一条日志的续行。
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, AppResult};
+use crate::logging;
+use crate::reporting::ReportErr;
+
+/// 单页最多返回的条目数
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSource {
+    Shell,
+    Backend,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogQuery {
+    pub source: LogSource,
+    /// 最低级别，例如 "warn" 返回 WARN 和 ERROR
+    pub min_level: Option<String>,
+    /// RFC 3339 时间
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// 关键字（不区分大小写）
+    pub search: Option<String>,
+    /// 页码，从 0 开始
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl Default for LogQuery {
+    fn default() -> Self {
+        Self {
+            source: LogSource::Shell,
+            min_level: None,
+            since: None,
+            until: None,
+            search: None,
+            page: 0,
+            page_size: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: Option<String>,
+    pub level: String,
+    pub target: Option<String>,
+    pub message: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogPage {
+    pub entries: Vec<LogEntry>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogFileInfo {
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+fn source_dir(source: LogSource) -> Option<PathBuf> {
+    match source {
+        LogSource::Shell => logging::log_dir().map(Path::to_path_buf),
+        LogSource::Backend => logging::backend_log_dir(),
+    }
+}
+
+/// 某个来源的日志文件，按文件名（日期）从新到旧排序
+fn log_files(source: LogSource) -> Vec<PathBuf> {
+    let Some(dir) = source_dir(source) else {
+        return Vec::new();
+    };
+    let prefix = match source {
+        LogSource::Shell => "shell.",
+        LogSource::Backend => "backend.",
+    };
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name.starts_with(prefix) && name.ends_with(".log"))
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    files
+}
+
+fn level_rank(level: &str) -> usize {
+    match level.to_ascii_uppercase().as_str() {
+        "TRACE" => 0,
+        "DEBUG" => 1,
+        "INFO" => 2,
+        "WARN" | "WARNING" => 3,
+        "ERROR" | "CRITICAL" => 4,
+        _ => 2,
+    }
+}
+
+/// 解析一个日志文件，按文件中的顺序返回条目
+fn parse_file(path: &Path) -> Vec<LogEntry> {
+    let Ok(content) = std::fs::read(path) else {
+        return Vec::new();
+    };
+    let content = String::from_utf8_lossy(&content);
+    let file = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut parts = line.splitn(3, ' ').filter(|p| !p.is_empty());
+        let timestamp = parts.next().filter(|ts| DateTime::parse_from_rfc3339(ts).is_ok());
+
+        let Some(timestamp) = timestamp else {
+            // 续行
+            match entries.last_mut() {
+                Some(last) => {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+                None => entries.push(LogEntry {
+                    timestamp: None,
+                    level: "INFO".to_string(),
+                    target: None,
+                    message: line.to_string(),
+                    file: file.clone(),
+                    line: index + 1,
+                }),
+            }
+            continue;
+        };
+
+        let rest = line[timestamp.len()..].trim_start();
+        let (level, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        let rest = rest.trim_start();
+        let (target, message) = match rest.split_once(": ") {
+            Some((target, message)) if !target.contains(' ') => {
+                (Some(target.to_string()), message.to_string())
+            }
+            _ => (None, rest.to_string()),
+        };
+
+        entries.push(LogEntry {
+            timestamp: Some(timestamp.to_string()),
+            level: level.to_string(),
+            target,
+            message,
+            file: file.clone(),
+            line: index + 1,
+        });
+    }
+    entries
+}
+
+fn parse_time(value: &Option<String>) -> AppResult<Option<DateTime<FixedOffset>>> {
+    value
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v)
+                .map_err(|e| AppError::InvalidArgument(format!("时间格式错误 {}: {}", v, e)))
+        })
+        .transpose()
+}
+
+pub fn query(query: &LogQuery) -> AppResult<LogPage> {
+    let since = parse_time(&query.since)?;
+    let until = parse_time(&query.until)?;
+    let min_rank = query.min_level.as_deref().map(level_rank).unwrap_or(0);
+    let search = query
+        .search
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .map(str::to_lowercase);
+    let page_size = query.page_size.clamp(1, MAX_PAGE_SIZE);
+
+    let matches = |entry: &LogEntry| {
+        if level_rank(&entry.level) < min_rank {
+            return false;
+        }
+        if since.is_some() || until.is_some() {
+            let Some(ts) = entry
+                .timestamp
+                .as_deref()
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            else {
+                return false;
+            };
+            if since.is_some_and(|since| ts < since) || until.is_some_and(|until| ts > until) {
+                return false;
+            }
+        }
+        search.as_deref().map_or(true, |search| {
+            entry.message.to_lowercase().contains(search)
+                || entry
+                    .target
+                    .as_deref()
+                    .is_some_and(|t| t.to_lowercase().contains(search))
+        })
+    };
+
+    let mut total = 0;
+    let mut entries = Vec::new();
+    let skip = query.page * page_size;
+
+    for file in log_files(query.source) {
+        for entry in parse_file(&file).into_iter().rev().filter(|e| matches(e)) {
+            if total >= skip && entries.len() < page_size {
+                entries.push(entry);
+            }
+            total += 1;
+        }
+    }
+
+    Ok(LogPage {
+        entries,
+        total,
+        page: query.page,
+        page_size,
+    })
+}
+
+// Tauri 命令
+
+#[tauri::command]
+pub async fn query_logs(query: LogQuery) -> AppResult<LogPage> {
+    self::query(&query).reported("query_logs")
+}
+
+#[tauri::command]
+pub async fn list_log_files(source: LogSource) -> Vec<LogFileInfo> {
+    log_files(source)
+        .iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(path).ok();
+            LogFileInfo {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or_default(),
+                modified: metadata
+                    .and_then(|m| m.modified().ok())
+                    .map(|t| DateTime::<chrono::Local>::from(t).to_rfc3339()),
+            }
+        })
+        .collect()
+}
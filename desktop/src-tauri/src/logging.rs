@@ -0,0 +1,237 @@
+// 日志模块
+//
+// 使用 tracing 输出日志：
+// - 写入应用日志目录下按天轮转的 shell.YYYY-MM-DD.log（保留最近 14 天）
+// - 开发模式下同时输出到控制台
+// - 支持按模块设置级别（EnvFilter 语法，例如 "warn,smartmart_desktop::backend=debug"）
+// - 运行时可通过命令调整级别，无需重启；Backend 的日志级别也通过这里转发调整
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::State;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::time::ChronoLocal;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::backend::{self, BackendProcess};
+use crate::error::{AppError, AppResult};
+use crate::reporting::ReportErr;
+
+/// 默认级别：第三方库只记录 warn，壳程序自身记录 info
+pub const DEFAULT_FILTER: &str = "warn,smartmart_desktop=info";
+
+/// 覆盖默认级别的环境变量
+const FILTER_ENV: &str = "SMARTMART_LOG";
+
+/// 日志时间格式（本地时间，带时区，便于与门店时间对照）
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+
+/// 保留的日志文件数量（按天轮转）
+const MAX_LOG_FILES: usize = 14;
+
+/// 壳程序自身的日志目标
+const SHELL_TARGET: &str = "smartmart_desktop";
+
+/// 支持的日志级别
+const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// 运行时设置的 Backend 日志级别，Backend 重启后通过环境变量继续生效
+static BACKEND_LEVEL: Mutex<Option<String>> = Mutex::new(None);
+
+/// 初始化日志系统，返回日志目录
+pub fn init(config: &tauri::Config) -> PathBuf {
+    let dir = tauri::api::path::app_log_dir(config)
+        .unwrap_or_else(|| std::env::temp_dir().join("smartmart").join("logs"));
+    let _ = std::fs::create_dir_all(&dir);
+    let _ = LOG_DIR.set(dir.clone());
+
+    let filter = std::env::var(FILTER_ENV)
+        .ok()
+        .and_then(|s| EnvFilter::try_new(s).ok())
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("shell")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir);
+
+    let (file_layer, file_error) = match appender {
+        Ok(writer) => (
+            Some(
+                fmt::layer()
+                    .with_ansi(false)
+                    .with_timer(ChronoLocal::new(TIME_FORMAT.to_string()))
+                    .with_writer(writer),
+            ),
+            None,
+        ),
+        Err(e) => (None, Some(e)),
+    };
+
+    // 开发模式下同时输出到控制台
+    let stdout_layer = cfg!(debug_assertions)
+        .then(|| fmt::layer().with_timer(ChronoLocal::new(TIME_FORMAT.to_string())));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(stdout_layer)
+        .init();
+
+    let _ = FILTER_HANDLE.set(handle);
+
+    match file_error {
+        None => tracing::info!("日志目录: {:?}", dir),
+        Some(e) => tracing::warn!("无法创建日志文件，仅输出到控制台: {:?} ({})", dir, e),
+    }
+
+    dir
+}
+
+/// 日志目录（init 之后可用）
+pub fn log_dir() -> Option<&'static Path> {
+    LOG_DIR.get().map(PathBuf::as_path)
+}
+
+/// Backend 输出的日志目录（<日志目录>/backend）
+pub fn backend_log_dir() -> Option<PathBuf> {
+    log_dir().map(|dir| dir.join("backend"))
+}
+
+/// 读取最新壳程序日志文件的最后 `lines` 行
+pub fn recent_lines(lines: usize) -> Vec<String> {
+    let Some(dir) = log_dir() else {
+        return Vec::new();
+    };
+
+    let latest = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("shell.") && name.ends_with(".log")
+        })
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok());
+
+    let Some(content) = latest.and_then(|entry| std::fs::read(entry.path()).ok()) else {
+        return Vec::new();
+    };
+
+    let content = String::from_utf8_lossy(&content);
+    let all: Vec<&str> = content.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    all[start..].iter().map(|line| line.to_string()).collect()
+}
+
+/// Backend 的日志级别（Python logging 命名），未调整过时为 None
+pub fn backend_level() -> Option<String> {
+    BACKEND_LEVEL.lock().ok().and_then(|level| level.clone())
+}
+
+/// 转换为 Python logging 的级别名称
+fn python_level(level: &str) -> &str {
+    match level {
+        "warn" => "warning",
+        "off" => "critical",
+        other => other,
+    }
+}
+
+/// 在现有过滤规则中替换（或追加）某个模块的级别
+///
+/// module 为空或 "*" 时修改全局默认级别，"shell" 是壳程序自身模块的简写
+fn merge_directive(current: &str, module: &str, level: &str) -> String {
+    let module = match module {
+        "shell" => SHELL_TARGET,
+        other => other,
+    };
+    let is_default = module.is_empty() || module == "*";
+
+    let mut directives: Vec<String> = current
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .filter(|d| match d.split_once('=') {
+            Some((target, _)) => target != module,
+            None => !is_default,
+        })
+        .map(str::to_string)
+        .collect();
+
+    if is_default {
+        directives.insert(0, level.to_string());
+    } else {
+        directives.push(format!("{}={}", module, level));
+    }
+    directives.join(",")
+}
+
+// Tauri 命令
+
+/// 获取当前日志级别（EnvFilter 语法）
+#[tauri::command]
+pub fn get_log_filter() -> AppResult<String> {
+    FILTER_HANDLE
+        .get()
+        .ok_or_else(|| AppError::NotInitialized("日志系统未初始化".to_string()))?
+        .with_current(|filter| filter.to_string())
+        .map_err(|e| AppError::Internal(format!("获取日志级别失败: {}", e)))
+}
+
+/// 运行时调整日志级别，例如 "info,smartmart_desktop::backend=debug"
+#[tauri::command]
+pub fn set_log_filter(filter: String) -> AppResult<()> {
+    let new_filter = EnvFilter::try_new(&filter)
+        .map_err(|e| AppError::InvalidArgument(format!("日志级别格式错误: {}", e)))?;
+
+    FILTER_HANDLE
+        .get()
+        .ok_or_else(|| AppError::NotInitialized("日志系统未初始化".to_string()))?
+        .reload(new_filter)
+        .map_err(|e| AppError::Internal(format!("调整日志级别失败: {}", e)))?;
+
+    tracing::info!("日志级别已调整为: {}", filter);
+    Ok(())
+}
+
+/// 调整单个模块的日志级别，无需重启
+///
+/// - module 为 "backend" 时调整 Backend 的根日志级别，"backend.<logger>" 调整指定的 Python 日志器
+/// - module 为空或 "*" 时调整壳程序的默认级别，其余值视为壳程序的模块路径
+///   （例如 "smartmart_desktop::backend"，"shell" 为壳程序全部模块）
+#[tauri::command]
+pub fn set_log_level(
+    module: String,
+    level: String,
+    backend: State<'_, Mutex<BackendProcess>>,
+) -> AppResult<()> {
+    let level = level.to_lowercase();
+    if !LEVELS.contains(&level.as_str()) {
+        return Err(AppError::InvalidArgument(format!("无效的日志级别: {}", level)));
+    }
+
+    if module == "backend" || module.starts_with("backend.") {
+        let logger = module.strip_prefix("backend.").map(str::to_string);
+        let port = backend.lock().unwrap().port();
+        backend::set_log_level(port, logger.as_deref(), python_level(&level))
+            .reported("set_log_level")?;
+
+        if logger.is_none() {
+            *BACKEND_LEVEL.lock().unwrap() = Some(python_level(&level).to_string());
+        }
+        tracing::info!("Backend 日志级别已调整: {}={}", module, level);
+        return Ok(());
+    }
+
+    let current = get_log_filter()?;
+    set_log_filter(merge_directive(&current, &module, &level)).reported("set_log_level")
+}
@@ -1,76 +1,358 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod accessibility;
+mod assist;
 mod backend;
+mod backend_logs;
+mod backup;
+mod battery;
+mod bluetooth;
+mod clipboard;
+mod config;
+mod crash;
+mod cron;
+mod day_close;
+mod dialog;
+mod disk;
+mod error;
+mod events;
+mod files;
+mod frontend;
+mod health;
+mod heartbeat;
+mod http;
+mod import;
+mod import_folder;
+mod instance;
+mod integrations;
+mod locale;
+mod location;
+mod log_viewer;
+mod logging;
+mod metrics;
+mod notify;
+mod os;
+mod pairing;
+mod performance;
+mod power;
+mod printer;
+mod proxy;
+mod readiness;
+mod recording;
+mod reminders;
+mod report_pdf;
+mod reporting;
+mod scanner;
+mod scheduler;
+mod screenshot;
+mod shortcuts;
+mod shutdown;
+mod speech;
+mod startup;
+mod storage;
+mod support;
+mod system_info;
+mod system_proxy;
+mod telemetry;
+mod tray;
+mod watchdog;
+mod wol;
 
 use std::sync::Mutex;
 use backend::BackendProcess;
+use tauri::Manager;
+use config::ConfigStore;
+use error::{AppError, AppResult};
+use reporting::ReportErr;
 use tauri_plugin_autostart::MacosLauncher;
 
 // 开机自启动相关命令
 #[tauri::command]
-fn autostart_enable(app_handle: tauri::AppHandle) -> Result<(), String> {
+fn autostart_enable(app_handle: tauri::AppHandle) -> AppResult<()> {
     use tauri_plugin_autostart::ManagerExt;
     app_handle
         .autolaunch()
         .enable()
-        .map_err(|e| format!("启用自启动失败: {}", e))
+        .map_err(|e| AppError::Autostart(format!("启用自启动失败: {}", e)))
+        .reported("autostart_enable")
 }
 
 #[tauri::command]
-fn autostart_disable(app_handle: tauri::AppHandle) -> Result<(), String> {
+fn autostart_disable(app_handle: tauri::AppHandle) -> AppResult<()> {
     use tauri_plugin_autostart::ManagerExt;
     app_handle
         .autolaunch()
         .disable()
-        .map_err(|e| format!("禁用自启动失败: {}", e))
+        .map_err(|e| AppError::Autostart(format!("禁用自启动失败: {}", e)))
+        .reported("autostart_disable")
 }
 
 #[tauri::command]
-fn autostart_is_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+fn autostart_is_enabled(app_handle: tauri::AppHandle) -> AppResult<bool> {
     use tauri_plugin_autostart::ManagerExt;
     app_handle
         .autolaunch()
         .is_enabled()
-        .map_err(|e| format!("获取自启动状态失败: {}", e))
+        .map_err(|e| AppError::Autostart(format!("获取自启动状态失败: {}", e)))
+        .reported("autostart_is_enabled")
 }
 
 fn main() {
-    // 创建 Backend 进程管理器
-    let mut backend = BackendProcess::new();
-    
+    startup::begin();
+    let context = tauri::generate_context!();
+
+    // 初始化日志（需在其他模块输出日志之前）
+    let log_dir = logging::init(context.config());
+    crash::install(&log_dir);
+
+    // 加载配置，按用户选择开启错误上报
+    let config = ConfigStore::load(context.config());
+    tray::init(&config);
+    if let Err(e) = reporting::apply(&config.get().crash_reporting) {
+        tracing::warn!("开启错误上报失败: {}", e);
+    }
+    system_proxy::init(&config.get().proxy);
+    performance::init(&config.get().performance);
+    backend::set_resource_dir(tauri::api::path::resource_dir(
+        context.package_info(),
+        &tauri::Env::default(),
+    ));
+
+    // 其他用户会话已在运行 SmartMart：能连上其 Backend 就直接使用，不再启动第二个
+    let backend_settings = config.get().backend;
+    let attached = match instance::acquire(backend_settings.port) {
+        Ok(()) => None,
+        Err(owner) => {
+            let port = owner.as_ref().map_or(backend_settings.port, |o| o.port);
+            if owner.as_ref().is_some_and(instance::is_current_session) {
+                // 本会话重复启动：不启动 Backend，由单实例插件激活已有窗口后退出
+                tracing::info!("SmartMart 已在本会话中运行");
+                Some(port)
+            } else if let Some(token) = config
+                .get()
+                .instance
+                .attach_existing
+                .then(instance::read_token)
+                .flatten()
+                .filter(|_| backend::check_health(port).is_ok())
+            {
+                // 使用已在运行的 Backend 时沿用其访问 Token
+                backend::use_token(token);
+                instance::attach(owner);
+                Some(port)
+            } else {
+                instance::refuse(owner.as_ref());
+                std::process::exit(1);
+            }
+        }
+    };
+
     // 仅在发布模式下自动启动 Backend
     // 开发模式下需要手动在单独终端启动 backend
     #[cfg(not(debug_assertions))]
-    {
-        println!("🚀 [生产模式] 启动 Backend 服务...");
-        let port = 8000;
-        if let Err(e) = backend.start(port) {
-            eprintln!("❌ 启动 Backend 失败: {}", e);
-            // 继续运行，但 Backend 功能不可用
+    let backend = {
+        let mut backend = BackendProcess::new(&backend_settings);
+        if let Some(port) = attached {
+            tracing::info!("[生产模式] 使用其他会话中运行的 Backend");
+            backend.attach(port);
+        } else {
+            tracing::info!("[生产模式] 启动 Backend 服务...");
+            if let Err(e) = backend.start(backend_settings.port) {
+                tracing::error!("启动 Backend 失败: {}", e);
+                // 继续运行，但 Backend 功能不可用
+            }
         }
-    }
-    
+        backend
+    };
+
     #[cfg(debug_assertions)]
-    {
-        println!("ℹ️ [开发模式] 请在单独的终端手动启动 Backend:");
-        println!("   cd backend && uv run uvicorn app.main:app --reload --host 0.0.0.0 --port 8000");
-    }
+    let backend = {
+        let mut backend = BackendProcess::new(&backend_settings);
+        if let Some(port) = attached {
+            tracing::info!("[开发模式] 使用其他会话中运行的 Backend");
+            backend.attach(port);
+        } else {
+            tracing::info!("[开发模式] 请在单独的终端手动启动 Backend:");
+            tracing::info!("   cd backend && uv run uvicorn app.main:app --reload --host {} --port {}", backend_settings.host, backend_settings.port);
+        }
+        backend
+    };
+
+    // 开机自启动的参数来自配置（默认只在托盘中运行），插件要求 'static 字符串
+    let autostart_args: Vec<&'static str> = config
+        .get()
+        .launch
+        .autostart_args
+        .into_iter()
+        .map(|arg| &*Box::leak(arg.into_boxed_str()))
+        .collect();
 
     tauri::Builder::default()
+        // 需最先注册：重复启动时激活已有的主窗口，新进程直接退出
+        .plugin(tauri_plugin_single_instance::init(|app_handle, _args, _cwd| {
+            tracing::info!("再次启动 SmartMart，激活已有窗口");
+            tray::show_main_window(app_handle);
+        }))
         .plugin(tauri_plugin_autostart::init(
             MacosLauncher::LaunchAgent,
-            Some(vec!["--minimized"]), // 可选参数：启动时最小化
+            Some(autostart_args),
         ))
         .manage(Mutex::new(backend))
+        .manage(config)
+        .manage(Mutex::new(watchdog::LatencyTracker::default()))
+        .system_tray(tray::system_tray())
+        .on_system_tray_event(tray::handle_event)
+        .setup(|app| {
+            startup::record_from("start", "shell_init", true);
+            startup::mark("setup");
+            // 主窗口创建时隐藏，显示启动画面，Backend 就绪后再显示主窗口
+            readiness::start(&app.handle());
+            crash::check_previous(&app.handle());
+            watchdog::start(app.handle());
+            backend::supervise(app.handle());
+            backend_logs::start(&app.handle());
+            telemetry::start(app.handle());
+            heartbeat::start(app.handle());
+            metrics::start(app.handle());
+            storage::start(app.handle());
+            import_folder::start(app.handle());
+            disk::start(app.handle());
+            reminders::start(app.handle());
+            report_pdf::register_tasks();
+            day_close::register_tasks(&app.state::<ConfigStore>());
+            backup::register_tasks();
+            wol::register_tasks();
+            scheduler::start(app.handle());
+            shortcuts::start(app.handle());
+            scanner::start(&app.handle());
+            power::start(&app.handle());
+            battery::start(app.handle());
+            accessibility::start(&app.handle());
+            shutdown::start(&app.handle());
+
+            Ok(())
+        })
+        .on_window_event(|event| {
+            import::handle_window_event(event.window(), event.event());
+            tray::handle_window_event(event.window(), event.event());
+            shutdown::handle_window_event(event.window(), event.event());
+        })
+        .on_page_load(|window, _| {
+            if report_pdf::is_report_window(&window) {
+                report_pdf::page_loaded(&window);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             backend::get_backend_status,
+            backend::get_backend_port,
+            backend::get_backend_credentials,
             backend::restart_backend,
+            backend_logs::get_backend_logs,
+            backend_logs::tail_backend_logs,
+            backup::create_backup,
+            backup::restore_backup,
+            battery::get_battery_status,
+            accessibility::get_accessibility_settings,
+            performance::get_performance_mode,
+            performance::set_performance_mode,
             autostart_enable,
             autostart_disable,
             autostart_is_enabled,
+            clipboard::clipboard_read,
+            clipboard::clipboard_write,
+            config::get_config,
+            config::set_config,
+            dialog::pick_file,
+            dialog::pick_folder,
+            dialog::save_file,
+            dialog::save_text_file,
+            disk::get_disk_space,
+            logging::get_log_filter,
+            logging::set_log_filter,
+            logging::set_log_level,
+            log_viewer::list_log_files,
+            log_viewer::query_logs,
+            proxy::backend_request,
+            readiness::get_startup_state,
+            readiness::open_main_window,
+            readiness::open_log_folder,
+            reporting::get_crash_reporting,
+            reporting::set_crash_reporting,
+            reporting::get_crash_upload_consent,
+            reporting::set_crash_upload_consent,
+            recording::start_screen_recording,
+            recording::stop_screen_recording,
+            recording::get_screen_recording,
+            reminders::schedule_notification,
+            reminders::list_notifications,
+            reminders::cancel_notification,
+            report_pdf::render_report_pdf,
+            scanner::get_scanner_status,
+            scanner::set_scanner_hook,
+            scheduler::list_scheduled_tasks,
+            scheduler::run_scheduled_task,
+            scheduler::get_task_result,
+            scheduler::set_task_schedule,
+            screenshot::capture_screenshot,
+            shortcuts::list_shortcuts,
+            shortcuts::register_shortcut,
+            shortcuts::unregister_shortcut,
+            speech::speak,
+            speech::list_voices,
+            assist::start_support_session,
+            assist::stop_support_session,
+            assist::get_support_session,
+            events::get_recent_events,
+            events::record_event,
+            files::open_path,
+            frontend::log_frontend_error,
+            health::get_health_summary,
+            import::import_dropped_csv,
+            import_folder::get_import_folder_status,
+            import_folder::set_import_folder,
+            instance::get_instance_info,
+            integrations::list_integrations,
+            integrations::run_integration,
+            locale::get_locale_info,
+            locale::format_money,
+            locale::format_number,
+            locale::parse_amount,
+            location::detect_location,
+            pairing::create_pairing_code,
+            pairing::list_paired_devices,
+            pairing::revoke_paired_device,
+            support::create_support_bundle,
+            startup::get_startup_timelines,
+            storage::get_storage_health,
+            system_info::get_system_info,
+            system_proxy::get_proxy_info,
+            watchdog::get_backend_latency,
+            wol::send_wol,
+            wol::list_wol_devices,
+            wol::save_wol_device,
+            wol::remove_wol_device,
+            wol::wake_all_devices,
+            bluetooth::list_bluetooth_devices,
+            bluetooth::pair_bluetooth_device,
+            bluetooth::bluetooth_send,
+            bluetooth::bluetooth_read,
+            printer::list_printers,
+            printer::get_printer_settings,
+            printer::set_printer_settings,
+            printer::print_receipt,
+            printer::open_cash_drawer,
+            telemetry::get_telemetry_enabled,
+            telemetry::set_telemetry_enabled,
+            telemetry::preview_telemetry,
+            telemetry::track_feature,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while running tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::prepare(app_handle, "应用退出");
+            }
+        });
 }
 
@@ -0,0 +1,203 @@
+// 本机指标接口
+//
+// 可选开启一个仅监听 127.0.0.1 的 HTTP 接口，以 Prometheus 文本格式输出
+// 壳程序和 Backend 的运行指标，供已有门店监控体系的连锁客户抓取。
+// 开关和端口修改后需重启程序生效。
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::backend::BackendProcess;
+use crate::config::ConfigStore;
+use crate::crash;
+use crate::watchdog::LatencyTracker;
+
+/// Backend 重启次数
+pub const BACKEND_RESTARTS: &str = "smartmart_backend_restarts_total";
+/// 打印失败次数
+pub const PRINT_FAILURES: &str = "smartmart_print_failures_total";
+
+/// 计数器及说明（未发生过的计数器也输出 0，方便配置告警规则）
+const COUNTERS: &[(&str, &str)] = &[
+    (BACKEND_RESTARTS, "Backend 重启次数"),
+    (PRINT_FAILURES, "小票打印失败次数"),
+];
+
+static COUNTER_VALUES: Mutex<BTreeMap<&'static str, u64>> = Mutex::new(BTreeMap::new());
+
+/// 计数器加一
+pub fn increment(name: &'static str) {
+    if let Ok(mut counters) = COUNTER_VALUES.lock() {
+        *counters.entry(name).or_default() += 1;
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+    out.push_str(&format!(
+        "# HELP {} {}\n# TYPE {} {}\n",
+        name, help, name, kind
+    ));
+    for (labels, value) in samples {
+        out.push_str(&format!("{}{} {}\n", name, labels, value));
+    }
+}
+
+/// 以 Prometheus 文本格式输出全部指标
+pub fn render(app_handle: &tauri::AppHandle, started: Instant) -> String {
+    let mut out = String::new();
+
+    write_metric(
+        &mut out,
+        "smartmart_shell_uptime_seconds",
+        "gauge",
+        "壳程序运行时长",
+        &[(String::new(), started.elapsed().as_secs_f64())],
+    );
+
+    let uptime = {
+        let state = app_handle.state::<Mutex<BackendProcess>>();
+        let process = state.lock().unwrap();
+        process.uptime()
+    };
+    if let Some(uptime) = uptime {
+        write_metric(
+            &mut out,
+            "smartmart_backend_uptime_seconds",
+            "gauge",
+            "Backend 进程运行时长",
+            &[(String::new(), uptime.as_secs_f64())],
+        );
+    }
+
+    let threshold_ms = app_handle
+        .state::<ConfigStore>()
+        .get()
+        .monitoring
+        .slow_threshold_ms;
+    let (up, stats) = {
+        let state = app_handle.state::<Mutex<LatencyTracker>>();
+        let tracker = state.lock().unwrap();
+        (tracker.last_probe_ok(), tracker.stats(threshold_ms))
+    };
+    write_metric(
+        &mut out,
+        "smartmart_backend_up",
+        "gauge",
+        "最近一次 Backend 健康检查是否成功",
+        &[(String::new(), if up { 1.0 } else { 0.0 })],
+    );
+    let latency: Vec<(String, f64)> = [
+        ("0.5", stats.p50_ms),
+        ("0.95", stats.p95_ms),
+        ("0.99", stats.p99_ms),
+    ]
+    .into_iter()
+    .filter_map(|(q, v)| v.map(|v| (format!("{{quantile=\"{}\"}}", q), v as f64 / 1000.0)))
+    .collect();
+    write_metric(
+        &mut out,
+        "smartmart_backend_latency_seconds",
+        "summary",
+        "Backend 健康检查延迟",
+        &latency,
+    );
+    write_metric(
+        &mut out,
+        "smartmart_backend_probe_failures_total",
+        "counter",
+        "Backend 健康检查失败次数",
+        &[(String::new(), stats.failures as f64)],
+    );
+
+    let counters = COUNTER_VALUES.lock().map(|c| c.clone()).unwrap_or_default();
+    for (name, help) in COUNTERS {
+        let value = counters.get(name).copied().unwrap_or_default();
+        write_metric(
+            &mut out,
+            name,
+            "counter",
+            help,
+            &[(String::new(), value as f64)],
+        );
+    }
+
+    let queues = [("crash_reports", crash::pending_reports().len())];
+    let queues: Vec<(String, f64)> = queues
+        .into_iter()
+        .map(|(queue, depth)| (format!("{{queue=\"{}\"}}", queue), depth as f64))
+        .collect();
+    write_metric(
+        &mut out,
+        "smartmart_queue_depth",
+        "gauge",
+        "队列当前长度",
+        &queues,
+    );
+
+    out
+}
+
+fn handle(mut stream: TcpStream, app_handle: &tauri::AppHandle, started: Instant) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // 读完请求头
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+        line.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let (status, body) = if request_line.starts_with("GET ") && (path == "/metrics" || path == "/")
+    {
+        ("200 OK", render(app_handle, started))
+    } else {
+        ("404 Not Found", "not found\n".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// 按配置启动指标接口
+pub fn start(app_handle: tauri::AppHandle) {
+    let settings = app_handle.state::<ConfigStore>().get().metrics;
+    if !settings.enabled {
+        return;
+    }
+
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, settings.port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::warn!("启动指标接口失败, 端口: {}: {}", settings.port, e);
+            return;
+        }
+    };
+    tracing::info!("指标接口已启动: http://127.0.0.1:{}/metrics", settings.port);
+
+    let started = Instant::now();
+    let spawned = std::thread::Builder::new()
+        .name("metrics".into())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &app_handle, started);
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动指标线程失败: {}", e);
+    }
+}
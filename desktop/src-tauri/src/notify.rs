@@ -0,0 +1,13 @@
+// 系统通知
+
+/// 显示一条系统通知（失败时只写日志）
+pub fn show(app_handle: &tauri::AppHandle, title: &str, body: &str) {
+    let identifier = app_handle.config().tauri.bundle.identifier.clone();
+    let shown = tauri::api::notification::Notification::new(identifier)
+        .title(title)
+        .body(body)
+        .show();
+    if let Err(e) = shown {
+        tracing::warn!("显示通知失败: {}", e);
+    }
+}
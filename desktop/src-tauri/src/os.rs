@@ -0,0 +1,50 @@
+// 平台相关的辅助函数
+
+use std::path::Path;
+use std::ffi::OsStr;
+use std::process::Command;
+
+/// 创建子进程命令（Windows 下不弹出控制台窗口）
+pub fn hidden_command<S: AsRef<OsStr>>(program: S) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// 使用系统默认程序打开文件或文件夹
+pub fn open_path(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = Command::new("explorer");
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("打开 {:?} 失败: {}", path, e))
+}
+
+/// 在文件管理器中显示并选中文件
+pub fn reveal_path(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = Command::new("explorer").arg("/select,").arg(path).spawn();
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg("-R").arg(path).spawn();
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let result = Command::new("xdg-open")
+        .arg(path.parent().unwrap_or(path))
+        .spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("打开文件管理器失败: {}", e))
+}
@@ -0,0 +1,218 @@
+// 手机配套应用配对
+//
+// 设备配对页调用 create_pairing_code 生成一次性配对码，二维码内容为 Backend 的局域网地址和配对 Token，
+// 手机应用扫码后用配对 Token 换取设备 Token（Token 由 Backend 校验，只能使用一次且只接受局域网连接），
+// 之后的请求和 WebSocket 连接都出示设备 Token。已配对的设备可以列出和撤销，
+// 撤销后其设备 Token 立即失效，需要重新扫码。
+//
+// 二维码中的端口使用壳程序实际启动 Backend 的端口，而不是固定的 8000。
+// Backend 默认只监听本机，手机需要在开启局域网模式（backend.lan_mode）后才能连接。
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::backend::BackendProcess;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::http;
+
+/// 配对码默认有效期
+const DEFAULT_VALIDITY: Duration = Duration::from_secs(300);
+
+/// 配对码最长有效期
+const MAX_VALIDITY: Duration = Duration::from_secs(3600);
+
+/// 二维码内容的类型标识（手机应用据此识别）
+const QR_TYPE: &str = "smartmart_pairing";
+
+/// Backend 生成的配对信息
+#[derive(Debug, Deserialize)]
+struct BackendPairing {
+    token: String,
+    expires_in: u64,
+    local_ip: String,
+    #[serde(default)]
+    all_ips: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingCode {
+    pub http_url: String,
+    pub ws_url: String,
+    pub token: String,
+    /// 有效期（秒）
+    pub expires_in: u64,
+    pub expires_at: String,
+    /// 二维码中使用的地址
+    pub address: String,
+    /// 本机所有局域网地址（可切换二维码使用的地址）
+    pub addresses: Vec<String>,
+    /// 二维码内容（JSON）
+    pub qr_payload: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDevice {
+    pub device_id: String,
+    pub device_type: Option<String>,
+    pub device_name: Option<String>,
+    pub authenticated: bool,
+    pub last_seen: Option<String>,
+    pub created_at: Option<String>,
+}
+
+fn backend_port(app_handle: &tauri::AppHandle) -> u16 {
+    app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port()
+}
+
+fn backend_error(action: &str, error: ureq::Error) -> AppError {
+    match error {
+        ureq::Error::Status(404, _) => AppError::InvalidArgument("设备不存在".to_string()),
+        ureq::Error::Status(status, _) => {
+            AppError::BackendUnavailable(format!("{}失败: HTTP {}", action, status))
+        }
+        e => AppError::BackendUnavailable(format!("{}失败: {}", action, e)),
+    }
+}
+
+fn pairing_code(
+    pairing: BackendPairing,
+    address: Option<String>,
+    port: u16,
+) -> AppResult<PairingCode> {
+    let mut addresses = pairing.all_ips;
+    if !addresses.contains(&pairing.local_ip) {
+        addresses.insert(0, pairing.local_ip.clone());
+    }
+    let address = match address.filter(|a| !a.trim().is_empty()) {
+        Some(address) if addresses.contains(&address) => address,
+        Some(address) => {
+            return Err(AppError::InvalidArgument(format!(
+                "{} 不是本机的局域网地址",
+                address
+            )))
+        }
+        None => pairing.local_ip,
+    };
+
+    let http_url = format!("http://{}:{}", address, port);
+    let ws_url = format!("ws://{}:{}/ws", address, port);
+    let qr_payload = serde_json::json!({
+        "http_url": http_url,
+        "ws_url": ws_url,
+        "token": pairing.token,
+        "type": QR_TYPE,
+    })
+    .to_string();
+    let expires_at = chrono::Local::now() + chrono::Duration::seconds(pairing.expires_in as i64);
+    Ok(PairingCode {
+        http_url,
+        ws_url,
+        token: pairing.token,
+        expires_in: pairing.expires_in,
+        expires_at: expires_at.to_rfc3339(),
+        address,
+        addresses,
+        qr_payload,
+    })
+}
+
+// Tauri 命令
+
+/// 生成一次性配对码，address 为空时使用本机最可能的局域网地址
+#[tauri::command]
+pub async fn create_pairing_code(
+    address: Option<String>,
+    validity_secs: Option<u64>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<PairingCode> {
+    let validity = validity_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_VALIDITY)
+        .clamp(Duration::from_secs(30), MAX_VALIDITY);
+    let (port, lan_mode) = {
+        let process = app_handle.state::<Mutex<BackendProcess>>();
+        let process = process.lock().unwrap();
+        (process.port(), process.lan_mode())
+    };
+    if !lan_mode {
+        return Err(AppError::InvalidArgument(
+            "手机需要在局域网内连接 Backend，请先在设置中开启局域网模式".to_string(),
+        ));
+    }
+    let pairing: BackendPairing = http::local()
+        .post(&format!(
+            "http://127.0.0.1:{}/pairing/generate_pairing_code",
+            port
+        ))
+        .query("validity_seconds", &validity.as_secs().to_string())
+        .call()
+        .map_err(|e| backend_error("生成配对码", e))?
+        .into_json()
+        .map_err(|e| AppError::BackendUnavailable(format!("解析配对信息失败: {}", e)))?;
+    let code = pairing_code(pairing, address, port)?;
+    tracing::info!(
+        "已生成配对码: {}，有效期 {} 秒",
+        code.http_url,
+        code.expires_in
+    );
+    Ok(code)
+}
+
+/// 列出已配对的设备
+#[tauri::command]
+pub async fn list_paired_devices(app_handle: tauri::AppHandle) -> AppResult<Vec<PairedDevice>> {
+    http::local()
+        .get(&format!(
+            "http://127.0.0.1:{}/pairing/devices",
+            backend_port(&app_handle)
+        ))
+        .call()
+        .map_err(|e| backend_error("读取已配对设备", e))?
+        .into_json()
+        .map_err(|e| AppError::BackendUnavailable(format!("解析设备列表失败: {}", e)))
+}
+
+/// 撤销设备的配对，设备需要重新扫码才能连接
+#[tauri::command]
+pub async fn revoke_paired_device(
+    device_id: String,
+    app_handle: tauri::AppHandle,
+) -> AppResult<()> {
+    if device_id.trim().is_empty() {
+        return Err(AppError::InvalidArgument("设备 ID 不能为空".to_string()));
+    }
+    // 设备 ID 作为路径的一部分
+    let encoded: String = device_id
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    http::local()
+        .delete(&format!(
+            "http://127.0.0.1:{}/pairing/devices/{}",
+            backend_port(&app_handle),
+            encoded
+        ))
+        .call()
+        .map_err(|e| backend_error("撤销配对", e))?;
+
+    tracing::info!("已撤销设备配对: {}", device_id);
+    events::record(
+        EventKind::State,
+        "撤销设备配对",
+        serde_json::json!({ "device_id": device_id }),
+    );
+    let _ = app_handle.emit_all("pairing://revoked", &device_id);
+    Ok(())
+}
@@ -0,0 +1,204 @@
+// 性能模式
+//
+// 门店常用的低价赛扬收银机同时运行 Backend、WebView 和后台检查时容易卡顿。
+// 低配模式（Low）下：
+// - 看门狗、电池、磁盘和存储检查的间隔放大 LOW_INTERVAL_FACTOR 倍
+// - Backend 进程提高到“高于正常”优先级，收银请求不被其他程序抢占
+// - WebView 关闭平滑滚动等效果，并通过 performance://mode 事件提示前端减少动画
+//
+// 自动模式（Auto）在 CPU 核数或内存较少时使用低配模式。
+// 硬件加速开关通过 WebView2 的启动参数生效，修改后需要重启。
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::backend::BackendProcess;
+use crate::config::{ConfigStore, PerformanceConfig, PerformanceLevel};
+use crate::error::AppResult;
+use crate::events::{self, EventKind};
+
+/// 低配模式下后台检查间隔的倍数
+const LOW_INTERVAL_FACTOR: u32 = 3;
+
+/// CPU 核数不超过该值时自动使用低配模式
+const LOW_CPU_CORES: usize = 2;
+
+/// 内存少于该值时自动使用低配模式
+const LOW_MEMORY_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// 当前是否为低配模式
+static LOW: AtomicBool = AtomicBool::new(false);
+
+/// 启动 WebView 时的（低配模式，硬件加速）设置
+static STARTED_WITH: Mutex<Option<(bool, bool)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceMode {
+    /// 设置的模式
+    pub level: PerformanceLevel,
+    /// 实际使用的模式（自动模式下为检测结果）
+    pub effective: PerformanceLevel,
+    pub hardware_acceleration: bool,
+    /// 前端应减少动画和过渡效果
+    pub reduce_animations: bool,
+    /// 修改的设置需要重启才能生效
+    pub restart_required: bool,
+}
+
+/// 是否为低配电脑
+fn is_low_end() -> bool {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let memory = system.total_memory();
+    cores <= LOW_CPU_CORES || (memory > 0 && memory < LOW_MEMORY_BYTES)
+}
+
+fn resolve(level: PerformanceLevel) -> PerformanceLevel {
+    match level {
+        PerformanceLevel::Auto if is_low_end() => PerformanceLevel::Low,
+        PerformanceLevel::Auto => PerformanceLevel::Standard,
+        level => level,
+    }
+}
+
+fn mode(settings: &PerformanceConfig) -> PerformanceMode {
+    let low = LOW.load(Ordering::Relaxed);
+    let started_with = *STARTED_WITH.lock().unwrap();
+    PerformanceMode {
+        level: settings.level,
+        effective: if low {
+            PerformanceLevel::Low
+        } else {
+            PerformanceLevel::Standard
+        },
+        hardware_acceleration: settings.hardware_acceleration,
+        reduce_animations: low,
+        // WebView 的启动参数只在启动时生效
+        restart_required: started_with != Some((low, settings.hardware_acceleration)),
+    }
+}
+
+/// 启动时应用性能设置（需在创建窗口和启动 Backend 之前调用）
+pub fn init(settings: &PerformanceConfig) {
+    let effective = resolve(settings.level);
+    LOW.store(effective == PerformanceLevel::Low, Ordering::Relaxed);
+    *STARTED_WITH.lock().unwrap() = Some((
+        effective == PerformanceLevel::Low,
+        settings.hardware_acceleration,
+    ));
+    tracing::info!(
+        "性能模式: {:?}（设置: {:?}），硬件加速: {}",
+        effective,
+        settings.level,
+        settings.hardware_acceleration
+    );
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut args: Vec<&str> = Vec::new();
+        if effective == PerformanceLevel::Low {
+            args.push("--disable-smooth-scrolling");
+        }
+        if !settings.hardware_acceleration {
+            args.push("--disable-gpu");
+        }
+        if !args.is_empty() {
+            // 保留用户自行设置的参数
+            let existing =
+                std::env::var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS").unwrap_or_default();
+            let value = existing
+                .split_whitespace()
+                .chain(args)
+                .collect::<Vec<_>>()
+                .join(" ");
+            std::env::set_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS", value);
+        }
+    }
+}
+
+/// 按性能模式调整后台检查的间隔
+pub fn interval(base: Duration) -> Duration {
+    if LOW.load(Ordering::Relaxed) {
+        base * LOW_INTERVAL_FACTOR
+    } else {
+        base
+    }
+}
+
+/// 按性能模式设置 Backend 进程的优先级
+pub fn apply_backend_priority(pid: u32) {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::Threading::{
+            OpenProcess, SetPriorityClass, ABOVE_NORMAL_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+            PROCESS_SET_INFORMATION,
+        };
+
+        let class = if LOW.load(Ordering::Relaxed) {
+            ABOVE_NORMAL_PRIORITY_CLASS
+        } else {
+            NORMAL_PRIORITY_CLASS
+        };
+        let process = unsafe { OpenProcess(PROCESS_SET_INFORMATION, 0, pid) };
+        if process == 0 {
+            tracing::warn!("打开 Backend 进程失败，无法设置优先级");
+            return;
+        }
+        if unsafe { SetPriorityClass(process, class) } == 0 {
+            tracing::warn!(
+                "设置 Backend 进程优先级失败: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        unsafe { CloseHandle(process) };
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = pid;
+}
+
+// Tauri 命令
+
+/// 读取性能模式
+#[tauri::command]
+pub fn get_performance_mode(config: tauri::State<'_, ConfigStore>) -> PerformanceMode {
+    mode(&config.get().performance)
+}
+
+/// 设置性能模式，hardware_acceleration 为空时保持不变
+#[tauri::command]
+pub fn set_performance_mode(
+    level: PerformanceLevel,
+    hardware_acceleration: Option<bool>,
+    app_handle: tauri::AppHandle,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<PerformanceMode> {
+    let updated = config.update(|c| {
+        c.performance.level = level;
+        if let Some(enabled) = hardware_acceleration {
+            c.performance.hardware_acceleration = enabled;
+        }
+    })?;
+    let settings = updated.performance;
+    let effective = resolve(settings.level);
+    LOW.store(effective == PerformanceLevel::Low, Ordering::Relaxed);
+
+    if let Some(pid) = app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .pid()
+    {
+        apply_backend_priority(pid);
+    }
+
+    let mode = mode(&settings);
+    tracing::info!("性能模式已修改: {:?}", mode);
+    events::record(EventKind::State, "修改性能模式", serde_json::json!(mode));
+    let _ = app_handle.emit_all("performance://mode", &mode);
+    Ok(mode)
+}
@@ -0,0 +1,241 @@
+// 系统休眠 / 唤醒处理
+//
+// 笔记本作为移动收银使用时，唤醒后 Backend 连接、WebSocket 和系统时间都可能失效。
+// 检测到唤醒后：重新检查 Backend（进程已退出则重新启动）、尝试同步系统时间，
+// 并发出 power://resume 事件，前端据此重连 WebSocket。
+//
+// Windows 下通过主窗口的 WM_POWERBROADCAST 消息得到休眠和唤醒通知（电源状态变化转给 battery 模块）；
+// 其他平台通过检测系统时间跳变推断唤醒。
+
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+
+use crate::backend::{self, BackendProcess};
+use crate::events::{self, EventKind};
+#[cfg(target_os = "windows")]
+use crate::os;
+
+/// 上次休眠的时间
+static SUSPENDED_AT: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+/// 上次处理唤醒的时间（同一次唤醒可能收到多条通知）
+static LAST_RESUME: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumeInfo {
+    /// 休眠时长（秒），无法确定时为空
+    pub slept_secs: Option<u64>,
+    pub backend_ok: bool,
+    pub backend_restarted: bool,
+    pub clock_synced: bool,
+}
+
+fn on_suspend(app_handle: &tauri::AppHandle) {
+    *SUSPENDED_AT.lock().unwrap() = Some(SystemTime::now());
+    tracing::info!("系统即将休眠");
+    events::record(EventKind::State, "系统休眠", serde_json::Value::Null);
+    let _ = app_handle.emit_all("power://suspend", ());
+}
+
+/// 尝试立即同步系统时间（需要时间服务可用，失败不影响其他处理）
+fn sync_clock() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        match os::hidden_command("w32tm").args(["/resync", "/nowait"]).output() {
+            Ok(output) if output.status.success() => true,
+            Ok(output) => {
+                tracing::debug!(
+                    "同步系统时间失败: {}",
+                    String::from_utf8_lossy(&output.stdout).trim()
+                );
+                false
+            }
+            Err(e) => {
+                tracing::debug!("同步系统时间失败: {}", e);
+                false
+            }
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // 其他平台由系统的 NTP 服务在唤醒后自动校时
+        false
+    }
+}
+
+fn on_resume(app_handle: &tauri::AppHandle, slept: Option<Duration>) {
+    {
+        let mut last = LAST_RESUME.lock().unwrap();
+        let now = SystemTime::now();
+        let recent = last
+            .and_then(|t| now.duration_since(t).ok())
+            .is_some_and(|d| d < Duration::from_secs(10));
+        if recent {
+            return;
+        }
+        *last = Some(now);
+    }
+
+    let slept = slept.or_else(|| {
+        SUSPENDED_AT
+            .lock()
+            .unwrap()
+            .take()
+            .and_then(|t| t.elapsed().ok())
+    });
+    tracing::info!("系统已唤醒, 休眠时长: {:?}", slept);
+
+    let app_handle = app_handle.clone();
+    let spawned = std::thread::Builder::new()
+        .name("power-resume".into())
+        .spawn(move || {
+            let clock_synced = sync_clock();
+
+            // 网络和 Backend 可能需要几秒钟恢复
+            let state = app_handle.state::<Mutex<BackendProcess>>();
+            let port = state.lock().unwrap().port();
+            let mut backend_ok = false;
+            for _ in 0..5 {
+                if backend::check_health(port).is_ok() {
+                    backend_ok = true;
+                    break;
+                }
+                std::thread::sleep(Duration::from_secs(2));
+            }
+
+            let mut backend_restarted = false;
+            if !backend_ok {
+                let pending = {
+                    let mut process = state.lock().unwrap();
+                    if process.pid().is_some() && !process.is_running() {
+                        tracing::warn!("唤醒后 Backend 已退出，重新启动");
+                        process.begin_restart(port, None)
+                    } else {
+                        tracing::warn!("唤醒后 Backend 无响应");
+                        None
+                    }
+                };
+                if let Some(pending) = pending {
+                    match pending.run(&state) {
+                        Ok(()) => backend_restarted = true,
+                        Err(e) => tracing::error!("重新启动 Backend 失败: {}", e),
+                    }
+                }
+            }
+
+            let info = ResumeInfo {
+                slept_secs: slept.map(|d| d.as_secs()),
+                backend_ok,
+                backend_restarted,
+                clock_synced,
+            };
+            events::record(EventKind::State, "系统唤醒", json!(info));
+            let _ = app_handle.emit_all("power://resume", info);
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动唤醒处理线程失败: {}", e);
+    }
+}
+
+/// 开始监听休眠 / 唤醒
+pub fn start(app_handle: &tauri::AppHandle) {
+    #[cfg(target_os = "windows")]
+    windows::install(app_handle);
+
+    #[cfg(not(target_os = "windows"))]
+    watch_clock(app_handle.clone());
+}
+
+/// 定期对比系统时间，间隔远大于休眠时间即认为刚刚被唤醒
+#[cfg(not(target_os = "windows"))]
+fn watch_clock(app_handle: tauri::AppHandle) {
+    const TICK: Duration = Duration::from_secs(5);
+    const GAP: Duration = Duration::from_secs(30);
+
+    let spawned = std::thread::Builder::new()
+        .name("power-watch".into())
+        .spawn(move || {
+            let mut last = SystemTime::now();
+            loop {
+                std::thread::sleep(TICK);
+                let now = SystemTime::now();
+                if let Ok(elapsed) = now.duration_since(last) {
+                    if elapsed > GAP {
+                        on_resume(&app_handle, Some(elapsed - TICK));
+                    }
+                }
+                last = now;
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动休眠检测线程失败: {}", e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        PBT_APMPOWERSTATUSCHANGE, PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND,
+        WM_POWERBROADCAST,
+    };
+
+    use tauri::Manager;
+
+    const SUBCLASS_ID: usize = 0x534d_5057; // "SMPW"
+
+    static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        _data: usize,
+    ) -> LRESULT {
+        if msg == WM_POWERBROADCAST {
+            if let Some(app_handle) = APP_HANDLE.get() {
+                match wparam as u32 {
+                    PBT_APMSUSPEND => super::on_suspend(app_handle),
+                    PBT_APMRESUMEAUTOMATIC | PBT_APMRESUMESUSPEND => {
+                        super::on_resume(app_handle, None)
+                    }
+                    PBT_APMPOWERSTATUSCHANGE => {
+                        crate::battery::refresh(app_handle);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+
+    /// 为主窗口安装消息钩子
+    pub fn install(app_handle: &tauri::AppHandle) {
+        let Some(window) = app_handle.get_window("main") else {
+            tracing::warn!("未找到主窗口，无法监听休眠事件");
+            return;
+        };
+        let hwnd = match window.hwnd() {
+            Ok(hwnd) => hwnd.0,
+            Err(e) => {
+                tracing::warn!("获取窗口句柄失败: {}", e);
+                return;
+            }
+        };
+        let _ = APP_HANDLE.set(app_handle.clone());
+
+        let installed = unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0) };
+        if installed == 0 {
+            tracing::warn!("安装窗口消息钩子失败，无法监听休眠事件");
+        }
+    }
+}
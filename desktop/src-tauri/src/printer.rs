@@ -0,0 +1,758 @@
+// 小票打印机（ESC/POS）
+//
+// 收银台通过浏览器打印小票需要弹出系统打印对话框，无法在结账时使用。
+// 壳程序直接向热敏打印机发送 ESC/POS 指令，支持：
+// - 网口打印机（RAW 9100 端口）
+// - 串口打印机
+// - USB 打印机：Windows 下通过已安装的驱动以 RAW 方式发送，其他系统写入设备文件
+// - 蓝牙打印机（见 bluetooth.rs）
+//
+// 前端传入结构化的小票内容（文字、左右两栏、分隔线、条码、二维码），由这里排版并按 GBK 编码，
+// 不需要关心打印机纸宽和指令。钱箱接在打印机上，通过打印机发送开钱箱的脉冲。
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::{ConfigStore, PrinterConfig, PrinterConnection};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::{bluetooth, metrics};
+
+/// 连接和发送的超时时间
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 单张小票最多的行数
+const MAX_LINES: usize = 500;
+
+/// 最多打印的份数
+const MAX_COPIES: u32 = 5;
+
+/// 同一时间只发送一张小票，避免多张小票的内容交错
+static PRINTING: Mutex<()> = Mutex::new(());
+
+/// 上次打印的结果（健康状态使用）
+static LAST: Mutex<Option<PrintResult>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintResult {
+    pub ok: bool,
+    pub error: Option<String>,
+    pub at: String,
+}
+
+/// 可选择的打印机
+#[derive(Debug, Clone, Serialize)]
+pub struct PrinterInfo {
+    pub name: String,
+    pub connection: PrinterConnection,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// 小票的一行
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReceiptLine {
+    Text {
+        text: String,
+        #[serde(default)]
+        align: Align,
+        #[serde(default)]
+        bold: bool,
+        /// 倍宽倍高（店名、合计金额）
+        #[serde(default)]
+        large: bool,
+    },
+    /// 左右两栏，例如商品名称和金额
+    Row {
+        left: String,
+        right: String,
+        #[serde(default)]
+        bold: bool,
+    },
+    /// 整行的分隔线
+    Divider,
+    /// 空行
+    Feed {
+        #[serde(default = "one")]
+        lines: u8,
+    },
+    /// CODE128 条码（例如订单号）
+    Barcode { data: String },
+    /// 二维码（例如电子发票链接）
+    Qr { data: String },
+}
+
+fn one() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Receipt {
+    pub lines: Vec<ReceiptLine>,
+    /// 打印份数，默认 1
+    #[serde(default)]
+    pub copies: Option<u32>,
+    /// 打印后打开钱箱（现金结账）
+    #[serde(default)]
+    pub open_drawer: bool,
+}
+
+/// 按 GBK 编码（国内热敏打印机的默认字符集），无法编码的字符输出为 ?。
+/// GBK 没有半角的 ¥（U+00A5），改用全角的 ￥
+fn encode(text: &str) -> Vec<u8> {
+    let text = text.replace('\u{A5}', "\u{FFE5}");
+    let (bytes, _, unmappable) = encoding_rs::GBK.encode(&text);
+    if !unmappable {
+        return bytes.into_owned();
+    }
+    let text: String = text
+        .chars()
+        .map(|c| {
+            if encoding_rs::GBK.encode(c.encode_utf8(&mut [0; 4])).2 {
+                '?'
+            } else {
+                c
+            }
+        })
+        .collect();
+    encoding_rs::GBK.encode(&text).0.into_owned()
+}
+
+/// 打印宽度（半角字符数），按实际发送的 GBK 字节计算：单字节字符占一个，双字节字符占两个
+fn display_width(text: &str) -> usize {
+    encode(text).len()
+}
+
+/// ESC/POS 指令
+struct EscPos {
+    data: Vec<u8>,
+    /// 每行的半角字符数
+    columns: usize,
+}
+
+impl EscPos {
+    fn new(settings: &PrinterConfig) -> Self {
+        let mut printer = Self {
+            data: Vec::new(),
+            columns: if settings.paper_width_mm >= 76 {
+                48
+            } else {
+                32
+            },
+        };
+        // 初始化，进入汉字模式
+        printer.data.extend_from_slice(&[0x1B, 0x40, 0x1C, 0x26]);
+        printer
+    }
+
+    fn align(&mut self, align: Align) {
+        let n = match align {
+            Align::Left => 0,
+            Align::Center => 1,
+            Align::Right => 2,
+        };
+        self.data.extend_from_slice(&[0x1B, 0x61, n]);
+    }
+
+    fn bold(&mut self, on: bool) {
+        self.data.extend_from_slice(&[0x1B, 0x45, on as u8]);
+    }
+
+    fn large(&mut self, on: bool) {
+        self.data
+            .extend_from_slice(&[0x1D, 0x21, if on { 0x11 } else { 0x00 }]);
+    }
+
+    fn line(&mut self, text: &str) {
+        self.data.extend(encode(text));
+        self.data.push(b'\n');
+    }
+
+    fn row(&mut self, left: &str, right: &str) {
+        let used = display_width(left) + display_width(right);
+        if used < self.columns {
+            let padding = " ".repeat(self.columns - used);
+            self.line(&format!("{}{}{}", left, padding, right));
+        } else {
+            // 放不下时金额另起一行靠右
+            self.line(left);
+            let padding = " ".repeat(self.columns.saturating_sub(display_width(right)));
+            self.line(&format!("{}{}", padding, right));
+        }
+    }
+
+    fn feed(&mut self, lines: u8) {
+        self.data.extend_from_slice(&[0x1B, 0x64, lines]);
+    }
+
+    fn barcode(&mut self, data: &str) -> Result<(), String> {
+        if data.is_empty() || data.len() > 64 || !data.bytes().all(|b| (0x20..0x7F).contains(&b)) {
+            return Err(format!("条码内容只能是 64 个以内的 ASCII 字符: {}", data));
+        }
+        // 高度 80 点，模块宽度 2，条码下方显示内容
+        self.data
+            .extend_from_slice(&[0x1D, 0x68, 80, 0x1D, 0x77, 2, 0x1D, 0x48, 2]);
+        // CODE128，使用 B 字符集
+        self.data
+            .extend_from_slice(&[0x1D, 0x6B, 73, (data.len() + 2) as u8, b'{', b'B']);
+        self.data.extend_from_slice(data.as_bytes());
+        self.data.push(b'\n');
+        Ok(())
+    }
+
+    fn qr(&mut self, data: &str) -> Result<(), String> {
+        let bytes = data.as_bytes();
+        if bytes.is_empty() || bytes.len() > 700 {
+            return Err("二维码内容不能为空且不能超过 700 字节".to_string());
+        }
+        let [low, high] = ((bytes.len() + 3) as u16).to_le_bytes();
+        // 模型 2，模块大小 6，纠错等级 M
+        self.data.extend_from_slice(&[
+            0x1D, 0x28, 0x6B, 0x04, 0x00, 0x31, 0x41, 0x32, 0x00, //
+            0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x43, 0x06, //
+            0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x45, 0x31,
+        ]);
+        self.data
+            .extend_from_slice(&[0x1D, 0x28, 0x6B, low, high, 0x31, 0x50, 0x30]);
+        self.data.extend_from_slice(bytes);
+        self.data
+            .extend_from_slice(&[0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x51, 0x30, b'\n']);
+        Ok(())
+    }
+
+    /// 走纸后半切
+    fn cut(&mut self) {
+        self.data.extend_from_slice(&[0x1D, 0x56, 0x42, 0x00]);
+    }
+
+    fn open_drawer(&mut self, pin: u8) {
+        // 脉冲 50ms 开，500ms 关
+        self.data
+            .extend_from_slice(&[0x1B, 0x70, pin.min(1), 0x19, 0xFA]);
+    }
+}
+
+/// 排版小票，返回要发送的指令
+fn render(receipt: &Receipt, settings: &PrinterConfig) -> Result<Vec<u8>, String> {
+    let mut printer = EscPos::new(settings);
+    for line in &receipt.lines {
+        match line {
+            ReceiptLine::Text {
+                text,
+                align,
+                bold,
+                large,
+            } => {
+                printer.align(*align);
+                printer.bold(*bold);
+                printer.large(*large);
+                for text in text.lines() {
+                    printer.line(text);
+                }
+                printer.large(false);
+                printer.bold(false);
+                printer.align(Align::Left);
+            }
+            ReceiptLine::Row { left, right, bold } => {
+                printer.bold(*bold);
+                printer.row(left, right);
+                printer.bold(false);
+            }
+            ReceiptLine::Divider => printer.line(&"-".repeat(printer.columns)),
+            ReceiptLine::Feed { lines } => printer.feed(*lines),
+            ReceiptLine::Barcode { data } => {
+                printer.align(Align::Center);
+                printer.barcode(data)?;
+                printer.align(Align::Left);
+            }
+            ReceiptLine::Qr { data } => {
+                printer.align(Align::Center);
+                printer.qr(data)?;
+                printer.align(Align::Left);
+            }
+        }
+    }
+    if settings.cut {
+        printer.feed(4);
+        printer.cut();
+    } else {
+        printer.feed(6);
+    }
+    Ok(printer.data)
+}
+
+/// 通过网口发送
+fn send_network(host: &str, port: u16, data: &[u8]) -> Result<(), String> {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let address = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("解析打印机地址 {} 失败: {}", host, e))?
+        .next()
+        .ok_or_else(|| format!("解析打印机地址 {} 失败", host))?;
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)
+        .map_err(|e| format!("连接打印机 {} 失败: {}", address, e))?;
+    let _ = stream.set_write_timeout(Some(TIMEOUT));
+    stream
+        .write_all(data)
+        .and_then(|_| stream.flush())
+        .map_err(|e| format!("发送到打印机失败: {}", e))
+}
+
+/// 写入设备文件（串口或 USB 打印机）
+#[cfg(not(target_os = "windows"))]
+fn send_device(path: &str, data: &[u8]) -> Result<(), String> {
+    let mut device = std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("打开打印机 {} 失败: {}", path, e))?;
+    device
+        .write_all(data)
+        .and_then(|_| device.flush())
+        .map_err(|e| format!("发送到打印机失败: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn send_serial(port: &str, baud_rate: u32, data: &[u8]) -> Result<(), String> {
+    // 通过 stty 设置波特率（8 位数据、无校验、1 位停止位）
+    #[cfg(target_os = "macos")]
+    let flag = "-f";
+    #[cfg(not(target_os = "macos"))]
+    let flag = "-F";
+    let configured = crate::os::hidden_command("stty")
+        .args([
+            flag,
+            port,
+            &baud_rate.to_string(),
+            "raw",
+            "cs8",
+            "-cstopb",
+            "-parenb",
+        ])
+        .status();
+    if !configured.is_ok_and(|status| status.success()) {
+        tracing::warn!("设置串口 {} 的波特率失败，使用当前设置", port);
+    }
+    send_device(port, data)
+}
+
+fn send(connection: &PrinterConnection, data: &[u8]) -> Result<(), String> {
+    match connection {
+        PrinterConnection::Network { host, port } => send_network(host, *port, data),
+        #[cfg(target_os = "windows")]
+        PrinterConnection::Serial { port, baud_rate } => {
+            windows::send_serial(port, *baud_rate, data)
+        }
+        #[cfg(not(target_os = "windows"))]
+        PrinterConnection::Serial { port, baud_rate } => send_serial(port, *baud_rate, data),
+        #[cfg(target_os = "windows")]
+        PrinterConnection::Usb { name } => windows::send_raw(name, data),
+        #[cfg(not(target_os = "windows"))]
+        PrinterConnection::Usb { name } => send_device(name, data),
+        PrinterConnection::Bluetooth { address } => bluetooth::send(address, data, TIMEOUT),
+    }
+}
+
+/// 发送到已配置的打印机，记录结果
+fn print(settings: &PrinterConfig, data: &[u8], action: &str) -> AppResult<()> {
+    let connection = settings
+        .connection
+        .as_ref()
+        .ok_or_else(|| AppError::Config("尚未设置小票打印机".to_string()))?;
+
+    let result = {
+        let _printing = PRINTING.lock().unwrap();
+        send(connection, data)
+    };
+
+    *LAST.lock().unwrap() = Some(PrintResult {
+        ok: result.is_ok(),
+        error: result.as_ref().err().cloned(),
+        at: chrono::Local::now().to_rfc3339(),
+    });
+    match &result {
+        Ok(()) => events::record(
+            EventKind::Print,
+            action,
+            serde_json::json!({ "connection": connection, "bytes": data.len() }),
+        ),
+        Err(e) => {
+            tracing::warn!("{}失败: {}", action, e);
+            metrics::increment(metrics::PRINT_FAILURES);
+            events::record(
+                EventKind::Error,
+                format!("{}失败", action),
+                serde_json::json!({ "connection": connection, "error": e }),
+            );
+        }
+    }
+    result.map_err(AppError::Network)
+}
+
+/// 上次打印的结果
+pub fn last_result() -> Option<PrintResult> {
+    LAST.lock().unwrap().clone()
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::io::Write;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Devices::Communication::{
+        GetCommState, SetCommState, SetCommTimeouts, COMMTIMEOUTS, DCB, NOPARITY, ONESTOPBIT,
+    };
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::Graphics::Printing::{
+        ClosePrinter, EndDocPrinter, EndPagePrinter, EnumPrintersW, OpenPrinterW, StartDocPrinterW,
+        StartPagePrinter, WritePrinter, DOC_INFO_1W, PRINTER_ENUM_CONNECTIONS, PRINTER_ENUM_LOCAL,
+        PRINTER_INFO_4W,
+    };
+    use windows_sys::Win32::System::Registry::{
+        RegCloseKey, RegEnumValueW, RegOpenKeyExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ,
+    };
+
+    use super::TIMEOUT;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn from_wide(buffer: &[u16]) -> String {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    }
+
+    unsafe fn from_wide_ptr(ptr: *const u16) -> String {
+        if ptr.is_null() {
+            return String::new();
+        }
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+    }
+
+    fn last_error() -> std::io::Error {
+        std::io::Error::last_os_error()
+    }
+
+    /// 已安装的打印机名称
+    pub fn installed_printers() -> Vec<String> {
+        let flags = PRINTER_ENUM_LOCAL | PRINTER_ENUM_CONNECTIONS;
+        let mut needed = 0u32;
+        let mut count = 0u32;
+        unsafe {
+            EnumPrintersW(
+                flags,
+                std::ptr::null(),
+                4,
+                std::ptr::null_mut(),
+                0,
+                &mut needed,
+                &mut count,
+            )
+        };
+        if needed == 0 {
+            return Vec::new();
+        }
+        // 按指针对齐分配
+        let mut buffer = vec![0u64; (needed as usize + 7) / 8];
+        let ok = unsafe {
+            EnumPrintersW(
+                flags,
+                std::ptr::null(),
+                4,
+                buffer.as_mut_ptr() as *mut u8,
+                needed,
+                &mut needed,
+                &mut count,
+            )
+        };
+        if ok == 0 {
+            tracing::warn!("枚举打印机失败: {}", last_error());
+            return Vec::new();
+        }
+        let infos = unsafe {
+            std::slice::from_raw_parts(buffer.as_ptr() as *const PRINTER_INFO_4W, count as usize)
+        };
+        infos
+            .iter()
+            .map(|info| unsafe { from_wide_ptr(info.pPrinterName) })
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    /// 系统中的串口（HKLM\HARDWARE\DEVICEMAP\SERIALCOMM）
+    pub fn serial_ports() -> Vec<String> {
+        let path = wide("HARDWARE\\DEVICEMAP\\SERIALCOMM");
+        let mut key: HKEY = 0;
+        if unsafe { RegOpenKeyExW(HKEY_LOCAL_MACHINE, path.as_ptr(), 0, KEY_READ, &mut key) } != 0 {
+            return Vec::new();
+        }
+        let mut ports = Vec::new();
+        for index in 0.. {
+            let mut name = [0u16; 256];
+            let mut name_len = name.len() as u32;
+            let mut data = [0u16; 64];
+            let mut data_len = std::mem::size_of_val(&data) as u32;
+            let status = unsafe {
+                RegEnumValueW(
+                    key,
+                    index,
+                    name.as_mut_ptr(),
+                    &mut name_len,
+                    std::ptr::null(),
+                    std::ptr::null_mut(),
+                    data.as_mut_ptr() as *mut u8,
+                    &mut data_len,
+                )
+            };
+            if status != 0 {
+                break;
+            }
+            let port = from_wide(&data);
+            if !port.is_empty() {
+                ports.push(port);
+            }
+        }
+        unsafe { RegCloseKey(key) };
+        ports.sort();
+        ports
+    }
+
+    /// 以 RAW 方式发送到已安装的打印机（不经过驱动排版）
+    pub fn send_raw(name: &str, data: &[u8]) -> Result<(), String> {
+        let printer_name = wide(name);
+        let mut handle: HANDLE = 0;
+        if unsafe { OpenPrinterW(printer_name.as_ptr(), &mut handle, std::ptr::null()) } == 0 {
+            return Err(format!("打开打印机「{}」失败: {}", name, last_error()));
+        }
+
+        let mut doc_name = wide("SmartMart 小票");
+        let mut datatype = wide("RAW");
+        let info = DOC_INFO_1W {
+            pDocName: doc_name.as_mut_ptr(),
+            pOutputFile: std::ptr::null_mut(),
+            pDatatype: datatype.as_mut_ptr(),
+        };
+        let result = unsafe {
+            if StartDocPrinterW(handle, 1, &info) == 0 {
+                Err(format!("打印机「{}」拒绝打印任务: {}", name, last_error()))
+            } else {
+                let result = if StartPagePrinter(handle) == 0 {
+                    Err(format!("打印机「{}」开始打印失败: {}", name, last_error()))
+                } else {
+                    let mut written = 0u32;
+                    let ok = WritePrinter(
+                        handle,
+                        data.as_ptr() as *const _,
+                        data.len() as u32,
+                        &mut written,
+                    );
+                    EndPagePrinter(handle);
+                    if ok == 0 || written as usize != data.len() {
+                        Err(format!("发送到打印机「{}」失败: {}", name, last_error()))
+                    } else {
+                        Ok(())
+                    }
+                };
+                EndDocPrinter(handle);
+                result
+            }
+        };
+        unsafe { ClosePrinter(handle) };
+        result
+    }
+
+    /// 通过串口发送（8 位数据、无校验、1 位停止位）
+    pub fn send_serial(port: &str, baud_rate: u32, data: &[u8]) -> Result<(), String> {
+        // COM10 及以上必须使用 \\.\ 前缀
+        let path = format!("\\\\.\\{}", port.trim_start_matches("\\\\.\\"));
+        let mut device = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .map_err(|e| format!("打开串口 {} 失败: {}", port, e))?;
+
+        let handle = device.as_raw_handle() as HANDLE;
+        let mut dcb: DCB = unsafe { std::mem::zeroed() };
+        dcb.DCBlength = std::mem::size_of::<DCB>() as u32;
+        if unsafe { GetCommState(handle, &mut dcb) } == 0 {
+            return Err(format!("读取串口 {} 设置失败: {}", port, last_error()));
+        }
+        dcb.BaudRate = baud_rate;
+        dcb.ByteSize = 8;
+        dcb.Parity = NOPARITY;
+        dcb.StopBits = ONESTOPBIT;
+        // fBinary
+        dcb._bitfield |= 1;
+        if unsafe { SetCommState(handle, &dcb) } == 0 {
+            return Err(format!("设置串口 {} 失败: {}", port, last_error()));
+        }
+        let timeouts = COMMTIMEOUTS {
+            ReadIntervalTimeout: 0,
+            ReadTotalTimeoutMultiplier: 0,
+            ReadTotalTimeoutConstant: 0,
+            WriteTotalTimeoutMultiplier: 10,
+            WriteTotalTimeoutConstant: TIMEOUT.as_millis() as u32,
+        };
+        unsafe { SetCommTimeouts(handle, &timeouts) };
+
+        device
+            .write_all(data)
+            .and_then(|_| device.flush())
+            .map_err(|e| format!("发送到串口 {} 失败: {}", port, e))
+    }
+}
+
+/// 本机可用的打印机（网口和蓝牙打印机需要手动填写或通过蓝牙搜索）
+fn local_printers() -> Vec<PrinterInfo> {
+    #[cfg(target_os = "windows")]
+    {
+        let printers = windows::installed_printers()
+            .into_iter()
+            .map(|name| PrinterInfo {
+                connection: PrinterConnection::Usb { name: name.clone() },
+                name,
+            });
+        let ports = windows::serial_ports().into_iter().map(|port| PrinterInfo {
+            name: format!("串口 {}", port),
+            connection: PrinterConnection::Serial {
+                port,
+                baud_rate: 9600,
+            },
+        });
+        printers.chain(ports).collect()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        const PREFIXES: [&str; 4] = ["usb/lp", "ttyUSB", "ttyACM", "cu.usbserial"];
+        let dev = std::path::Path::new("/dev");
+        let mut devices: Vec<String> = std::fs::read_dir(dev)
+            .into_iter()
+            .flatten()
+            .chain(std::fs::read_dir(dev.join("usb")).into_iter().flatten())
+            .flatten()
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .filter(|path| {
+                let relative = path.trim_start_matches("/dev/");
+                PREFIXES.iter().any(|prefix| relative.starts_with(prefix))
+            })
+            .collect();
+        devices.sort();
+        devices
+            .into_iter()
+            .map(|path| PrinterInfo {
+                name: path.clone(),
+                connection: if path.starts_with("/dev/usb/") {
+                    PrinterConnection::Usb { name: path }
+                } else {
+                    PrinterConnection::Serial {
+                        port: path,
+                        baud_rate: 9600,
+                    }
+                },
+            })
+            .collect()
+    }
+}
+
+fn validate(settings: &PrinterConfig) -> AppResult<()> {
+    if ![58, 80].contains(&settings.paper_width_mm) {
+        return Err(AppError::InvalidArgument(
+            "纸宽只能是 58 或 80 毫米".to_string(),
+        ));
+    }
+    if settings.drawer_pin > 1 {
+        return Err(AppError::InvalidArgument(
+            "钱箱针脚只能是 0 或 1".to_string(),
+        ));
+    }
+    let empty = match &settings.connection {
+        None => false,
+        Some(PrinterConnection::Network { host, port }) => host.trim().is_empty() || *port == 0,
+        Some(PrinterConnection::Serial { port, baud_rate }) => {
+            port.trim().is_empty() || *baud_rate == 0
+        }
+        Some(PrinterConnection::Usb { name }) => name.trim().is_empty(),
+        Some(PrinterConnection::Bluetooth { address }) => address.trim().is_empty(),
+    };
+    if empty {
+        return Err(AppError::InvalidArgument(
+            "打印机的连接信息不完整".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Tauri 命令
+
+/// 列出本机的打印机（已安装的打印机、串口、USB 打印机设备）
+#[tauri::command]
+pub async fn list_printers() -> Vec<PrinterInfo> {
+    local_printers()
+}
+
+#[tauri::command]
+pub fn get_printer_settings(config: tauri::State<'_, ConfigStore>) -> PrinterConfig {
+    config.get().printer
+}
+
+/// 保存小票打印机设置
+#[tauri::command]
+pub fn set_printer_settings(
+    settings: PrinterConfig,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<PrinterConfig> {
+    validate(&settings)?;
+    let updated = config.update(|c| c.printer = settings)?;
+    tracing::info!("小票打印机设置已修改: {:?}", updated.printer.connection);
+    Ok(updated.printer)
+}
+
+/// 打印小票
+#[tauri::command]
+pub async fn print_receipt(
+    receipt: Receipt,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<()> {
+    if receipt.lines.is_empty() || receipt.lines.len() > MAX_LINES {
+        return Err(AppError::InvalidArgument(format!(
+            "小票内容不能为空且不能超过 {} 行",
+            MAX_LINES
+        )));
+    }
+    let settings = config.get().printer;
+    let ticket = render(&receipt, &settings).map_err(AppError::InvalidArgument)?;
+    let copies = receipt.copies.unwrap_or(1).clamp(1, MAX_COPIES);
+
+    let mut data = Vec::with_capacity(ticket.len() * copies as usize + 5);
+    for _ in 0..copies {
+        data.extend_from_slice(&ticket);
+    }
+    if receipt.open_drawer {
+        let mut drawer = EscPos::new(&settings);
+        drawer.open_drawer(settings.drawer_pin);
+        data.extend(drawer.data);
+    }
+    print(&settings, &data, "打印小票")
+}
+
+/// 打开接在打印机上的钱箱
+#[tauri::command]
+pub async fn open_cash_drawer(config: tauri::State<'_, ConfigStore>) -> AppResult<()> {
+    let settings = config.get().printer;
+    let mut printer = EscPos::new(&settings);
+    printer.open_drawer(settings.drawer_pin);
+    print(&settings, &printer.data, "打开钱箱")
+}
@@ -0,0 +1,161 @@
+// Backend 请求代理
+//
+// 前端可以通过 backend_request 经壳程序访问 Backend，壳程序记录每次请求的耗时，
+// 超过阈值时写日志并发出 perf://long-task 事件，在收银员感觉到卡顿之前发现性能下降。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{Manager, State};
+
+use crate::backend::BackendProcess;
+use crate::config::ConfigStore;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::http;
+use crate::reporting::ReportErr;
+
+#[derive(Debug, Deserialize)]
+pub struct ProxyRequest {
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// 以 / 开头的路径，可带查询参数
+    pub path: String,
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProxyResponse {
+    pub status: u16,
+    /// JSON 响应解析为对象，其他响应为字符串
+    pub body: Value,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LongTask {
+    pub method: String,
+    /// 归一化后的接口（数字 ID 替换为 :id，不含查询参数）
+    pub endpoint: String,
+    pub path: String,
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub threshold_ms: u64,
+}
+
+/// 代理请求使用的客户端（超时较长，识别等接口可能需要数秒）
+fn agent() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| http::backend_builder().timeout(Duration::from_secs(120)).build())
+}
+
+/// 把 /products/123?x=1 归一化为 /products/:id，便于按接口聚合
+fn endpoint(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    path.split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn into_response(response: ureq::Response, duration: Duration) -> ProxyResponse {
+    let status = response.status();
+    let is_json = response.content_type() == "application/json";
+    let text = response.into_string().unwrap_or_default();
+    let body = if is_json {
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    } else {
+        Value::String(text)
+    };
+    ProxyResponse {
+        status,
+        body,
+        duration_ms: duration.as_millis() as u64,
+    }
+}
+
+fn report_long_task(app_handle: &tauri::AppHandle, task: LongTask) {
+    tracing::warn!(
+        "Backend 请求耗时过长: {} {} {}ms（阈值 {}ms）",
+        task.method,
+        task.endpoint,
+        task.duration_ms,
+        task.threshold_ms
+    );
+    events::record(
+        EventKind::Backend,
+        format!("请求耗时过长: {} {}", task.method, task.endpoint),
+        json!(task),
+    );
+    let _ = app_handle.emit_all("perf://long-task", task);
+}
+
+// Tauri 命令
+
+/// 经壳程序访问 Backend（记录耗时）
+#[tauri::command]
+pub async fn backend_request(
+    request: ProxyRequest,
+    app_handle: tauri::AppHandle,
+    backend: State<'_, Mutex<BackendProcess>>,
+    config: State<'_, ConfigStore>,
+) -> AppResult<ProxyResponse> {
+    if !request.path.starts_with('/') {
+        return Err(AppError::InvalidArgument(format!(
+            "路径必须以 / 开头: {}",
+            request.path
+        )));
+    }
+
+    let port = backend.lock().unwrap().port();
+    let method = request.method.to_ascii_uppercase();
+    let url = format!("http://127.0.0.1:{}{}", port, request.path);
+
+    let started = Instant::now();
+    let call = agent().request(&method, &url);
+    let result = match &request.body {
+        Some(body) => call.send_json(body),
+        None => call.call(),
+    };
+    let duration = started.elapsed();
+
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => {
+            Ok(into_response(response, duration))
+        }
+        Err(e) => Err(AppError::BackendUnavailable(format!(
+            "请求 Backend 失败: {} {}: {}",
+            method, request.path, e
+        ))),
+    };
+
+    let threshold_ms = config.get().monitoring.long_task_threshold_ms;
+    let duration_ms = duration.as_millis() as u64;
+    if threshold_ms > 0 && duration_ms > threshold_ms {
+        report_long_task(
+            &app_handle,
+            LongTask {
+                method: method.clone(),
+                endpoint: endpoint(&request.path),
+                path: request.path.clone(),
+                status: response.as_ref().ok().map(|r| r.status),
+                duration_ms,
+                threshold_ms,
+            },
+        );
+    }
+
+    response.reported("backend_request")
+}
@@ -0,0 +1,175 @@
+// 启动画面与 Backend 就绪检查
+//
+// 主窗口创建时隐藏，启动时先显示启动画面（splash 窗口），由等待线程轮询 Backend：
+// - 就绪：记录 Backend 版本和启动耗时，发出 backend-ready 事件，关闭启动画面并显示主窗口
+// - 超过 READY_TIMEOUT 仍未就绪：发出 backend-error 事件（附失败原因），启动画面显示原因，
+//   可以直接打开主窗口或查看日志；之后继续检查，Backend 就绪后照常进入主窗口
+//
+// 前端在 backend-ready / backend-error 之前不渲染。无窗口模式和开机自启动时不显示任何窗口。
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::backend::{self, BackendProcess};
+use crate::{crash, startup, tray};
+
+/// 启动画面的窗口标签
+pub const SPLASH_LABEL: &str = "splash";
+
+/// 等待 Backend 就绪的时长（首次启动需迁移数据库）
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 检查间隔（超时后放慢）
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const SLOW_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadinessState {
+    Waiting,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupState {
+    pub state: ReadinessState,
+    /// 启动失败的原因
+    pub error: Option<String>,
+    /// 从开始等待到就绪（或失败）的耗时
+    pub elapsed_ms: u64,
+}
+
+static STATE: Mutex<StartupState> = Mutex::new(StartupState {
+    state: ReadinessState::Waiting,
+    error: None,
+    elapsed_ms: 0,
+});
+
+fn set_state(state: ReadinessState, error: Option<String>, elapsed: Duration) {
+    *STATE.lock().unwrap() = StartupState {
+        state,
+        error,
+        elapsed_ms: elapsed.as_millis() as u64,
+    };
+}
+
+/// 显示主窗口，并记录启动过程中首次显示主窗口的耗时
+fn show_main_window(app_handle: &tauri::AppHandle) {
+    tray::show_main_window(app_handle);
+    startup::record_from("setup", "window_show", true);
+    startup::try_finish(app_handle);
+}
+
+/// 关闭启动画面，非无窗口模式时显示主窗口
+fn enter_main_window(app_handle: &tauri::AppHandle) {
+    if !tray::start_hidden() {
+        show_main_window(app_handle);
+    }
+    if let Some(splash) = app_handle.get_window(SPLASH_LABEL) {
+        let _ = splash.close();
+    }
+}
+
+fn wait(app_handle: &tauri::AppHandle) {
+    let started = Instant::now();
+    // 端口被占用时 Backend 可能改用其他端口，每次都读取当前端口
+    let port = || {
+        app_handle
+            .state::<Mutex<BackendProcess>>()
+            .lock()
+            .unwrap()
+            .port()
+    };
+
+    let mut failed = false;
+    let version = loop {
+        if let Some(version) = backend::fetch_version(port()) {
+            break version;
+        }
+        if !failed && started.elapsed() >= READY_TIMEOUT {
+            failed = true;
+            let reason = backend::last_error()
+                .unwrap_or_else(|| format!("Backend 在 {} 秒内未就绪", READY_TIMEOUT.as_secs()));
+            tracing::error!("Backend 启动超时: {}", reason);
+            set_state(
+                ReadinessState::Failed,
+                Some(reason.clone()),
+                started.elapsed(),
+            );
+            startup::record_from("backend_spawned", "backend_ready", false);
+            startup::try_finish(app_handle);
+            let _ = app_handle.emit_all("backend-error", serde_json::json!({ "reason": reason }));
+        }
+        std::thread::sleep(if failed {
+            SLOW_POLL_INTERVAL
+        } else {
+            POLL_INTERVAL
+        });
+    };
+
+    crash::set_backend_version(version.clone());
+    set_state(ReadinessState::Ready, None, started.elapsed());
+    tracing::info!("Backend 已就绪（{} ms）", started.elapsed().as_millis());
+    if !failed {
+        startup::record_from("backend_spawned", "backend_ready", true);
+        let timings = backend::fetch_startup_timings(port());
+        for (name, ms) in timings.unwrap_or_default() {
+            if let Some(ms) = ms.as_f64() {
+                startup::record_duration(&name, ms as u64);
+            }
+        }
+        startup::try_finish(app_handle);
+    }
+    let _ = app_handle.emit_all(
+        "backend-ready",
+        serde_json::json!({
+            "version": version,
+            "elapsed_ms": started.elapsed().as_millis() as u64,
+        }),
+    );
+    enter_main_window(app_handle);
+}
+
+/// 显示启动画面并开始等待 Backend
+pub fn start(app_handle: &tauri::AppHandle) {
+    if !tray::start_hidden() {
+        if let Some(splash) = app_handle.get_window(SPLASH_LABEL) {
+            let _ = splash.show();
+        }
+    }
+
+    let handle = app_handle.clone();
+    let spawned = std::thread::Builder::new()
+        .name("backend-ready".into())
+        .spawn(move || wait(&handle));
+    if let Err(e) = spawned {
+        tracing::error!("启动就绪检查线程失败: {}", e);
+        enter_main_window(app_handle);
+    }
+}
+
+// Tauri 命令
+
+/// 当前的启动状态（前端和启动画面可能错过事件时读取）
+#[tauri::command]
+pub fn get_startup_state() -> StartupState {
+    STATE.lock().unwrap().clone()
+}
+
+/// Backend 未就绪时直接打开主窗口（启动画面的“仍然打开”按钮）
+#[tauri::command]
+pub fn open_main_window(app_handle: tauri::AppHandle) {
+    show_main_window(&app_handle);
+    if let Some(splash) = app_handle.get_window(SPLASH_LABEL) {
+        let _ = splash.close();
+    }
+}
+
+/// 打开日志目录（启动画面的“查看日志”按钮）
+#[tauri::command]
+pub fn open_log_folder() {
+    tray::open_log_folder();
+}
@@ -0,0 +1,261 @@
+// 录屏
+//
+// 偶发的界面问题很难用截图说明，提交问题前可以录一段主窗口的短片：
+// - 按设置中的帧率截取主窗口内容区域（打马赛克的区域与截图相同），编码为 GIF
+// - 超过最长录制时间或文件大小上限时自动停止，并发出 recording://stopped 事件
+// - 文件保存在技术支持目录，生成技术支持包时会一起打包
+//
+// 同一时间只能有一个录屏。
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame};
+
+use crate::config::ConfigStore;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::screenshot::{self, CaptureTarget};
+use crate::{shutdown, support};
+
+/// GIF 编码速度（1-30，越大越快、颜色越差）
+const ENCODE_SPEED: i32 = 10;
+
+/// 进行中（或已自动停止但还未取走结果）的录屏
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+struct Recording {
+    info: RecordingInfo,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Result<RecordingResult, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingInfo {
+    pub path: String,
+    pub started_at: String,
+    pub max_secs: u64,
+    pub fps: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StopReason {
+    /// 用户停止
+    User,
+    /// 达到最长录制时间
+    Duration,
+    /// 达到文件大小上限
+    Size,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingResult {
+    pub path: String,
+    pub frames: u32,
+    pub width: u32,
+    pub height: u32,
+    pub duration_ms: u64,
+    pub size: u64,
+    pub reason: StopReason,
+}
+
+fn record(
+    app_handle: &tauri::AppHandle,
+    path: &PathBuf,
+    stop: &AtomicBool,
+) -> Result<RecordingResult, String> {
+    let _busy = shutdown::busy("screen_recording");
+    let config = app_handle.state::<ConfigStore>().get();
+    let settings = config.recording;
+    let regions = config.support.blur_regions;
+    let interval = Duration::from_millis(1000 / settings.fps.clamp(1, 15) as u64);
+    let max_duration = Duration::from_secs(settings.max_secs.max(1));
+    let max_bytes = settings.max_mb.max(1) * 1024 * 1024;
+
+    let file = File::create(path).map_err(|e| format!("创建文件失败: {}", e))?;
+    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), ENCODE_SPEED);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|e| format!("写入录屏失败: {}", e))?;
+
+    let started = Instant::now();
+    let mut size: Option<(u32, u32)> = None;
+    let mut frames = 0;
+    let mut previous: Option<(image::RgbaImage, Instant)> = None;
+    let reason = loop {
+        let captured_at = Instant::now();
+        let mut image = screenshot::capture_window(app_handle, CaptureTarget::Window, &regions)?;
+
+        // 第一帧决定画面大小，之后窗口大小变化时缩放到相同大小
+        let (width, height) = *size.get_or_insert_with(|| {
+            if image.width() > settings.max_width {
+                let height =
+                    image.height() as u64 * settings.max_width as u64 / image.width() as u64;
+                (settings.max_width, height.max(1) as u32)
+            } else {
+                (image.width(), image.height())
+            }
+        });
+        if image.dimensions() != (width, height) {
+            image = image::imageops::resize(
+                &image,
+                width,
+                height,
+                image::imageops::FilterType::Triangle,
+            );
+        }
+
+        // 帧的显示时长要等下一帧截取时才知道，因此延后一帧写入
+        if let Some((frame, at)) = previous.replace((image, captured_at)) {
+            let delay = Delay::from_saturating_duration(captured_at - at);
+            encoder
+                .encode_frame(Frame::from_parts(frame, 0, 0, delay))
+                .map_err(|e| format!("写入录屏失败: {}", e))?;
+            frames += 1;
+        }
+
+        if stop.load(Ordering::Relaxed) {
+            break StopReason::User;
+        }
+        if started.elapsed() >= max_duration {
+            break StopReason::Duration;
+        }
+        let written = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if written >= max_bytes {
+            break StopReason::Size;
+        }
+        std::thread::sleep(interval.saturating_sub(captured_at.elapsed()));
+    };
+
+    if let Some((frame, at)) = previous {
+        let delay = Delay::from_saturating_duration(at.elapsed().max(interval));
+        encoder
+            .encode_frame(Frame::from_parts(frame, 0, 0, delay))
+            .map_err(|e| format!("写入录屏失败: {}", e))?;
+        frames += 1;
+    }
+    // 释放编码器时写入文件结尾
+    drop(encoder);
+
+    let (width, height) = size.unwrap_or_default();
+    Ok(RecordingResult {
+        path: path.to_string_lossy().into_owned(),
+        frames,
+        width,
+        height,
+        duration_ms: started.elapsed().as_millis() as u64,
+        size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        reason,
+    })
+}
+
+fn finished(app_handle: &tauri::AppHandle, result: &Result<RecordingResult, String>) {
+    match result {
+        Ok(result) => {
+            tracing::info!(
+                "录屏已保存: {} ({} 帧, {:?})",
+                result.path,
+                result.frames,
+                result.reason
+            );
+            events::record(
+                EventKind::State,
+                "保存录屏",
+                serde_json::json!({
+                    "path": result.path,
+                    "frames": result.frames,
+                    "duration_ms": result.duration_ms,
+                    "reason": result.reason,
+                }),
+            );
+            let _ = app_handle.emit_all("recording://stopped", result);
+        }
+        Err(e) => {
+            tracing::warn!("录屏失败: {}", e);
+            let _ = app_handle.emit_all("recording://failed", e);
+        }
+    }
+}
+
+// Tauri 命令
+
+/// 开始录制主窗口，返回保存路径和录制限制
+#[tauri::command]
+pub async fn start_screen_recording(app_handle: tauri::AppHandle) -> AppResult<RecordingInfo> {
+    let mut recording = RECORDING.lock().unwrap();
+    if recording.as_ref().is_some_and(|r| !r.handle.is_finished()) {
+        return Err(AppError::InvalidArgument("已经在录屏".to_string()));
+    }
+
+    let settings = app_handle.state::<ConfigStore>().get().recording;
+    let dir = support::support_dir(&app_handle);
+    std::fs::create_dir_all(&dir).map_err(|e| AppError::Io(format!("创建目录失败: {}", e)))?;
+    let path = dir.join(format!(
+        "recording-{}.gif",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let info = RecordingInfo {
+        path: path.to_string_lossy().into_owned(),
+        started_at: chrono::Local::now().to_rfc3339(),
+        max_secs: settings.max_secs,
+        fps: settings.fps,
+    };
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = {
+        let stop = stop.clone();
+        let app_handle = app_handle.clone();
+        std::thread::Builder::new()
+            .name("screen-recording".into())
+            .spawn(move || {
+                let result = record(&app_handle, &path, &stop);
+                finished(&app_handle, &result);
+                result
+            })
+            .map_err(|e| AppError::Internal(format!("启动录屏线程失败: {}", e)))?
+    };
+
+    tracing::info!("开始录屏: {}", info.path);
+    *recording = Some(Recording {
+        info: info.clone(),
+        stop,
+        handle,
+    });
+    Ok(info)
+}
+
+/// 停止录屏并返回结果（已自动停止时直接返回结果）
+#[tauri::command]
+pub async fn stop_screen_recording() -> AppResult<RecordingResult> {
+    let recording = RECORDING
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| AppError::InvalidArgument("没有正在进行的录屏".to_string()))?;
+    recording.stop.store(true, Ordering::Relaxed);
+    recording
+        .handle
+        .join()
+        .map_err(|_| AppError::Internal("录屏线程异常退出".to_string()))?
+        .map_err(AppError::Internal)
+}
+
+/// 查看进行中的录屏
+#[tauri::command]
+pub fn get_screen_recording() -> Option<RecordingInfo> {
+    RECORDING
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|r| !r.handle.is_finished())
+        .map(|r| r.info.clone())
+}
@@ -0,0 +1,280 @@
+// 定时提醒
+//
+// 前端通过 schedule_notification 预约一条系统通知（例如日结提醒、备份逾期、授权到期），
+// 由壳程序到点显示，不依赖 Backend 和页面是否在运行。
+// 提醒保存在 <应用数据目录>/reminders.json，重启后继续生效；repeat 为 cron 表达式时每次显示后自动排到下一次。
+//
+// 程序未运行期间错过的提醒在启动后补发一次，超过 MAX_LATE_HOURS 的直接丢弃。
+// 显示通知的同时发出 reminder://fired 事件，payload 为该提醒，前端可据此跳转到对应页面。
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::cron::Schedule;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::{config, notify};
+
+const REMINDERS_FILE: &str = "reminders.json";
+
+/// 检查间隔
+const TICK: Duration = Duration::from_secs(15);
+
+/// 错过的提醒最多补发多久以前的（小时）
+const MAX_LATE_HOURS: i64 = 24;
+
+/// 最多保存多少条提醒
+const MAX_REMINDERS: usize = 200;
+
+static REMINDERS: Mutex<Vec<Reminder>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderPayload {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    /// 提醒类别（例如 close_day、backup_overdue、license_expiry），同类别的提醒可整体替换
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// 原样交给前端的附加数据
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    /// 下次显示时间（RFC 3339）
+    pub time: String,
+    /// 重复规则（cron 表达式），为空表示只提醒一次
+    pub repeat: Option<String>,
+    pub payload: ReminderPayload,
+    pub created_at: String,
+}
+
+fn reminders_path(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join(REMINDERS_FILE)
+}
+
+fn load(app_handle: &tauri::AppHandle) {
+    let Ok(content) = std::fs::read_to_string(reminders_path(app_handle)) else {
+        return;
+    };
+    match serde_json::from_str(&content) {
+        Ok(reminders) => *REMINDERS.lock().unwrap() = reminders,
+        Err(e) => tracing::warn!("读取提醒失败: {}", e),
+    }
+}
+
+fn save(app_handle: &tauri::AppHandle, reminders: &[Reminder]) {
+    let path = reminders_path(app_handle);
+    let content = match serde_json::to_string_pretty(reminders) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::warn!("序列化提醒失败: {}", e);
+            return;
+        }
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = std::fs::write(&path, content) {
+        tracing::warn!("保存提醒失败: {:?} ({})", path, e);
+    }
+}
+
+/// 解析时间：RFC 3339，或本地时间 "YYYY-MM-DD HH:MM[:SS]"
+fn parse_time(time: &str) -> Result<DateTime<Local>, String> {
+    let time = time.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(time) {
+        return Ok(time.with_timezone(&Local));
+    }
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(time, format).ok())
+    .and_then(|time| Local.from_local_datetime(&time).earliest())
+    .ok_or_else(|| format!("时间格式错误: {}", time))
+}
+
+fn fire(app_handle: &tauri::AppHandle, reminder: &Reminder) {
+    tracing::info!("显示提醒: {} ({})", reminder.payload.title, reminder.id);
+    notify::show(app_handle, &reminder.payload.title, &reminder.payload.body);
+    events::record(
+        EventKind::State,
+        "显示提醒",
+        serde_json::json!({ "id": reminder.id, "kind": reminder.payload.kind }),
+    );
+    let _ = app_handle.emit_all("reminder://fired", reminder);
+}
+
+/// 显示到期的提醒，重复的提醒排到下一次，一次性的删除
+fn tick(app_handle: &tauri::AppHandle) {
+    let now = Local::now();
+    let mut due = Vec::new();
+    let reminders = {
+        let mut reminders = REMINDERS.lock().unwrap();
+        let mut changed = false;
+
+        reminders.retain_mut(|reminder| {
+            let Ok(time) = parse_time(&reminder.time) else {
+                changed = true;
+                return false;
+            };
+            if time > now {
+                return true;
+            }
+            changed = true;
+            if now - time <= chrono::Duration::hours(MAX_LATE_HOURS) {
+                due.push(reminder.clone());
+            } else {
+                tracing::warn!(
+                    "提醒已过期太久，不再显示: {} ({})",
+                    reminder.id,
+                    reminder.time
+                );
+            }
+            let next = reminder
+                .repeat
+                .as_deref()
+                .and_then(|repeat| Schedule::parse(repeat).ok())
+                .and_then(|schedule| schedule.next_after(now));
+            match next {
+                Some(next) => {
+                    reminder.time = next.to_rfc3339();
+                    true
+                }
+                None => false,
+            }
+        });
+
+        changed.then(|| reminders.clone())
+    };
+
+    if let Some(reminders) = reminders {
+        save(app_handle, &reminders);
+    }
+    for reminder in &due {
+        fire(app_handle, reminder);
+    }
+}
+
+/// 读取保存的提醒并启动检查线程
+pub fn start(app_handle: tauri::AppHandle) {
+    load(&app_handle);
+
+    let spawned = std::thread::Builder::new()
+        .name("reminders".into())
+        .spawn(move || loop {
+            tick(&app_handle);
+            std::thread::sleep(TICK);
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动提醒线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 预约一条提醒，返回保存的提醒（含 ID）
+///
+/// payload.kind 不为空时会替换同类别的未显示提醒；repeat 为 cron 表达式时按规则重复，此时 time 可为空。
+#[tauri::command]
+pub fn schedule_notification(
+    time: Option<String>,
+    payload: ReminderPayload,
+    repeat: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<Reminder> {
+    if payload.title.trim().is_empty() {
+        return Err(AppError::InvalidArgument("提醒标题不能为空".to_string()));
+    }
+    let repeat = repeat.filter(|repeat| !repeat.trim().is_empty());
+    let schedule = repeat
+        .as_deref()
+        .map(Schedule::parse)
+        .transpose()
+        .map_err(AppError::InvalidArgument)?;
+
+    let now = Local::now();
+    let time = match (time.as_deref().filter(|t| !t.trim().is_empty()), &schedule) {
+        (Some(time), _) => parse_time(time).map_err(AppError::InvalidArgument)?,
+        (None, Some(schedule)) => schedule
+            .next_after(now)
+            .ok_or_else(|| AppError::InvalidArgument("重复规则没有可用的时间".to_string()))?,
+        (None, None) => {
+            return Err(AppError::InvalidArgument(
+                "需要指定提醒时间或重复规则".to_string(),
+            ))
+        }
+    };
+    if time <= now && schedule.is_none() {
+        return Err(AppError::InvalidArgument(format!(
+            "提醒时间已过: {}",
+            time.format("%Y-%m-%d %H:%M:%S")
+        )));
+    }
+
+    let reminder = Reminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        time: time.to_rfc3339(),
+        repeat,
+        payload,
+        created_at: now.to_rfc3339(),
+    };
+
+    let reminders = {
+        let mut reminders = REMINDERS.lock().unwrap();
+        if let Some(kind) = &reminder.payload.kind {
+            reminders.retain(|r| r.payload.kind.as_ref() != Some(kind));
+        }
+        if reminders.len() >= MAX_REMINDERS {
+            return Err(AppError::InvalidArgument(format!(
+                "提醒数量已达上限 ({})",
+                MAX_REMINDERS
+            )));
+        }
+        reminders.push(reminder.clone());
+        reminders.clone()
+    };
+    save(&app_handle, &reminders);
+    tracing::info!(
+        "已预约提醒: {} 于 {}",
+        reminder.payload.title,
+        reminder.time
+    );
+    Ok(reminder)
+}
+
+/// 列出所有未显示的提醒（按时间排序）
+#[tauri::command]
+pub fn list_notifications() -> Vec<Reminder> {
+    let mut reminders = REMINDERS.lock().unwrap().clone();
+    reminders.sort_by_key(|r| parse_time(&r.time).ok());
+    reminders
+}
+
+/// 取消提醒，返回是否存在
+#[tauri::command]
+pub fn cancel_notification(id: String, app_handle: tauri::AppHandle) -> bool {
+    let reminders = {
+        let mut reminders = REMINDERS.lock().unwrap();
+        let before = reminders.len();
+        reminders.retain(|r| r.id != id);
+        if reminders.len() == before {
+            return false;
+        }
+        reminders.clone()
+    };
+    save(&app_handle, &reminders);
+    true
+}
@@ -0,0 +1,271 @@
+// 报表 PDF
+//
+// 用隐藏窗口打开 Backend 生成的报表页面（/reports/sales_daily/html），
+// 通过 WebView2 的打印为 PDF 功能保存到 <应用数据目录>/reports/z-report-YYYY-MM-DD.pdf，
+// 需要时再交给系统默认程序静默打印。
+//
+// 每天营业结束后由定时任务 z_report 自动归档当日的日结报表，也可以在页面上手动生成。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::backend::{self, BackendProcess};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::{config, scheduler, shutdown};
+
+/// 等待报表页面加载的最长时间
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 等待生成 PDF 的最长时间
+const PRINT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 报表窗口的标签前缀（用于和主窗口区分页面加载事件）
+const WINDOW_PREFIX: &str = "report-";
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 正在等待页面加载的报表窗口
+static PENDING: Mutex<Option<HashMap<String, mpsc::Sender<()>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportPdf {
+    pub date: String,
+    pub path: String,
+    pub printed: bool,
+}
+
+/// 报表 PDF 保存目录
+pub fn reports_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join("reports")
+}
+
+/// 是否为报表窗口
+pub fn is_report_window(window: &tauri::Window) -> bool {
+    window.label().starts_with(WINDOW_PREFIX)
+}
+
+/// 报表窗口页面加载完成（由全局的 on_page_load 转发）
+pub fn page_loaded(window: &tauri::Window) {
+    let sender = PENDING
+        .lock()
+        .unwrap()
+        .as_mut()
+        .and_then(|pending| pending.remove(window.label()));
+    if let Some(sender) = sender {
+        let _ = sender.send(());
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn print_to_pdf(window: &tauri::Window, path: &Path) -> Result<(), String> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_7;
+    use webview2_com::PrintToPdfCompletedHandler;
+    use windows::core::{Interface, PCWSTR};
+
+    let (sender, receiver) = mpsc::channel();
+    let path: Vec<u16> = path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(Some(0))
+        .collect();
+
+    window
+        .with_webview(move |webview| {
+            let started = unsafe {
+                webview
+                    .controller()
+                    .CoreWebView2()
+                    .and_then(|core| core.cast::<ICoreWebView2_7>())
+                    .and_then(|core| {
+                        let done = sender.clone();
+                        let handler =
+                            PrintToPdfCompletedHandler::create(Box::new(move |result, ok| {
+                                let _ = done.send(match result {
+                                    Ok(()) if ok => Ok(()),
+                                    Ok(()) => Err("生成 PDF 失败".to_string()),
+                                    Err(e) => Err(format!("生成 PDF 失败: {}", e)),
+                                });
+                                Ok(())
+                            }));
+                        core.PrintToPdf(PCWSTR(path.as_ptr()), None, &handler)
+                    })
+            };
+            if let Err(e) = started {
+                let _ = sender.send(Err(format!("WebView2 不支持打印为 PDF: {}", e)));
+            }
+        })
+        .map_err(|e| format!("访问报表窗口失败: {}", e))?;
+
+    receiver
+        .recv_timeout(PRINT_TIMEOUT)
+        .map_err(|_| "生成 PDF 超时".to_string())?
+}
+
+#[cfg(not(target_os = "windows"))]
+fn print_to_pdf(_window: &tauri::Window, _path: &Path) -> Result<(), String> {
+    Err("当前系统不支持导出 PDF".to_string())
+}
+
+/// 交给系统默认的 PDF 程序打印（需已安装支持打印的 PDF 阅读器）
+#[cfg(target_os = "windows")]
+fn print_file(path: &Path) -> Result<(), String> {
+    let path = path.to_string_lossy().replace('\'', "''");
+    let output = crate::os::hidden_command("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Start-Process -FilePath '{}' -Verb Print -WindowStyle Hidden",
+                path
+            ),
+        ])
+        .output()
+        .map_err(|e| format!("打印失败: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "打印失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn print_file(path: &Path) -> Result<(), String> {
+    crate::os::hidden_command("lp")
+        .arg(path)
+        .status()
+        .map_err(|e| format!("打印失败: {}", e))
+        .and_then(|status| {
+            status
+                .success()
+                .then_some(())
+                .ok_or_else(|| "打印失败".to_string())
+        })
+}
+
+/// 打开报表页面并保存为 PDF（阻塞，不能在主线程调用）
+fn render(app_handle: &tauri::AppHandle, date: &str) -> Result<PathBuf, String> {
+    let _busy = shutdown::busy("report_pdf");
+    let dir = reports_dir(app_handle);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    let path = dir.join(format!("z-report-{}.pdf", date));
+
+    let port = app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .port();
+    let url = format!("http://127.0.0.1:{}/reports/sales_daily/html", port);
+    let mut url: tauri::Url = url.parse().map_err(|e| format!("报表地址无效: {}", e))?;
+    // 窗口加载页面时无法带上 Authorization 头，Token 放在查询参数中（Backend 日志中会隐去）
+    url.query_pairs_mut()
+        .append_pair("date", date)
+        .append_pair("access_token", backend::token());
+
+    let label = format!(
+        "{}{}",
+        WINDOW_PREFIX,
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let (sender, receiver) = mpsc::channel();
+    PENDING
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(label.clone(), sender);
+
+    let window = tauri::WindowBuilder::new(app_handle, &label, tauri::WindowUrl::External(url))
+        .title("报表")
+        .visible(false)
+        .skip_taskbar(true)
+        .build()
+        .map_err(|e| format!("创建报表窗口失败: {}", e));
+
+    let result = window.and_then(|window| {
+        let result = receiver
+            .recv_timeout(LOAD_TIMEOUT)
+            .map_err(|_| "加载报表页面超时".to_string())
+            .and_then(|_| print_to_pdf(&window, &path));
+        let _ = window.close();
+        result
+    });
+
+    if let Some(pending) = PENDING.lock().unwrap().as_mut() {
+        pending.remove(&label);
+    }
+    result.map(|_| path)
+}
+
+/// 生成日结报表 PDF 并记录事件，print 为 true 时同时打印
+pub(crate) fn render_and_record(
+    app_handle: &tauri::AppHandle,
+    date: &str,
+    print: bool,
+) -> Result<ReportPdf, String> {
+    let path = render(app_handle, date)?;
+    tracing::info!("日结报表已保存: {:?}", path);
+
+    let printed = print
+        && match print_file(&path) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("{}", e);
+                false
+            }
+        };
+
+    let report = ReportPdf {
+        date: date.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        printed,
+    };
+    events::record(
+        EventKind::Print,
+        "日结报表已生成",
+        serde_json::json!(report),
+    );
+    Ok(report)
+}
+
+/// 定时任务：归档当日的日结报表
+fn archive_today(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    render_and_record(app_handle, &date, false).map(|report| format!("已保存: {}", report.path))
+}
+
+/// 登记日结报表定时任务（默认每天 23:55）
+pub fn register_tasks() {
+    scheduler::register(
+        "z_report",
+        "归档日结报表 PDF",
+        Some("55 23 * * *"),
+        archive_today,
+    );
+}
+
+// Tauri 命令
+
+/// 生成指定日期（默认今天）的日结报表 PDF，print 为 true 时同时打印
+#[tauri::command]
+pub async fn render_report_pdf(
+    date: Option<String>,
+    print: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<ReportPdf> {
+    let date = match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| AppError::InvalidArgument(format!("日期格式错误: {}", date)))?
+            .format("%Y-%m-%d")
+            .to_string(),
+        None => chrono::Local::now().format("%Y-%m-%d").to_string(),
+    };
+    render_and_record(&app_handle, &date, print.unwrap_or(false)).map_err(AppError::Internal)
+}
@@ -0,0 +1,233 @@
+// 错误上报模块
+//
+// 用户在设置中开启后，通过 Sentry 协议上报 Rust panic 和命令错误，
+// 便于发现门店现场的崩溃。默认关闭。
+//
+// 上报前会清理个人信息：用户目录和用户名、主机名、邮箱、手机号以及较长的数字串
+// （会员卡号、银行卡号等）。
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::State;
+
+use crate::config::{ConfigStore, CrashReportingConfig, UploadConsent};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::telemetry;
+
+/// 构建时内置的上报地址
+const BUILTIN_DSN: Option<&str> = option_env!("SMARTMART_SENTRY_DSN");
+
+static GUARD: Mutex<Option<sentry::ClientInitGuard>> = Mutex::new(None);
+
+/// 根据配置开启或关闭上报
+pub fn apply(config: &CrashReportingConfig) -> Result<(), String> {
+    let mut guard = GUARD.lock().unwrap();
+
+    if !config.enabled {
+        if guard.take().is_some() {
+            tracing::info!("错误上报已关闭");
+        }
+        return Ok(());
+    }
+    if guard.is_some() {
+        return Ok(());
+    }
+
+    let client = sentry::init(client_options(config)?);
+    *guard = Some(client);
+
+    tracing::info!("错误上报已开启");
+    Ok(())
+}
+
+fn client_options(config: &CrashReportingConfig) -> Result<sentry::ClientOptions, String> {
+    let dsn = config
+        .dsn
+        .as_deref()
+        .filter(|dsn| !dsn.is_empty())
+        .or(BUILTIN_DSN)
+        .ok_or("未配置错误上报地址")?;
+    let dsn: sentry::types::Dsn = dsn.parse().map_err(|e| format!("上报地址无效: {}", e))?;
+
+    Ok(sentry::ClientOptions {
+        dsn: Some(dsn),
+        release: Some(Cow::Borrowed(concat!(
+            "smartmart-desktop@",
+            env!("CARGO_PKG_VERSION")
+        ))),
+        send_default_pii: false,
+        before_send: Some(Arc::new(|event| Some(scrub_event(event)))),
+        ..Default::default()
+    })
+}
+
+/// 上传一份崩溃报告及相关日志（以附件形式，内容已清理个人信息）
+///
+/// 使用独立的客户端，不要求开启错误上报
+pub fn upload_crash_report(
+    config: &CrashReportingConfig,
+    name: &str,
+    report: &str,
+    logs: &str,
+) -> Result<(), String> {
+    let client = Arc::new(sentry::Client::from(client_options(config)?));
+    let hub = sentry::Hub::new(Some(client.clone()), Arc::new(sentry::Scope::default()));
+
+    let summary = report
+        .lines()
+        .find_map(|line| line.strip_prefix("信息: "))
+        .unwrap_or("程序崩溃");
+    let attachment = |filename: &str, content: &str| sentry::protocol::Attachment {
+        buffer: scrub(content).into_bytes(),
+        filename: filename.to_string(),
+        content_type: Some("text/plain".to_string()),
+        ..Default::default()
+    };
+
+    hub.with_scope(
+        |scope| {
+            scope.set_tag("kind", "crash_report");
+            scope.add_attachment(attachment(name, report));
+            scope.add_attachment(attachment("logs.txt", logs));
+        },
+        || hub.capture_message(&scrub(summary), sentry::Level::Fatal),
+    );
+
+    if client.flush(Some(Duration::from_secs(15))) {
+        Ok(())
+    } else {
+        Err("上传崩溃报告超时".to_string())
+    }
+}
+
+/// 上报命令错误（未开启上报时只写日志）
+pub fn capture_error(command: &str, message: &str) {
+    tracing::warn!("命令 {} 失败: {}", command, message);
+    events::record(
+        EventKind::Error,
+        message,
+        serde_json::json!({ "command": command }),
+    );
+    telemetry::record_error(command);
+
+    if GUARD.lock().map(|g| g.is_some()).unwrap_or(false) {
+        sentry::with_scope(
+            |scope| scope.set_tag("command", command),
+            || sentry::capture_message(message, sentry::Level::Error),
+        );
+    }
+}
+
+/// 为命令结果附加错误上报：`do_something().reported("command_name")`
+pub trait ReportErr {
+    fn reported(self, command: &str) -> Self;
+}
+
+impl<T, E: std::fmt::Display> ReportErr for Result<T, E> {
+    fn reported(self, command: &str) -> Self {
+        if let Err(e) = &self {
+            capture_error(command, &e.to_string());
+        }
+        self
+    }
+}
+
+fn scrub_event(mut event: sentry::protocol::Event<'static>) -> sentry::protocol::Event<'static> {
+    event.user = None;
+    event.server_name = None;
+    event.message = event.message.map(|m| scrub(&m));
+
+    for exception in event.exception.values.iter_mut() {
+        exception.value = exception.value.as_deref().map(scrub);
+        if let Some(stacktrace) = exception.stacktrace.as_mut() {
+            for frame in stacktrace.frames.iter_mut() {
+                frame.abs_path = frame.abs_path.as_deref().map(scrub);
+                frame.filename = frame.filename.as_deref().map(scrub);
+            }
+        }
+    }
+    for breadcrumb in event.breadcrumbs.values.iter_mut() {
+        breadcrumb.message = breadcrumb.message.as_deref().map(scrub);
+    }
+    event
+}
+
+/// 清理文本中的个人信息
+pub fn scrub(text: &str) -> String {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        vec![
+            (
+                Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(),
+                "<email>",
+            ),
+            (Regex::new(r"\b1[3-9]\d{9}\b").unwrap(), "<phone>"),
+            (Regex::new(r"\d{12,}").unwrap(), "<number>"),
+        ]
+    });
+
+    let mut text = text.to_string();
+
+    // 用户目录（可能包含真实姓名）
+    for var in ["USERPROFILE", "HOME"] {
+        if let Ok(home) = std::env::var(var) {
+            if home.len() > 3 {
+                text = text.replace(&home, "~");
+            }
+        }
+    }
+    for var in ["USERNAME", "USER", "COMPUTERNAME", "HOSTNAME"] {
+        if let Ok(name) = std::env::var(var) {
+            if name.len() > 2 {
+                text = text.replace(&name, "<user>");
+            }
+        }
+    }
+
+    for (pattern, replacement) in patterns {
+        text = pattern.replace_all(&text, *replacement).into_owned();
+    }
+    text
+}
+
+// Tauri 命令
+
+#[tauri::command]
+pub fn get_crash_reporting(config: State<'_, ConfigStore>) -> bool {
+    config.get().crash_reporting.enabled
+}
+
+#[tauri::command]
+pub fn set_crash_reporting(enabled: bool, config: State<'_, ConfigStore>) -> AppResult<()> {
+    // 先应用，成功后再保存，避免上报地址无效时保存了没有生效的设置；保存失败时恢复原来的设置
+    let previous = config.get().crash_reporting;
+    let mut settings = previous.clone();
+    settings.enabled = enabled;
+    apply(&settings)
+        .map_err(AppError::CrashReporting)
+        .reported("set_crash_reporting")?;
+    if let Err(e) = config.update(|c| c.crash_reporting.enabled = enabled) {
+        let _ = apply(&previous);
+        return Err(e).reported("set_crash_reporting");
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_crash_upload_consent(config: State<'_, ConfigStore>) -> UploadConsent {
+    config.get().crash_reporting.upload_consent
+}
+
+#[tauri::command]
+pub fn set_crash_upload_consent(
+    consent: UploadConsent,
+    config: State<'_, ConfigStore>,
+) -> AppResult<()> {
+    config
+        .update(|c| c.crash_reporting.upload_consent = consent)
+        .reported("set_crash_upload_consent")?;
+    Ok(())
+}
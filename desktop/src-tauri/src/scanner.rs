@@ -0,0 +1,504 @@
+// 扫码枪全局识别
+//
+// 键盘模拟的扫码枪只有在收银页面获得焦点时才能被识别；弹出对话框或其他程序在前台时，
+// 条码会输入到错误的位置。开启 scanner.enabled 后（仅 Windows）安装系统级低级键盘钩子，
+// 按按键速度识别扫码：
+// - 相邻按键间隔都不超过 max_interval_ms、至少 min_length 个字符，require_enter 时需以回车结束
+// - 主窗口在前台时只识别不拦截，按键照常输入到页面
+// - 其他窗口在前台且 suppress 开启时，可能是扫码的按键先暂存：确认是扫码后丢弃，
+//   否则（人工输入）按原顺序重新发送给前台窗口，人工输入最多延迟 max_interval_ms
+//
+// 识别出的条码发出 scanner://scan 事件并记录到事件日志。
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::config::{ConfigStore, ScannerConfig};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+
+/// 钩子线程的状态
+static STATE: Mutex<State> = Mutex::new(State {
+    thread_id: None,
+    scans: 0,
+    last_scan: None,
+    error: None,
+});
+
+struct State {
+    /// 钩子线程 ID（用于通知线程退出）
+    thread_id: Option<u32>,
+    scans: u64,
+    last_scan: Option<ScanEvent>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanEvent {
+    pub code: String,
+    /// 按键是否已被拦截（未输入到前台窗口）
+    pub suppressed: bool,
+    /// 扫码时主窗口是否在前台
+    pub foreground: bool,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannerStatus {
+    /// 当前平台是否支持
+    pub supported: bool,
+    pub enabled: bool,
+    /// 键盘钩子是否已安装
+    pub running: bool,
+    pub scans: u64,
+    pub last_scan: Option<ScanEvent>,
+    pub error: Option<String>,
+}
+
+fn on_scan(app_handle: &tauri::AppHandle, scan: ScanEvent) {
+    tracing::debug!("扫码枪输入: {} (拦截: {})", scan.code, scan.suppressed);
+    events::record(
+        EventKind::Scan,
+        scan.code.clone(),
+        serde_json::json!({
+            "source": "keyboard_hook",
+            "suppressed": scan.suppressed,
+            "foreground": scan.foreground,
+        }),
+    );
+    {
+        let mut state = STATE.lock().unwrap();
+        state.scans += 1;
+        state.last_scan = Some(scan.clone());
+    }
+    let _ = app_handle.emit_all("scanner://scan", scan);
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::System::Threading::GetCurrentThreadId;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        GetKeyState, SendInput, ToUnicode, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT,
+        KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VK_CAPITAL, VK_CONTROL,
+        VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_RCONTROL, VK_RETURN, VK_RMENU,
+        VK_RSHIFT, VK_RWIN, VK_SHIFT,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetForegroundWindow, GetMessageW, KillTimer, PostThreadMessageW, SetTimer,
+        SetWindowsHookExW, UnhookWindowsHookEx, KBDLLHOOKSTRUCT, LLKHF_EXTENDED, LLKHF_INJECTED,
+        MSG, WH_KEYBOARD_LL, WM_APP, WM_KEYDOWN, WM_QUIT, WM_SYSKEYDOWN, WM_TIMER,
+    };
+
+    use super::{on_scan, ScanEvent, STATE};
+    use crate::config::ScannerConfig;
+
+    /// 钩子中识别出条码后通知消息循环
+    const WM_SCAN: u32 = WM_APP + 1;
+
+    thread_local! {
+        static DETECTOR: RefCell<Option<Detector>> = const { RefCell::new(None) };
+    }
+
+    struct Detector {
+        settings: ScannerConfig,
+        main_window: HWND,
+        /// 当前按键序列的字符
+        buffer: String,
+        /// 当前按键序列是否被暂存（未输入到前台窗口）
+        holding: bool,
+        /// 上一个按键的时间（毫秒）
+        last_key: u32,
+        shift: bool,
+        /// 按住 Ctrl / Alt / Win 时的按键是快捷键，不是扫码
+        command: bool,
+        /// 按下时已拦截、松开时也要拦截的按键
+        swallow_up: HashSet<u32>,
+        timer: usize,
+        scans: Vec<ScanEvent>,
+    }
+
+    fn keyboard_input(vk: u16, scan: u16, flags: u32) -> INPUT {
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    wScan: scan,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        }
+    }
+
+    impl Detector {
+        /// 按键对应的字符（只认可打印字符）
+        fn to_char(&self, kb: &KBDLLHOOKSTRUCT) -> Option<String> {
+            let mut state = [0u8; 256];
+            if self.shift {
+                state[VK_SHIFT as usize] = 0x80;
+            }
+            state[VK_CAPITAL as usize] = (unsafe { GetKeyState(VK_CAPITAL as i32) } & 1) as u8;
+            let mut buffer = [0u16; 8];
+            // 标志 4：不改变键盘状态（不影响前台窗口的死键输入）
+            let len = unsafe {
+                ToUnicode(
+                    kb.vkCode,
+                    kb.scanCode,
+                    state.as_ptr(),
+                    buffer.as_mut_ptr(),
+                    buffer.len() as i32,
+                    4,
+                )
+            };
+            if len <= 0 {
+                return None;
+            }
+            let text = String::from_utf16(&buffer[..len as usize]).ok()?;
+            (!text.chars().any(char::is_control)).then_some(text)
+        }
+
+        fn main_window_foreground(&self) -> bool {
+            unsafe { GetForegroundWindow() == self.main_window }
+        }
+
+        fn restart_timer(&mut self) {
+            let interval = self.settings.max_interval_ms.max(1) + 5;
+            self.timer = unsafe { SetTimer(0, self.timer, interval, None) };
+        }
+
+        fn stop_timer(&mut self) {
+            if self.timer != 0 {
+                unsafe { KillTimer(0, self.timer) };
+                self.timer = 0;
+            }
+        }
+
+        fn complete(&mut self) {
+            self.stop_timer();
+            self.scans.push(ScanEvent {
+                code: std::mem::take(&mut self.buffer),
+                suppressed: self.holding,
+                foreground: !self.holding && self.main_window_foreground(),
+                timestamp: chrono::Local::now().to_rfc3339(),
+            });
+            self.holding = false;
+            unsafe { PostThreadMessageW(GetCurrentThreadId(), WM_SCAN, 0, 0) };
+        }
+
+        /// 不是扫码：把暂存的字符（和当前按键）按原顺序重新发送，返回当前按键是否需要拦截
+        fn release(&mut self, current: Option<&KBDLLHOOKSTRUCT>) -> bool {
+            self.stop_timer();
+            let text = std::mem::take(&mut self.buffer);
+            if !std::mem::take(&mut self.holding) {
+                return false;
+            }
+            let mut inputs = Vec::new();
+            for unit in text.encode_utf16() {
+                inputs.push(keyboard_input(0, unit, KEYEVENTF_UNICODE));
+                inputs.push(keyboard_input(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+            }
+            // 当前按键排在暂存的字符之后，否则顺序会颠倒
+            if let Some(kb) = current {
+                let flags = if kb.flags & LLKHF_EXTENDED != 0 {
+                    KEYEVENTF_EXTENDEDKEY
+                } else {
+                    0
+                };
+                inputs.push(keyboard_input(kb.vkCode as u16, kb.scanCode as u16, flags));
+            }
+            unsafe {
+                SendInput(
+                    inputs.len() as u32,
+                    inputs.as_ptr(),
+                    std::mem::size_of::<INPUT>() as i32,
+                )
+            };
+            current.is_some()
+        }
+
+        /// 按键间隔超时：不要求回车时，足够长的序列即为扫码
+        fn on_timer(&mut self) {
+            if self.buffer.is_empty() {
+                self.stop_timer();
+            } else if !self.settings.require_enter
+                && self.buffer.chars().count() >= self.settings.min_length
+            {
+                self.complete();
+            } else {
+                self.release(None);
+            }
+        }
+
+        /// 处理一个按键，返回是否拦截
+        fn on_key(&mut self, kb: &KBDLLHOOKSTRUCT, down: bool) -> bool {
+            let vk = kb.vkCode as u16;
+            if matches!(vk, VK_SHIFT | VK_LSHIFT | VK_RSHIFT) {
+                self.shift = down;
+                return false;
+            }
+            if matches!(
+                vk,
+                VK_CONTROL
+                    | VK_LCONTROL
+                    | VK_RCONTROL
+                    | VK_MENU
+                    | VK_LMENU
+                    | VK_RMENU
+                    | VK_LWIN
+                    | VK_RWIN
+            ) {
+                self.command = down;
+                if down && !self.buffer.is_empty() {
+                    return self.release(Some(kb));
+                }
+                return false;
+            }
+            if !down {
+                return self.swallow_up.remove(&kb.vkCode);
+            }
+
+            let in_time = !self.buffer.is_empty()
+                && kb.time.wrapping_sub(self.last_key) <= self.settings.max_interval_ms;
+            if !self.buffer.is_empty() && !in_time {
+                // 超时的序列一般已由定时器处理
+                if self.release(Some(kb)) {
+                    return true;
+                }
+            }
+
+            if vk == VK_RETURN && in_time {
+                if self.buffer.chars().count() >= self.settings.min_length {
+                    let holding = self.holding;
+                    self.complete();
+                    if holding {
+                        self.swallow_up.insert(kb.vkCode);
+                    }
+                    return holding;
+                }
+                return self.release(Some(kb));
+            }
+
+            let text = if self.command { None } else { self.to_char(kb) };
+            let Some(text) = text else {
+                return !self.buffer.is_empty() && self.release(Some(kb));
+            };
+            if self.buffer.is_empty() {
+                self.holding = self.settings.suppress && !self.main_window_foreground();
+            }
+            self.buffer.push_str(&text);
+            self.last_key = kb.time;
+            self.restart_timer();
+            if self.holding {
+                self.swallow_up.insert(kb.vkCode);
+            }
+            self.holding
+        }
+    }
+
+    unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 {
+            let kb = &*(lparam as *const KBDLLHOOKSTRUCT);
+            // 重新发送的按键和其他程序模拟的输入都不处理
+            if kb.flags & LLKHF_INJECTED == 0 {
+                let down = wparam as u32 == WM_KEYDOWN || wparam as u32 == WM_SYSKEYDOWN;
+                let suppress = DETECTOR.with(|detector| {
+                    detector
+                        .borrow_mut()
+                        .as_mut()
+                        .is_some_and(|d| d.on_key(kb, down))
+                });
+                if suppress {
+                    return 1;
+                }
+            }
+        }
+        CallNextHookEx(0, code, wparam, lparam)
+    }
+
+    /// 安装键盘钩子并运行消息循环，直到收到 WM_QUIT
+    pub fn run(
+        app_handle: &tauri::AppHandle,
+        settings: ScannerConfig,
+        main_window: HWND,
+    ) -> Result<(), String> {
+        let hook = unsafe {
+            SetWindowsHookExW(
+                WH_KEYBOARD_LL,
+                Some(hook_proc),
+                GetModuleHandleW(std::ptr::null()),
+                0,
+            )
+        };
+        if hook == 0 {
+            return Err(format!(
+                "安装键盘钩子失败: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        DETECTOR.with(|detector| {
+            *detector.borrow_mut() = Some(Detector {
+                settings,
+                main_window,
+                buffer: String::new(),
+                holding: false,
+                last_key: 0,
+                shift: false,
+                command: false,
+                swallow_up: HashSet::new(),
+                timer: 0,
+                scans: Vec::new(),
+            })
+        });
+        STATE.lock().unwrap().thread_id = Some(unsafe { GetCurrentThreadId() });
+        tracing::info!("扫码枪全局识别已开启");
+
+        let mut msg: MSG = unsafe { std::mem::zeroed() };
+        while unsafe { GetMessageW(&mut msg, 0, 0, 0) } > 0 {
+            match msg.message {
+                WM_TIMER => DETECTOR.with(|detector| {
+                    if let Some(detector) = detector.borrow_mut().as_mut() {
+                        detector.on_timer();
+                    }
+                }),
+                WM_SCAN => {
+                    let scans = DETECTOR.with(|detector| {
+                        detector
+                            .borrow_mut()
+                            .as_mut()
+                            .map(|d| std::mem::take(&mut d.scans))
+                            .unwrap_or_default()
+                    });
+                    for scan in scans {
+                        on_scan(app_handle, scan);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        DETECTOR.with(|detector| {
+            if let Some(mut detector) = detector.borrow_mut().take() {
+                detector.release(None);
+            }
+        });
+        unsafe { UnhookWindowsHookEx(hook) };
+        tracing::info!("扫码枪全局识别已关闭");
+        Ok(())
+    }
+
+    /// 通知钩子线程退出
+    pub fn quit(thread_id: u32) {
+        unsafe { PostThreadMessageW(thread_id, WM_QUIT, 0, 0) };
+    }
+}
+
+/// 启动键盘钩子线程
+#[cfg(target_os = "windows")]
+fn spawn(app_handle: &tauri::AppHandle, settings: ScannerConfig) -> Result<(), String> {
+    let window = app_handle
+        .get_window("main")
+        .ok_or_else(|| "未找到主窗口".to_string())?;
+    let main_window = window
+        .hwnd()
+        .map_err(|e| format!("获取窗口句柄失败: {}", e))?
+        .0;
+
+    STATE.lock().unwrap().error = None;
+    let app_handle = app_handle.clone();
+    std::thread::Builder::new()
+        .name("scanner-hook".into())
+        .spawn(move || {
+            let result = windows::run(&app_handle, settings, main_window);
+            let mut state = STATE.lock().unwrap();
+            state.thread_id = None;
+            if let Err(e) = result {
+                tracing::error!("{}", e);
+                state.error = Some(e);
+            }
+        })
+        .map(|_| ())
+        .map_err(|e| format!("启动扫码枪识别线程失败: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn spawn(_app_handle: &tauri::AppHandle, _settings: ScannerConfig) -> Result<(), String> {
+    Err("扫码枪全局识别仅支持 Windows".to_string())
+}
+
+fn stop() {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(thread_id) = STATE.lock().unwrap().thread_id {
+            windows::quit(thread_id);
+        }
+    }
+}
+
+/// 按设置启动扫码枪全局识别
+pub fn start(app_handle: &tauri::AppHandle) {
+    let settings = app_handle.state::<ConfigStore>().get().scanner;
+    if !settings.enabled {
+        return;
+    }
+    if let Err(e) = spawn(app_handle, settings) {
+        tracing::warn!("{}", e);
+        STATE.lock().unwrap().error = Some(e);
+    }
+}
+
+// Tauri 命令
+
+/// 查看扫码枪全局识别是否在运行以及最近一次扫码
+#[tauri::command]
+pub fn get_scanner_status(config: tauri::State<'_, ConfigStore>) -> ScannerStatus {
+    let state = STATE.lock().unwrap();
+    ScannerStatus {
+        supported: cfg!(target_os = "windows"),
+        enabled: config.get().scanner.enabled,
+        running: state.thread_id.is_some(),
+        scans: state.scans,
+        last_scan: state.last_scan.clone(),
+        error: state.error.clone(),
+    }
+}
+
+/// 开启或关闭扫码枪全局识别
+#[tauri::command]
+pub async fn set_scanner_hook(enabled: bool, app_handle: tauri::AppHandle) -> AppResult<()> {
+    if enabled && !cfg!(target_os = "windows") {
+        return Err(AppError::InvalidArgument(
+            "扫码枪全局识别仅支持 Windows".to_string(),
+        ));
+    }
+    let config = app_handle.state::<ConfigStore>();
+    let settings = config.update(|c| c.scanner.enabled = enabled)?.scanner;
+
+    stop();
+    if enabled {
+        // 等待上一个钩子线程退出
+        for _ in 0..20 {
+            if STATE.lock().unwrap().thread_id.is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        spawn(&app_handle, settings).map_err(AppError::Internal)?;
+    }
+    events::record(
+        EventKind::State,
+        if enabled {
+            "开启扫码枪全局识别"
+        } else {
+            "关闭扫码枪全局识别"
+        },
+        serde_json::Value::Null,
+    );
+    Ok(())
+}
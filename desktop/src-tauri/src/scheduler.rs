@@ -0,0 +1,342 @@
+// 定时任务
+//
+// 各模块在启动时通过 register 登记任务（备份、重启 Backend、打印报表、同步等），
+// 触发时间使用 cron 表达式：登记时给出默认值，设置中的 scheduler.tasks 可按任务名覆盖，
+// 设为空字符串表示停用该任务。
+//
+// 调度线程每分钟检查一次，到点的任务在独立线程中执行，同一任务不会重叠运行。
+// 每个任务最近一次的执行结果保存在 <应用数据目录>/scheduler.json，重启后仍可查看。
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::backend::BackendProcess;
+use crate::config::{self, ConfigStore};
+use crate::cron::Schedule;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::metrics;
+
+const RESULTS_FILE: &str = "scheduler.json";
+
+/// 任务的执行函数，成功时返回结果说明
+pub type TaskFn = fn(&tauri::AppHandle) -> Result<String, String>;
+
+struct Task {
+    name: &'static str,
+    description: &'static str,
+    default_cron: Option<&'static str>,
+    run: TaskFn,
+    running: bool,
+    /// 上次由调度线程触发的时间（精确到分钟），避免同一分钟重复触发
+    last_fired: Option<String>,
+}
+
+static TASKS: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+
+/// 各任务最近一次的执行结果
+static RESULTS: Mutex<BTreeMap<String, TaskResult>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub ok: bool,
+    pub message: String,
+    /// 是否为手动执行
+    pub manual: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub name: String,
+    pub description: String,
+    /// 生效的 cron 表达式，为空表示未启用
+    pub cron: Option<String>,
+    pub next_run: Option<String>,
+    pub running: bool,
+    pub last_result: Option<TaskResult>,
+}
+
+/// 登记定时任务（需在 start 之前调用）
+pub fn register(
+    name: &'static str,
+    description: &'static str,
+    default_cron: Option<&'static str>,
+    run: TaskFn,
+) {
+    TASKS.lock().unwrap().push(Task {
+        name,
+        description,
+        default_cron,
+        run,
+        running: false,
+        last_fired: None,
+    });
+}
+
+fn results_path(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join(RESULTS_FILE)
+}
+
+fn load_results(app_handle: &tauri::AppHandle) {
+    let Ok(content) = std::fs::read_to_string(results_path(app_handle)) else {
+        return;
+    };
+    match serde_json::from_str(&content) {
+        Ok(results) => *RESULTS.lock().unwrap() = results,
+        Err(e) => tracing::warn!("读取定时任务结果失败: {}", e),
+    }
+}
+
+fn save_results(app_handle: &tauri::AppHandle) {
+    let path = results_path(app_handle);
+    let content = match serde_json::to_string_pretty(&*RESULTS.lock().unwrap()) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::warn!("序列化定时任务结果失败: {}", e);
+            return;
+        }
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = std::fs::write(&path, content) {
+        tracing::warn!("保存定时任务结果失败: {:?} ({})", path, e);
+    }
+}
+
+/// 任务生效的 cron 表达式（设置优先，空字符串表示停用）
+fn effective_cron(app_handle: &tauri::AppHandle, task: &Task) -> Option<String> {
+    let config = app_handle.state::<ConfigStore>().get().scheduler;
+    match config.tasks.get(task.name) {
+        Some(cron) if cron.trim().is_empty() => None,
+        Some(cron) => Some(cron.clone()),
+        None => task.default_cron.map(str::to_string),
+    }
+}
+
+/// 执行任务并记录结果（任务正在运行时返回错误）
+pub fn execute(app_handle: &tauri::AppHandle, name: &str, manual: bool) -> AppResult<TaskResult> {
+    let run = {
+        let mut tasks = TASKS.lock().unwrap();
+        let task = tasks
+            .iter_mut()
+            .find(|task| task.name == name)
+            .ok_or_else(|| AppError::InvalidArgument(format!("未知的定时任务: {}", name)))?;
+        if task.running {
+            return Err(AppError::InvalidArgument(format!("任务正在运行: {}", name)));
+        }
+        task.running = true;
+        task.run
+    };
+
+    tracing::info!("执行定时任务: {}", name);
+    let started_at = chrono::Local::now().to_rfc3339();
+    let started = Instant::now();
+    let (ok, message) = match run(app_handle) {
+        Ok(message) => (true, message),
+        Err(message) => (false, message),
+    };
+
+    let result = TaskResult {
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        ok,
+        message,
+        manual,
+    };
+
+    if let Some(task) = TASKS
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .find(|task| task.name == name)
+    {
+        task.running = false;
+    }
+
+    if result.ok {
+        tracing::info!("定时任务完成: {} ({} ms)", name, result.duration_ms);
+    } else {
+        tracing::warn!("定时任务失败: {} ({})", name, result.message);
+    }
+    events::record(
+        EventKind::State,
+        format!(
+            "定时任务{}: {}",
+            if result.ok { "完成" } else { "失败" },
+            name
+        ),
+        serde_json::json!(result),
+    );
+    RESULTS
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), result.clone());
+    save_results(app_handle);
+    let _ = app_handle.emit_all(
+        "scheduler://finished",
+        serde_json::json!({ "name": name, "result": result }),
+    );
+    Ok(result)
+}
+
+/// 检查当前分钟需要触发的任务
+fn tick(app_handle: &tauri::AppHandle) {
+    let now = chrono::Local::now();
+    let minute = now.format("%Y-%m-%dT%H:%M").to_string();
+
+    let mut due = Vec::new();
+    for task in TASKS.lock().unwrap().iter_mut() {
+        if task.running || task.last_fired.as_deref() == Some(minute.as_str()) {
+            continue;
+        }
+        let Some(cron) = effective_cron(app_handle, task) else {
+            continue;
+        };
+        match Schedule::parse(&cron) {
+            Ok(schedule) if schedule.matches(&now) => {
+                task.last_fired = Some(minute.clone());
+                due.push(task.name);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::debug!("定时任务 {} 的 cron 表达式无效: {}", task.name, e),
+        }
+    }
+
+    for name in due {
+        let app_handle = app_handle.clone();
+        let spawned = std::thread::Builder::new()
+            .name(format!("task-{}", name))
+            .spawn(move || {
+                let _ = execute(&app_handle, name, false);
+            });
+        if let Err(e) = spawned {
+            tracing::error!("启动定时任务线程失败: {}", e);
+        }
+    }
+}
+
+/// 在长时间运行后重启 Backend，释放内存（默认不启用）
+fn restart_backend(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let state = app_handle.state::<Mutex<BackendProcess>>();
+    let pending = {
+        let mut process = state.lock().unwrap();
+        if process.pid().is_none() {
+            return Err("Backend 不是由壳程序启动的，跳过重启".to_string());
+        }
+        let port = process.port();
+        process.begin_restart(port, Some(Duration::from_secs(10)))
+    };
+    let pending = pending.ok_or_else(|| "Backend 正在重启，跳过".to_string())?;
+    metrics::increment(metrics::BACKEND_RESTARTS);
+    pending.run(&state).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "Backend 已重启, 端口: {}",
+        state.lock().unwrap().port()
+    ))
+}
+
+/// 登记内置任务并启动调度线程
+pub fn start(app_handle: tauri::AppHandle) {
+    register("backend_restart", "重启 Backend", None, restart_backend);
+    load_results(&app_handle);
+
+    let spawned = std::thread::Builder::new()
+        .name("scheduler".into())
+        .spawn(move || loop {
+            if app_handle.state::<ConfigStore>().get().scheduler.enabled {
+                tick(&app_handle);
+            }
+            // 每分钟检查一次，对齐到整分钟后的第 1 秒
+            let second = chrono::Local::now().second() as u64;
+            std::thread::sleep(Duration::from_secs(61 - second.min(60)));
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动定时任务线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 列出所有定时任务，包括下次执行时间和最近一次结果
+#[tauri::command]
+pub fn list_scheduled_tasks(app_handle: tauri::AppHandle) -> Vec<TaskInfo> {
+    let results = RESULTS.lock().unwrap().clone();
+    let now = chrono::Local::now();
+    TASKS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|task| {
+            let cron = effective_cron(&app_handle, task);
+            let next_run = cron
+                .as_deref()
+                .and_then(|cron| Schedule::parse(cron).ok())
+                .and_then(|schedule| schedule.next_after(now))
+                .map(|time| time.to_rfc3339());
+            TaskInfo {
+                name: task.name.to_string(),
+                description: task.description.to_string(),
+                cron,
+                next_run,
+                running: task.running,
+                last_result: results.get(task.name).cloned(),
+            }
+        })
+        .collect()
+}
+
+/// 立即执行任务，返回执行结果
+#[tauri::command]
+pub async fn run_scheduled_task(
+    name: String,
+    app_handle: tauri::AppHandle,
+) -> AppResult<TaskResult> {
+    execute(&app_handle, &name, true)
+}
+
+/// 查看任务最近一次的执行结果
+#[tauri::command]
+pub fn get_task_result(name: String) -> Option<TaskResult> {
+    RESULTS.lock().unwrap().get(&name).cloned()
+}
+
+/// 修改任务的 cron 表达式，cron 为空字符串时停用，为 null 时恢复默认
+#[tauri::command]
+pub fn set_task_schedule(
+    name: String,
+    cron: Option<String>,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<()> {
+    if !TASKS.lock().unwrap().iter().any(|task| task.name == name) {
+        return Err(AppError::InvalidArgument(format!(
+            "未知的定时任务: {}",
+            name
+        )));
+    }
+    if let Some(cron) = cron.as_deref().filter(|cron| !cron.trim().is_empty()) {
+        Schedule::parse(cron).map_err(AppError::InvalidArgument)?;
+    }
+
+    config.update(|config| match cron {
+        Some(cron) => {
+            config
+                .scheduler
+                .tasks
+                .insert(name.clone(), cron.trim().to_string());
+        }
+        None => {
+            config.scheduler.tasks.remove(&name);
+        }
+    })?;
+    tracing::info!("定时任务 {} 的执行时间已修改", name);
+    Ok(())
+}
@@ -0,0 +1,216 @@
+// 屏幕截图
+//
+// 截取全部显示器的画面并编码为 PNG，用于远程协助和技术支持。
+//
+// 提交问题时也可以只截取主窗口或主窗口所在的显示器，保存到技术支持目录，附加到工单中。
+// 设置中 support.blur_regions 登记的区域（以及页面传入的区域）会打上马赛克，
+// 避免会员手机号、支付信息等出现在截图中。
+
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::config::{BlurRegion, ConfigStore};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::support;
+
+/// 马赛克方块边长（物理像素）
+const MOSAIC_BLOCK: u32 = 16;
+
+pub struct Screenshot {
+    /// 显示器名称
+    pub monitor: String,
+    pub width: u32,
+    pub height: u32,
+    pub png: Vec<u8>,
+}
+
+/// 截图范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureTarget {
+    /// 主窗口的内容区域
+    Window,
+    /// 主窗口所在的整个显示器
+    Screen,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedScreenshot {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// 打了马赛克的区域数
+    pub blurred: usize,
+}
+
+/// 截取全部显示器
+pub fn capture_all() -> Result<Vec<Screenshot>, String> {
+    let monitors = xcap::Monitor::all().map_err(|e| format!("获取显示器失败: {}", e))?;
+
+    let mut screenshots = Vec::new();
+    for monitor in monitors {
+        let image = monitor
+            .capture_image()
+            .map_err(|e| format!("截图失败: {}", e))?;
+
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .map_err(|e| format!("编码截图失败: {}", e))?;
+
+        screenshots.push(Screenshot {
+            monitor: monitor.name().to_string(),
+            width: monitor.width(),
+            height: monitor.height(),
+            png,
+        });
+    }
+    Ok(screenshots)
+}
+
+/// 对区域打马赛克（区域超出图片的部分忽略）
+fn pixelate(image: &mut image::RgbaImage, x: i64, y: i64, width: i64, height: i64) {
+    let left = x.max(0) as u32;
+    let top = y.max(0) as u32;
+    let right = ((x + width).max(0) as u32).min(image.width());
+    let bottom = ((y + height).max(0) as u32).min(image.height());
+    if left >= right || top >= bottom {
+        return;
+    }
+
+    for block_y in (top..bottom).step_by(MOSAIC_BLOCK as usize) {
+        for block_x in (left..right).step_by(MOSAIC_BLOCK as usize) {
+            let block_right = (block_x + MOSAIC_BLOCK).min(right);
+            let block_bottom = (block_y + MOSAIC_BLOCK).min(bottom);
+            let mut sum = [0u64; 4];
+            let mut count = 0u64;
+            for py in block_y..block_bottom {
+                for px in block_x..block_right {
+                    let pixel = image.get_pixel(px, py);
+                    for (total, channel) in sum.iter_mut().zip(pixel.0) {
+                        *total += channel as u64;
+                    }
+                    count += 1;
+                }
+            }
+            let average = image::Rgba(sum.map(|total| (total / count) as u8));
+            for py in block_y..block_bottom {
+                for px in block_x..block_right {
+                    image.put_pixel(px, py, average);
+                }
+            }
+        }
+    }
+}
+
+/// 截取主窗口或其所在的显示器，并对指定区域打马赛克
+pub fn capture_window(
+    app_handle: &tauri::AppHandle,
+    target: CaptureTarget,
+    regions: &[BlurRegion],
+) -> Result<image::RgbaImage, String> {
+    let window = app_handle
+        .get_window("main")
+        .ok_or_else(|| "未找到主窗口".to_string())?;
+    let position = window
+        .inner_position()
+        .map_err(|e| format!("获取窗口位置失败: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("获取窗口大小失败: {}", e))?;
+    let scale = window.scale_factor().unwrap_or(1.0);
+
+    // 以窗口中心所在的显示器为准
+    let monitor = xcap::Monitor::from_point(
+        position.x + size.width as i32 / 2,
+        position.y + size.height as i32 / 2,
+    )
+    .ok()
+    .or_else(|| {
+        xcap::Monitor::all()
+            .ok()?
+            .into_iter()
+            .find(|m| m.is_primary())
+    })
+    .ok_or_else(|| "获取显示器失败".to_string())?;
+    let mut image = monitor
+        .capture_image()
+        .map_err(|e| format!("截图失败: {}", e))?;
+
+    // 窗口内容区域在截图中的位置
+    let window_x = (position.x - monitor.x()) as i64;
+    let window_y = (position.y - monitor.y()) as i64;
+    for region in regions {
+        pixelate(
+            &mut image,
+            window_x + (region.x * scale).floor() as i64,
+            window_y + (region.y * scale).floor() as i64,
+            (region.width * scale).ceil() as i64,
+            (region.height * scale).ceil() as i64,
+        );
+    }
+
+    if target == CaptureTarget::Window {
+        let left = window_x.clamp(0, image.width() as i64) as u32;
+        let top = window_y.clamp(0, image.height() as i64) as u32;
+        let right = (window_x + size.width as i64).clamp(0, image.width() as i64) as u32;
+        let bottom = (window_y + size.height as i64).clamp(0, image.height() as i64) as u32;
+        if left >= right || top >= bottom {
+            return Err("主窗口不在屏幕范围内".to_string());
+        }
+        image = image::imageops::crop_imm(&image, left, top, right - left, bottom - top).to_image();
+    }
+    Ok(image)
+}
+
+/// 截图并保存为 PNG
+fn capture_to_file(
+    app_handle: &tauri::AppHandle,
+    target: CaptureTarget,
+    regions: &[BlurRegion],
+) -> Result<SavedScreenshot, String> {
+    let image = capture_window(app_handle, target, regions)?;
+    let dir = support::support_dir(app_handle);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    let path: PathBuf = dir.join(format!(
+        "screenshot-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    image
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| format!("保存截图失败: {}", e))?;
+
+    Ok(SavedScreenshot {
+        path: path.to_string_lossy().into_owned(),
+        width: image.width(),
+        height: image.height(),
+        blurred: regions.len(),
+    })
+}
+
+// Tauri 命令
+
+/// 截取主窗口（window）或其所在的显示器（screen），保存到技术支持目录
+///
+/// blur_regions 为页面额外指定的马赛克区域，与设置中登记的区域合并。
+#[tauri::command]
+pub async fn capture_screenshot(
+    target: CaptureTarget,
+    blur_regions: Option<Vec<BlurRegion>>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<SavedScreenshot> {
+    let mut regions = app_handle.state::<ConfigStore>().get().support.blur_regions;
+    regions.extend(blur_regions.unwrap_or_default());
+
+    let saved = capture_to_file(&app_handle, target, &regions).map_err(AppError::Internal)?;
+    tracing::info!("已保存截图: {}", saved.path);
+    events::record(
+        EventKind::State,
+        "保存截图",
+        serde_json::json!({ "path": saved.path, "blurred": saved.blurred }),
+    );
+    Ok(saved)
+}
@@ -0,0 +1,261 @@
+// 全局快捷键
+//
+// 快捷键绑定到预定义的动作上，按收银机分别保存在设置的 shortcuts.bindings 中（动作名 → 组合键）。
+// 触发时发出 shortcut://triggered 事件，由前端执行对应动作；show_window 由壳程序直接处理。
+//
+// 注册前检查冲突：
+// - 与其他动作的绑定重复
+// - 系统保留的组合键（Alt+F4、Ctrl+Alt+Delete 等）
+// - 收银页面依赖的按键（扫码枪输入的字母数字、Enter、Esc），必须带修饰键（功能键除外）
+// - 已被其他程序占用（系统注册失败）
+//
+// 注意：Tauri 1 中注册快捷键需要主线程事件循环处理，命令必须是 async，启动时在后台线程注册。
+
+use serde::Serialize;
+use tauri::{GlobalShortcutManager, Manager};
+
+use crate::config::ConfigStore;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::tray;
+
+/// 可绑定的动作（动作名，说明）
+const ACTIONS: [(&str, &str); 4] = [
+    ("show_window", "显示收银窗口"),
+    ("checkout", "结账"),
+    ("focus_search", "输入条码 / 搜索商品"),
+    ("manual_add", "手动添加商品"),
+];
+
+/// 系统保留的组合键
+const RESERVED: [&str; 8] = [
+    "Alt+F4",
+    "Alt+Tab",
+    "Alt+Escape",
+    "Ctrl+Alt+Delete",
+    "Ctrl+Escape",
+    "Ctrl+Shift+Escape",
+    "Super+L",
+    "Super+D",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutBinding {
+    pub action: String,
+    pub description: String,
+    pub accelerator: Option<String>,
+    /// 是否已成功注册到系统
+    pub registered: bool,
+}
+
+/// 统一组合键写法，例如 "control+shift+p" → "Ctrl+Shift+P"
+fn normalize(accelerator: &str) -> Result<String, String> {
+    let mut modifiers = Vec::new();
+    let mut key = None;
+
+    for part in accelerator.split('+').map(str::trim) {
+        let modifier = match part.to_lowercase().as_str() {
+            "ctrl" | "control" | "cmdorctrl" | "commandorcontrol" => Some("Ctrl"),
+            "alt" | "option" => Some("Alt"),
+            "shift" => Some("Shift"),
+            "super" | "cmd" | "command" | "meta" | "win" => Some("Super"),
+            _ => None,
+        };
+        match modifier {
+            Some(modifier) if !modifiers.contains(&modifier) => modifiers.push(modifier),
+            Some(_) => return Err(format!("修饰键重复: {}", accelerator)),
+            None if part.is_empty() => return Err(format!("无效的快捷键: {}", accelerator)),
+            None if key.is_some() => {
+                return Err(format!("快捷键只能包含一个按键: {}", accelerator))
+            }
+            None => key = Some(part.to_uppercase()),
+        }
+    }
+
+    let key = key.ok_or_else(|| format!("快捷键缺少按键: {}", accelerator))?;
+    let order = ["Ctrl", "Alt", "Shift", "Super"];
+    modifiers.sort_by_key(|m| order.iter().position(|o| o == m));
+
+    let is_function_key = key.len() > 1
+        && key.starts_with('F')
+        && key[1..].parse::<u8>().is_ok_and(|n| (1..=24).contains(&n));
+    if modifiers.is_empty() && !is_function_key {
+        return Err(format!(
+            "快捷键必须包含 Ctrl / Alt / Shift 等修饰键: {}",
+            accelerator
+        ));
+    }
+    if modifiers == ["Shift"] && key.len() == 1 {
+        return Err(format!("Shift + 字母数字会影响扫码输入: {}", accelerator));
+    }
+
+    let mut parts: Vec<String> = modifiers.iter().map(|m| m.to_string()).collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+fn description(action: &str) -> Option<&'static str> {
+    ACTIONS
+        .iter()
+        .find(|(name, _)| *name == action)
+        .map(|(_, description)| *description)
+}
+
+/// 注册到系统
+fn register(app_handle: &tauri::AppHandle, action: &str, accelerator: &str) -> AppResult<()> {
+    let mut manager = app_handle.global_shortcut_manager();
+    if manager.is_registered(accelerator).unwrap_or(false) {
+        return Err(AppError::InvalidArgument(format!(
+            "快捷键已被本程序使用: {}",
+            accelerator
+        )));
+    }
+
+    let handle = app_handle.clone();
+    let name = action.to_string();
+    manager
+        .register(accelerator, move || trigger(&handle, &name))
+        .map_err(|e| {
+            AppError::InvalidArgument(format!(
+                "快捷键可能已被其他程序占用: {} ({})",
+                accelerator, e
+            ))
+        })
+}
+
+fn trigger(app_handle: &tauri::AppHandle, action: &str) {
+    tracing::debug!("快捷键触发: {}", action);
+    if action == "show_window" {
+        tray::show_main_window(app_handle);
+    }
+    let _ = app_handle.emit_all(
+        "shortcut://triggered",
+        serde_json::json!({ "action": action }),
+    );
+}
+
+/// 启动时注册已保存的快捷键
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("shortcuts".into())
+        .spawn(move || {
+            let bindings = app_handle.state::<ConfigStore>().get().shortcuts.bindings;
+            for (action, accelerator) in bindings {
+                if description(&action).is_none() {
+                    tracing::warn!("忽略未知的快捷键动作: {}", action);
+                    continue;
+                }
+                if let Err(e) = register(&app_handle, &action, &accelerator) {
+                    tracing::warn!("注册快捷键失败: {}={} ({})", action, accelerator, e);
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动快捷键注册线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 列出所有可绑定的动作及当前快捷键
+#[tauri::command]
+pub async fn list_shortcuts(app_handle: tauri::AppHandle) -> Vec<ShortcutBinding> {
+    let bindings = app_handle.state::<ConfigStore>().get().shortcuts.bindings;
+    let manager = app_handle.global_shortcut_manager();
+    ACTIONS
+        .iter()
+        .map(|(action, description)| {
+            let accelerator = bindings.get(*action).cloned();
+            let registered = accelerator
+                .as_deref()
+                .is_some_and(|a| manager.is_registered(a).unwrap_or(false));
+            ShortcutBinding {
+                action: action.to_string(),
+                description: description.to_string(),
+                accelerator,
+                registered,
+            }
+        })
+        .collect()
+}
+
+/// 为动作设置快捷键（替换原有绑定），返回统一写法后的组合键
+#[tauri::command]
+pub async fn register_shortcut(
+    action: String,
+    accelerator: String,
+    app_handle: tauri::AppHandle,
+) -> AppResult<String> {
+    if description(&action).is_none() {
+        return Err(AppError::InvalidArgument(format!("未知的动作: {}", action)));
+    }
+    let accelerator = normalize(&accelerator).map_err(AppError::InvalidArgument)?;
+    if RESERVED
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(&accelerator))
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "系统保留的快捷键: {}",
+            accelerator
+        )));
+    }
+
+    let store = app_handle.state::<ConfigStore>();
+    let bindings = store.get().shortcuts.bindings;
+    if let Some((other, _)) = bindings
+        .iter()
+        .find(|(other, a)| **other != action && a.eq_ignore_ascii_case(&accelerator))
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "快捷键 {} 已用于“{}”",
+            accelerator,
+            description(other).unwrap_or(other)
+        )));
+    }
+
+    let previous = bindings.get(&action).cloned();
+    let mut manager = app_handle.global_shortcut_manager();
+    if let Some(previous) = previous.as_deref() {
+        let _ = manager.unregister(previous);
+    }
+    if let Err(e) = register(&app_handle, &action, &accelerator) {
+        // 恢复原来的绑定
+        if let Some(previous) = previous.as_deref() {
+            let _ = register(&app_handle, &action, previous);
+        }
+        return Err(e);
+    }
+
+    store.update(|config| {
+        config
+            .shortcuts
+            .bindings
+            .insert(action.clone(), accelerator.clone());
+    })?;
+    tracing::info!("快捷键已设置: {}={}", action, accelerator);
+    events::record(
+        EventKind::State,
+        format!("快捷键已设置: {}", action),
+        serde_json::json!({ "accelerator": accelerator }),
+    );
+    Ok(accelerator)
+}
+
+/// 取消动作的快捷键
+#[tauri::command]
+pub async fn unregister_shortcut(action: String, app_handle: tauri::AppHandle) -> AppResult<()> {
+    let store = app_handle.state::<ConfigStore>();
+    let Some(accelerator) = store.get().shortcuts.bindings.get(&action).cloned() else {
+        return Ok(());
+    };
+
+    let _ = app_handle
+        .global_shortcut_manager()
+        .unregister(&accelerator);
+    store.update(|config| {
+        config.shortcuts.bindings.remove(&action);
+    })?;
+    tracing::info!("快捷键已取消: {}={}", action, accelerator);
+    Ok(())
+}
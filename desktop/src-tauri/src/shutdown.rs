@@ -0,0 +1,178 @@
+// 关机保护
+//
+// 门店常在营业结束后直接关机，此时 Backend 可能正在写入数据或备份尚未完成。
+// 退出前（包括系统关机 / 注销）依次：
+// 1. 等待进行中的任务（备份等，通过 busy() 登记）完成
+// 2. 请求 Backend 正常退出（HTTP 接口，失败时发送 SIGTERM / CTRL_BREAK），超时后才强制结束，
+//    避免中断正在写入的事务
+//
+// 应用退出（托盘菜单“退出”、主窗口关闭）和系统关机 / 注销都会执行这一流程。
+//
+// Windows 下收到关机通知后通过 ShutdownBlockReasonCreate 向系统说明原因，
+// 在关机界面显示“正在保存数据”，处理完成后再允许关机。
+// 其他平台在应用正常退出时执行同样的流程。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Manager, WindowEvent};
+
+use crate::backend::BackendProcess;
+use crate::events::{self, EventKind};
+
+/// 等待进行中任务的最长时间
+const TASK_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// 等待 Backend 正常退出的最长时间
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 进行中的任务
+static BUSY: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// 是否已执行过退出流程
+static DONE: Mutex<bool> = Mutex::new(false);
+
+/// 进行中的任务，释放时自动注销
+pub struct BusyGuard(&'static str);
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        let mut busy = BUSY.lock().unwrap();
+        if let Some(index) = busy.iter().position(|task| *task == self.0) {
+            busy.remove(index);
+        }
+    }
+}
+
+/// 登记一个退出前必须完成的任务（例如备份）
+pub fn busy(task: &'static str) -> BusyGuard {
+    BUSY.lock().unwrap().push(task);
+    BusyGuard(task)
+}
+
+/// 等待进行中的任务完成，然后正常停止 Backend（只执行一次）
+pub fn prepare(app_handle: &tauri::AppHandle, reason: &str) {
+    let mut done = DONE.lock().unwrap();
+    if *done {
+        return;
+    }
+    *done = true;
+
+    tracing::info!("准备退出: {}", reason);
+    events::record(
+        EventKind::State,
+        "准备退出",
+        serde_json::json!({ "reason": reason }),
+    );
+
+    let started = Instant::now();
+    loop {
+        let pending = BUSY.lock().unwrap().clone();
+        if pending.is_empty() {
+            break;
+        }
+        if started.elapsed() >= TASK_TIMEOUT {
+            tracing::warn!("等待任务超时，仍在进行: {:?}", pending);
+            break;
+        }
+        tracing::info!("等待任务完成: {:?}", pending);
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    app_handle
+        .state::<Mutex<BackendProcess>>()
+        .lock()
+        .unwrap()
+        .shutdown(BACKEND_TIMEOUT);
+    tracing::info!("退出准备完成，耗时 {:?}", started.elapsed());
+}
+
+/// 主窗口被关闭（未隐藏到托盘）时，先停止 Backend 再退出应用
+pub fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
+    if matches!(event, WindowEvent::Destroyed) && window.label() == "main" {
+        let app_handle = window.app_handle();
+        prepare(&app_handle, "主窗口已关闭");
+        app_handle.exit(0);
+    }
+}
+
+/// 开始监听系统关机 / 注销
+pub fn start(app_handle: &tauri::AppHandle) {
+    #[cfg(target_os = "windows")]
+    windows::install(app_handle);
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = app_handle;
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::Shutdown::{
+        ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy,
+    };
+    use windows_sys::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{WM_ENDSESSION, WM_QUERYENDSESSION};
+
+    use tauri::Manager;
+
+    const SUBCLASS_ID: usize = 0x534d_5344; // "SMSD"
+
+    /// 关机界面显示的原因
+    const BLOCK_REASON: &str = "SmartMart 正在保存数据，请稍候…";
+
+    static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        _data: usize,
+    ) -> LRESULT {
+        match msg {
+            WM_QUERYENDSESSION => {
+                // 允许关机，但先登记原因，系统会等待 WM_ENDSESSION 处理完成
+                let reason: Vec<u16> = BLOCK_REASON.encode_utf16().chain(Some(0)).collect();
+                if ShutdownBlockReasonCreate(hwnd, reason.as_ptr()) == 0 {
+                    tracing::warn!("登记关机原因失败");
+                }
+                tracing::info!("收到系统关机通知");
+                return 1;
+            }
+            WM_ENDSESSION => {
+                if wparam != 0 {
+                    if let Some(app_handle) = APP_HANDLE.get() {
+                        super::prepare(app_handle, "系统关机或注销");
+                    }
+                }
+                ShutdownBlockReasonDestroy(hwnd);
+                return 0;
+            }
+            _ => {}
+        }
+        DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+
+    /// 为主窗口安装消息钩子
+    pub fn install(app_handle: &tauri::AppHandle) {
+        let Some(window) = app_handle.get_window("main") else {
+            tracing::warn!("未找到主窗口，无法监听关机事件");
+            return;
+        };
+        let hwnd = match window.hwnd() {
+            Ok(hwnd) => hwnd.0,
+            Err(e) => {
+                tracing::warn!("获取窗口句柄失败: {}", e);
+                return;
+            }
+        };
+        let _ = APP_HANDLE.set(app_handle.clone());
+
+        let installed = unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0) };
+        if installed == 0 {
+            tracing::warn!("安装窗口消息钩子失败，无法监听关机事件");
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// 语音播报
+//
+// 用系统自带的语音合成通过门店音箱播报取餐 / 叫号信息（例如“42 号订单已备好”），不需要额外硬件：
+// - Windows：System.Speech（SAPI），通过 PowerShell 调用
+// - macOS：say
+// - Linux：spd-say（speech-dispatcher）
+//
+// 播报按顺序排队进行，不会互相重叠；待播报的内容过多时拒绝新的播报。
+// 文本和语音名称通过环境变量或标准输入传给播报程序，不拼接到命令行中。
+
+use serde::Serialize;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::OnceLock;
+use tauri::Manager;
+
+use crate::config::{ConfigStore, SpeechConfig};
+use crate::error::{AppError, AppResult};
+use crate::os;
+
+/// 单条播报的最大字符数
+const MAX_TEXT_CHARS: usize = 500;
+
+/// 最多排队的播报数
+const MAX_QUEUED: usize = 10;
+
+static QUEUE: OnceLock<SyncSender<Utterance>> = OnceLock::new();
+
+struct Utterance {
+    text: String,
+    voice: Option<String>,
+    /// 语速（-10 到 10，0 为正常）
+    rate: i32,
+    /// 音量（0-100）
+    volume: u8,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Voice {
+    pub name: String,
+    /// 语言，例如 zh-CN
+    pub language: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+const SPEAK_SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+    $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+    if ($env:SMARTMART_TTS_VOICE) { $s.SelectVoice($env:SMARTMART_TTS_VOICE) }; \
+    $s.Rate = [int]$env:SMARTMART_TTS_RATE; \
+    $s.Volume = [int]$env:SMARTMART_TTS_VOLUME; \
+    $s.Speak($env:SMARTMART_TTS_TEXT)";
+
+#[cfg(target_os = "windows")]
+const VOICES_SCRIPT: &str = "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+    Add-Type -AssemblyName System.Speech; \
+    (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+    Where-Object { $_.Enabled } | \
+    ForEach-Object { $_.VoiceInfo.Name + \"`t\" + $_.VoiceInfo.Culture.Name }";
+
+#[cfg(target_os = "windows")]
+fn say(utterance: &Utterance) -> Result<(), String> {
+    let output = os::hidden_command("powershell")
+        .args(["-NoProfile", "-Command", SPEAK_SCRIPT])
+        .env("SMARTMART_TTS_TEXT", &utterance.text)
+        .env(
+            "SMARTMART_TTS_VOICE",
+            utterance.voice.as_deref().unwrap_or_default(),
+        )
+        .env("SMARTMART_TTS_RATE", utterance.rate.to_string())
+        .env("SMARTMART_TTS_VOLUME", utterance.volume.to_string())
+        .output()
+        .map_err(|e| format!("启动语音合成失败: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "语音合成失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn voices() -> Result<Vec<Voice>, String> {
+    let output = os::hidden_command("powershell")
+        .args(["-NoProfile", "-Command", VOICES_SCRIPT])
+        .output()
+        .map_err(|e| format!("读取语音列表失败: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, language) = line.trim().split_once('\t')?;
+            Some(Voice {
+                name: name.to_string(),
+                language: Some(language.to_string()).filter(|l| !l.is_empty()),
+            })
+        })
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+fn say(utterance: &Utterance) -> Result<(), String> {
+    use std::io::Write;
+
+    // 正常语速约为每分钟 175 词
+    let mut command = os::hidden_command("say");
+    command.args(["-r", &(175 + utterance.rate * 15).to_string()]);
+    if let Some(voice) = &utterance.voice {
+        command.args(["-v", voice]);
+    }
+    // 不带文本参数时 say 从标准输入读取
+    let mut child = command
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("启动语音合成失败: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(utterance.text.as_bytes());
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("语音合成失败: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "语音合成失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn voices() -> Result<Vec<Voice>, String> {
+    // 例如：Ting-Ting           zh_CN    # 你好，我叫婷婷。
+    let output = os::hidden_command("say")
+        .args(["-v", "?"])
+        .output()
+        .map_err(|e| format!("读取语音列表失败: {}", e))?;
+    let pattern = regex::Regex::new(r"^(.+?)\s+([a-z]{2,3}[_-][A-Za-z0-9]+)\s+#").unwrap();
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| pattern.captures(line))
+        .map(|caps| Voice {
+            name: caps[1].trim().to_string(),
+            language: Some(caps[2].replace('_', "-")),
+        })
+        .collect())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn say(utterance: &Utterance) -> Result<(), String> {
+    let mut command = os::hidden_command("spd-say");
+    command.args([
+        "--wait",
+        "--rate",
+        &(utterance.rate * 10).to_string(),
+        "--volume",
+        &(utterance.volume as i32 * 2 - 100).to_string(),
+    ]);
+    if let Some(voice) = &utterance.voice {
+        command.args(["--synthesis-voice", voice]);
+    }
+    let output = command
+        .arg("--")
+        .arg(&utterance.text)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("启动语音合成失败（需要安装 speech-dispatcher）: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "语音合成失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn voices() -> Result<Vec<Voice>, String> {
+    // 例如：
+    //           NAME     LANGUAGE  VARIANT
+    //     Chinese_(Mandarin)  cmn  none
+    let output = os::hidden_command("spd-say")
+        .arg("--list-synthesis-voices")
+        .output()
+        .map_err(|e| format!("读取语音列表失败（需要安装 speech-dispatcher）: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(Voice {
+                name: fields.next()?.to_string(),
+                language: fields.next().map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+/// 启动播报线程，返回播报队列
+fn queue() -> AppResult<&'static SyncSender<Utterance>> {
+    if let Some(sender) = QUEUE.get() {
+        return Ok(sender);
+    }
+    let (sender, receiver) = mpsc::sync_channel::<Utterance>(MAX_QUEUED);
+    std::thread::Builder::new()
+        .name("speech".into())
+        .spawn(move || {
+            for utterance in receiver {
+                if let Err(e) = say(&utterance) {
+                    tracing::warn!("{}", e);
+                }
+            }
+        })
+        .map_err(|e| AppError::Internal(format!("启动语音播报线程失败: {}", e)))?;
+    Ok(QUEUE.get_or_init(|| sender))
+}
+
+fn utterance(text: String, voice: Option<String>, settings: &SpeechConfig) -> AppResult<Utterance> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::InvalidArgument("播报内容不能为空".to_string()));
+    }
+    if text.chars().count() > MAX_TEXT_CHARS {
+        return Err(AppError::InvalidArgument(format!(
+            "播报内容不能超过 {} 个字",
+            MAX_TEXT_CHARS
+        )));
+    }
+    let voice = voice
+        .or_else(|| settings.voice.clone())
+        .filter(|v| !v.trim().is_empty());
+    if voice.as_deref().is_some_and(|v| v.starts_with('-')) {
+        return Err(AppError::InvalidArgument("语音名称无效".to_string()));
+    }
+    Ok(Utterance {
+        text: text.to_string(),
+        voice,
+        rate: settings.rate.clamp(-10, 10),
+        volume: settings.volume.min(100),
+    })
+}
+
+// Tauri 命令
+
+/// 播报一段文字（加入播报队列后立即返回），voice 为空时使用设置中的语音
+#[tauri::command]
+pub fn speak(text: String, voice: Option<String>, app_handle: tauri::AppHandle) -> AppResult<()> {
+    let settings = app_handle.state::<ConfigStore>().get().speech;
+    if !settings.enabled {
+        return Err(AppError::InvalidArgument("语音播报已关闭".to_string()));
+    }
+    let utterance = utterance(text, voice, &settings)?;
+    tracing::debug!("语音播报: {}", utterance.text);
+    queue()?.try_send(utterance).map_err(|e| match e {
+        TrySendError::Full(_) => {
+            AppError::InvalidArgument("待播报的内容过多，请稍后再试".to_string())
+        }
+        TrySendError::Disconnected(_) => AppError::Internal("语音播报线程已退出".to_string()),
+    })
+}
+
+/// 列出系统中可用的语音
+#[tauri::command]
+pub async fn list_voices() -> AppResult<Vec<Voice>> {
+    voices().map_err(AppError::Internal)
+}
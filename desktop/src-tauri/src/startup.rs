@@ -0,0 +1,195 @@
+// 启动耗时统计
+//
+// 记录每次启动各阶段的耗时（定位 Backend 可执行文件、启动进程、Backend 就绪、
+// 数据库迁移、窗口显示），保存最近几次的时间线到 <应用数据目录>/startup.json，
+// 用于分析门店低配电脑上启动慢的原因。
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::{config, tray};
+
+/// 保留的启动记录数
+const MAX_HISTORY: usize = 20;
+
+/// 全部记录完成才保存时间线的阶段
+const FINAL_PHASES: &[&str] = &["backend_ready", "window_show"];
+
+const HISTORY_FILE: &str = "startup.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// 相对启动开始的时间（毫秒），由 Backend 上报的阶段为空
+    pub offset_ms: Option<u64>,
+    pub duration_ms: u64,
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupTimeline {
+    pub started_at: String,
+    pub app_version: String,
+    pub release: bool,
+    /// 启动时系统已运行的时长（秒），较小说明是开机后的冷启动
+    pub system_uptime_secs: u64,
+    pub phases: Vec<StartupPhase>,
+    pub total_ms: Option<u64>,
+}
+
+struct Recorder {
+    started: Instant,
+    marks: Vec<(&'static str, Instant)>,
+    timeline: StartupTimeline,
+    finished: bool,
+}
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// 开始记录本次启动（在 main 最开始调用）
+pub fn begin() {
+    let timeline = StartupTimeline {
+        started_at: chrono::Local::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        release: cfg!(not(debug_assertions)),
+        system_uptime_secs: sysinfo::System::uptime(),
+        phases: Vec::new(),
+        total_ms: None,
+    };
+    *RECORDER.lock().unwrap() = Some(Recorder {
+        started: Instant::now(),
+        marks: Vec::new(),
+        timeline,
+        finished: false,
+    });
+}
+
+fn push(phase: StartupPhase) {
+    if let Ok(mut recorder) = RECORDER.lock() {
+        // 启动完成后（例如手动重启 Backend）不再记录
+        if let Some(recorder) = recorder.as_mut().filter(|r| !r.finished) {
+            tracing::debug!("启动阶段 {}: {}ms", phase.name, phase.duration_ms);
+            recorder.timeline.phases.push(phase);
+        }
+    }
+}
+
+/// 记录一个从 since 开始、到现在结束的阶段
+pub fn record(name: &str, since: Instant, ok: bool) {
+    let offset_ms = RECORDER
+        .lock()
+        .ok()
+        .and_then(|r| {
+            r.as_ref()
+                .map(|r| since.saturating_duration_since(r.started))
+        })
+        .map(|d| d.as_millis() as u64);
+    push(StartupPhase {
+        name: name.to_string(),
+        offset_ms,
+        duration_ms: since.elapsed().as_millis() as u64,
+        ok,
+    });
+}
+
+/// 记录一个只知道耗时的阶段（例如 Backend 上报的数据库迁移耗时）
+pub fn record_duration(name: &str, duration_ms: u64) {
+    push(StartupPhase {
+        name: name.to_string(),
+        offset_ms: None,
+        duration_ms,
+        ok: true,
+    });
+}
+
+/// 标记一个时间点，供之后的阶段作为起点
+pub fn mark(name: &'static str) {
+    if let Ok(mut recorder) = RECORDER.lock() {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.marks.push((name, Instant::now()));
+        }
+    }
+}
+
+/// 记录从标记点（不存在时为启动开始）到现在的阶段
+pub fn record_from(mark: &str, name: &str, ok: bool) {
+    let since = RECORDER.lock().ok().and_then(|r| {
+        r.as_ref().map(|r| {
+            r.marks
+                .iter()
+                .rev()
+                .find(|(m, _)| *m == mark)
+                .map(|(_, t)| *t)
+                .unwrap_or(r.started)
+        })
+    });
+    if let Some(since) = since {
+        record(name, since, ok);
+    }
+}
+
+fn history_path(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join(HISTORY_FILE)
+}
+
+fn load_history(app_handle: &tauri::AppHandle) -> Vec<StartupTimeline> {
+    std::fs::read_to_string(history_path(app_handle))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 关键阶段都已记录时结束本次记录并保存
+pub fn try_finish(app_handle: &tauri::AppHandle) {
+    let timeline = {
+        let mut recorder = RECORDER.lock().unwrap();
+        let Some(recorder) = recorder.as_mut().filter(|r| !r.finished) else {
+            return;
+        };
+        // 无窗口模式和开机自启动时不显示主窗口，不等待 window_show
+        let done = FINAL_PHASES
+            .iter()
+            .filter(|name| **name != "window_show" || !tray::start_hidden())
+            .all(|name| recorder.timeline.phases.iter().any(|p| p.name == *name));
+        if !done {
+            return;
+        }
+        recorder.finished = true;
+        recorder.timeline.total_ms = Some(recorder.started.elapsed().as_millis() as u64);
+        recorder.timeline.clone()
+    };
+    tracing::info!("启动完成, 总耗时: {:?}ms", timeline.total_ms);
+
+    let mut history = load_history(app_handle);
+    history.push(timeline);
+    if history.len() > MAX_HISTORY {
+        history.drain(..history.len() - MAX_HISTORY);
+    }
+
+    let path = history_path(app_handle);
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let saved = serde_json::to_string_pretty(&history)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = saved {
+        tracing::warn!("保存启动记录失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 最近几次启动的时间线（最新的在前）
+#[tauri::command]
+pub fn get_startup_timelines(
+    limit: Option<usize>,
+    app_handle: tauri::AppHandle,
+) -> Vec<StartupTimeline> {
+    let mut history = load_history(&app_handle);
+    history.reverse();
+    history.truncate(limit.unwrap_or(MAX_HISTORY));
+    history
+}
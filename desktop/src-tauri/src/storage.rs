@@ -0,0 +1,250 @@
+// 存储健康检查
+//
+// 定期检查数据库所在磁盘的健康状况：读取磁盘自身的 SMART 状态（系统支持时），
+// 并测量一次小文件写入 + 刷盘的耗时。老化的 SSD 是门店数据库损坏的常见原因，
+// 状态变差时弹出系统通知提醒尽快备份和更换。
+
+use serde::Serialize;
+use serde_json::json;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::config::{self, ConfigStore};
+use crate::events::{self, EventKind};
+use crate::health::HealthLevel;
+use crate::{backend, disk, notify, os, performance};
+
+/// 写入探测的数据量
+const PROBE_BYTES: usize = 1024 * 1024;
+
+const PROBE_FILE: &str = ".smartmart-write-probe";
+
+static LAST: Mutex<Option<StorageHealth>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmartStatus {
+    Healthy,
+    Warning,
+    Failing,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageHealth {
+    pub path: String,
+    pub mount_point: Option<String>,
+    pub smart: SmartStatus,
+    /// SMART 状态的原始描述
+    pub smart_detail: Option<String>,
+    pub write_latency_ms: Option<u64>,
+    pub write_error: Option<String>,
+    pub level: HealthLevel,
+    pub message: String,
+    pub checked_at: String,
+}
+
+/// 需要检查的目录：数据库所在目录，找不到 Backend 时使用应用数据目录
+fn target_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    backend::database_dir().unwrap_or_else(|| config::data_dir(app_handle))
+}
+
+/// 写入并刷盘一个小文件，返回耗时
+fn probe_write(dir: &Path) -> Result<Duration, String> {
+    let path = dir.join(PROBE_FILE);
+    let started = Instant::now();
+    let result = std::fs::File::create(&path)
+        .and_then(|mut file| {
+            file.write_all(&vec![0u8; PROBE_BYTES])?;
+            file.sync_all()
+        })
+        .map_err(|e| format!("写入测试失败: {}", e));
+    let elapsed = started.elapsed();
+    let _ = std::fs::remove_file(&path);
+    result.map(|_| elapsed)
+}
+
+/// 读取磁盘的 SMART 状态
+#[cfg(target_os = "windows")]
+fn smart_status(path: &Path, _mount_point: Option<&str>) -> (SmartStatus, Option<String>) {
+    let Some(letter) = path
+        .to_string_lossy()
+        .chars()
+        .next()
+        .filter(|c| c.is_ascii_alphabetic())
+    else {
+        return (SmartStatus::Unknown, None);
+    };
+    let script = format!(
+        "(Get-Partition -DriveLetter {} | Get-Disk | Get-PhysicalDisk).HealthStatus",
+        letter
+    );
+    let output = os::hidden_command("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output();
+    let Ok(output) = output else {
+        return (SmartStatus::Unknown, None);
+    };
+    let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let smart = match status.as_str() {
+        "Healthy" => SmartStatus::Healthy,
+        "Warning" => SmartStatus::Warning,
+        "Unhealthy" => SmartStatus::Failing,
+        _ => SmartStatus::Unknown,
+    };
+    (smart, Some(status).filter(|s| !s.is_empty()))
+}
+
+#[cfg(target_os = "macos")]
+fn smart_status(_path: &Path, mount_point: Option<&str>) -> (SmartStatus, Option<String>) {
+    let Some(mount_point) = mount_point else {
+        return (SmartStatus::Unknown, None);
+    };
+    let Ok(output) = os::hidden_command("diskutil").args(["info", mount_point]).output() else {
+        return (SmartStatus::Unknown, None);
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(status) = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("SMART Status:"))
+        .map(|s| s.trim().to_string())
+    else {
+        return (SmartStatus::Unknown, None);
+    };
+    let smart = match status.as_str() {
+        "Verified" => SmartStatus::Healthy,
+        "Failing" => SmartStatus::Failing,
+        _ => SmartStatus::Unknown,
+    };
+    (smart, Some(status))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn smart_status(_path: &Path, mount_point: Option<&str>) -> (SmartStatus, Option<String>) {
+    // 需要安装 smartmontools 且有权限读取设备，否则返回 unknown
+    let Some(device) = mount_point.and_then(disk::device_for) else {
+        return (SmartStatus::Unknown, None);
+    };
+    let Ok(output) = os::hidden_command("smartctl").args(["-H", &device]).output() else {
+        return (SmartStatus::Unknown, None);
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(status) = stdout
+        .lines()
+        .find(|line| line.contains("overall-health") || line.contains("Health Status"))
+        .and_then(|line| line.split(':').nth(1))
+        .map(|s| s.trim().to_string())
+    else {
+        return (SmartStatus::Unknown, None);
+    };
+    let smart = if status == "PASSED" || status == "OK" {
+        SmartStatus::Healthy
+    } else {
+        SmartStatus::Failing
+    };
+    (smart, Some(status))
+}
+
+/// 检查一次存储健康状况
+pub fn check(app_handle: &tauri::AppHandle) -> StorageHealth {
+    let settings = app_handle.state::<ConfigStore>().get().storage;
+    let dir = target_dir(app_handle);
+    let space = disk::space_for(&dir);
+    let mount_point = space.as_ref().map(|s| s.mount_point.clone());
+    let (smart, smart_detail) = smart_status(&dir, mount_point.as_deref());
+    let write = probe_write(&dir);
+    let write_latency_ms = write.as_ref().ok().map(|d| d.as_millis() as u64);
+
+    let (level, message) = if smart == SmartStatus::Failing {
+        (HealthLevel::Red, "磁盘自检报告故障，请尽快备份数据并更换磁盘".to_string())
+    } else if let Err(e) = &write {
+        (HealthLevel::Red, e.clone())
+    } else if smart == SmartStatus::Warning {
+        (HealthLevel::Yellow, "磁盘自检报告异常，建议尽快备份数据".to_string())
+    } else if write_latency_ms.is_some_and(|ms| ms > settings.slow_write_ms) {
+        (
+            HealthLevel::Yellow,
+            format!("磁盘写入缓慢（{}ms），磁盘可能老化", write_latency_ms.unwrap_or_default()),
+        )
+    } else {
+        (HealthLevel::Green, "磁盘状态正常".to_string())
+    };
+
+    StorageHealth {
+        path: dir.to_string_lossy().into_owned(),
+        mount_point,
+        smart,
+        smart_detail,
+        write_latency_ms,
+        write_error: write.err(),
+        level,
+        message,
+        checked_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+/// 最近一次检查结果
+pub fn last() -> Option<StorageHealth> {
+    LAST.lock().ok()?.clone()
+}
+
+/// 保存检查结果，状态变差时通知
+fn update(app_handle: &tauri::AppHandle, health: StorageHealth) {
+    let previous = LAST
+        .lock()
+        .unwrap()
+        .replace(health.clone())
+        .map(|h| h.level);
+
+    let worse = match (previous, health.level) {
+        (_, HealthLevel::Green | HealthLevel::Unknown) => false,
+        (Some(HealthLevel::Red), _) => false,
+        (Some(HealthLevel::Yellow), level) => level == HealthLevel::Red,
+        _ => true,
+    };
+    if !worse {
+        return;
+    }
+
+    tracing::warn!("存储健康告警: {}", health.message);
+    events::record(
+        EventKind::State,
+        format!("存储健康告警: {}", health.message),
+        json!({ "smart": health.smart, "write_latency_ms": health.write_latency_ms }),
+    );
+    notify::show(app_handle, "磁盘健康告警", &health.message);
+    let _ = app_handle.emit_all("storage://warning", &health);
+}
+
+/// 启动定期检查线程
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("storage-health".into())
+        .spawn(move || loop {
+            let settings = app_handle.state::<ConfigStore>().get().storage;
+            if settings.enabled {
+                let health = check(&app_handle);
+                update(&app_handle, health);
+            }
+            std::thread::sleep(performance::interval(Duration::from_secs(
+                settings.check_interval_mins.max(1) * 60,
+            )));
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动存储检查线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+/// 立即检查存储健康状况
+#[tauri::command]
+pub async fn get_storage_health(app_handle: tauri::AppHandle) -> StorageHealth {
+    let health = check(&app_handle);
+    update(&app_handle, health.clone());
+    health
+}
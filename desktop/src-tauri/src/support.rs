@@ -0,0 +1,217 @@
+// 技术支持包
+//
+// 一键把排查问题需要的材料打包成 zip：
+// - logs/         壳程序日志、Backend 日志、崩溃报告（整个日志目录，隐藏其中的 access_token）
+// - config.json   应用配置（已隐藏地址、密钥等敏感字段）
+// - system.json   系统信息
+// - diagnostics.json  打包时的诊断结果（Backend 状态、健康检查等）
+// - events.json   最近的壳程序事件
+// - attachments/  最近 24 小时内保存到支持目录的截图和录屏
+//
+// 生成的文件保存在 <应用数据目录>/support，并在文件管理器中选中，方便附加到工单。
+
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::backend::{self, BackendProcess};
+use crate::config::{self, ConfigStore};
+use crate::error::{AppError, AppResult};
+use crate::reporting::ReportErr;
+use crate::{backend_logs, crash, events, logging, os, shutdown, system_info, telemetry};
+
+/// 配置中需要隐藏的字段（字段名为以下任一名称，或以 "_名称" 结尾，例如 api_key、access_token）
+const SENSITIVE_KEYS: [&str; 5] = ["dsn", "token", "password", "secret", "key"];
+
+/// 打包多久以内保存的截图和录屏
+const ATTACHMENT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 支持包保存目录
+pub fn support_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join("support")
+}
+
+fn build_bundle(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let _busy = shutdown::busy("support_bundle");
+    let dir = support_dir(app_handle);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+
+    let path = dir.join(format!(
+        "smartmart-support-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let file = File::create(&path).map_err(|e| format!("创建文件失败: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+
+    if let Some(log_dir) = logging::log_dir() {
+        add_dir(&mut zip, log_dir, "logs")?;
+    }
+
+    let config = app_handle.state::<ConfigStore>().get();
+    let mut config = serde_json::to_value(config).map_err(|e| format!("序列化配置失败: {}", e))?;
+    redact(&mut config);
+    add_json(&mut zip, "config.json", &config)?;
+
+    let system = serde_json::to_value(system_info::collect(app_handle))
+        .map_err(|e| format!("序列化系统信息失败: {}", e))?;
+    add_json(&mut zip, "system.json", &system)?;
+    add_json(&mut zip, "diagnostics.json", &diagnostics(app_handle))?;
+    add_json(&mut zip, "events.json", &json!(events::recent(usize::MAX, None, None)))?;
+    add_attachments(&mut zip, &dir)?;
+
+    zip.finish().map_err(|e| format!("写入压缩包失败: {}", e))?;
+    tracing::info!("技术支持包已生成: {:?}", path);
+    Ok(path)
+}
+
+fn file_options() -> FileOptions {
+    FileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn add_json(zip: &mut ZipWriter<File>, name: &str, value: &Value) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(value).map_err(|e| format!("序列化失败: {}", e))?;
+    zip.start_file(name, file_options())
+        .and_then(|_| zip.write_all(&content).map_err(Into::into))
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
+
+/// 递归添加目录（跳过读取失败的文件，例如正在被其他进程独占写入的日志）
+fn add_dir(zip: &mut ZipWriter<File>, dir: &Path, prefix: &str) -> Result<(), String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+
+        if path.is_dir() {
+            add_dir(zip, &path, &name)?;
+            continue;
+        }
+
+        let mut content = Vec::new();
+        if let Err(e) = File::open(&path).and_then(|mut f| f.read_to_end(&mut content)) {
+            tracing::warn!("跳过无法读取的文件: {:?} ({})", path, e);
+            continue;
+        }
+        // 以前的日志中可能记录了带 access_token 的地址
+        if let Ok(text) = std::str::from_utf8(&content) {
+            if let Cow::Owned(redacted) = backend_logs::redact_access_token(text) {
+                content = redacted.into_bytes();
+            }
+        }
+        zip.start_file(name.as_str(), file_options())
+            .and_then(|_| zip.write_all(&content).map_err(Into::into))
+            .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// 添加支持目录中最近保存的截图和录屏（不包括以前生成的支持包）
+fn add_attachments(zip: &mut ZipWriter<File>, dir: &Path) -> Result<(), String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let recent = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < ATTACHMENT_MAX_AGE);
+        let is_bundle = path.extension().is_some_and(|ext| ext == "zip");
+        if !path.is_file() || is_bundle || !recent {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        if let Err(e) = File::open(&path).and_then(|mut f| f.read_to_end(&mut content)) {
+            tracing::warn!("跳过无法读取的文件: {:?} ({})", path, e);
+            continue;
+        }
+        let name = format!("attachments/{}", entry.file_name().to_string_lossy());
+        zip.start_file(name.as_str(), file_options())
+            .and_then(|_| zip.write_all(&content).map_err(Into::into))
+            .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    }
+    Ok(())
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SENSITIVE_KEYS.iter().any(|name| {
+        key == *name
+            || key
+                .strip_suffix(name)
+                .is_some_and(|prefix| prefix.ends_with(['_', '-']))
+    })
+}
+
+/// 隐藏配置中的敏感字段
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) && !value.is_null() {
+                    *value = json!("<redacted>");
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn diagnostics(app_handle: &tauri::AppHandle) -> Value {
+    let (port, pid, running) = {
+        let state = app_handle.state::<Mutex<BackendProcess>>();
+        let mut backend = state.lock().unwrap();
+        (backend.port(), backend.pid(), backend.is_running())
+    };
+
+    let health = match backend::check_health(port) {
+        Ok(elapsed) => json!({ "ok": true, "latency_ms": elapsed.as_millis() as u64 }),
+        Err(e) => json!({ "ok": false, "error": e }),
+    };
+
+    json!({
+        "generated_at": chrono::Local::now().to_rfc3339(),
+        "backend": {
+            "port": port,
+            "pid": pid,
+            "process_running": running,
+            "version": crash::backend_version(),
+            "health": health,
+        },
+        "log_filter": logging::get_log_filter().ok(),
+        "pending_crash_reports": crash::pending_reports().len(),
+    })
+}
+
+// Tauri 命令
+
+/// 生成技术支持包并在文件管理器中显示，返回文件路径
+#[tauri::command]
+pub async fn create_support_bundle(app_handle: tauri::AppHandle) -> AppResult<String> {
+    telemetry::record_feature("support_bundle");
+    let path = build_bundle(&app_handle)
+        .map_err(AppError::Io)
+        .reported("create_support_bundle")?;
+
+    if let Err(e) = os::reveal_path(&path) {
+        tracing::warn!("{}", e);
+    }
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,193 @@
+// 系统信息
+//
+// 汇总排查问题时经常需要向门店询问的环境信息：操作系统、CPU、内存、
+// 各分区剩余空间、显卡、显示器、区域设置和运行时长。
+
+use serde::Serialize;
+use sysinfo::{Disks, System};
+use tauri::Manager;
+
+use crate::os;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub os: OsInfo,
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    pub disks: Vec<DiskInfo>,
+    pub gpus: Vec<String>,
+    pub displays: Vec<DisplayInfo>,
+    pub locale: Option<String>,
+    /// 系统已运行时长（秒）
+    pub system_uptime_secs: u64,
+    /// 本程序已运行时长（秒）
+    pub app_uptime_secs: Option<u64>,
+    pub app_version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OsInfo {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub kernel: Option<String>,
+    pub arch: String,
+    pub hostname: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuInfo {
+    pub brand: String,
+    pub logical_cores: usize,
+    pub physical_cores: Option<usize>,
+    pub frequency_mhz: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryInfo {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskInfo {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub removable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayInfo {
+    pub name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+}
+
+/// 采集系统信息（CPU 信息需要短暂采样，耗时约几百毫秒）
+pub fn collect(app_handle: &tauri::AppHandle) -> SystemInfo {
+    let sys = System::new_all();
+
+    let cpus = sys.cpus();
+    let cpu = CpuInfo {
+        brand: cpus
+            .first()
+            .map(|c| c.brand().trim().to_string())
+            .unwrap_or_default(),
+        logical_cores: cpus.len(),
+        physical_cores: sys.physical_core_count(),
+        frequency_mhz: cpus.first().map(|c| c.frequency()).unwrap_or_default(),
+    };
+
+    let disks = Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .map(|disk| DiskInfo {
+            name: disk.name().to_string_lossy().into_owned(),
+            mount_point: disk.mount_point().to_string_lossy().into_owned(),
+            file_system: disk.file_system().to_string_lossy().into_owned(),
+            total_bytes: disk.total_space(),
+            available_bytes: disk.available_space(),
+            removable: disk.is_removable(),
+        })
+        .collect();
+
+    let app_uptime_secs = sysinfo::get_current_pid()
+        .ok()
+        .and_then(|pid| sys.process(pid))
+        .map(|process| process.run_time());
+
+    SystemInfo {
+        os: OsInfo {
+            name: System::name(),
+            version: System::long_os_version(),
+            kernel: System::kernel_version(),
+            arch: std::env::consts::ARCH.to_string(),
+            hostname: System::host_name(),
+        },
+        cpu,
+        memory: MemoryInfo {
+            total_bytes: sys.total_memory(),
+            available_bytes: sys.available_memory(),
+        },
+        disks,
+        gpus: gpus(),
+        displays: displays(app_handle),
+        locale: sys_locale::get_locale(),
+        system_uptime_secs: System::uptime(),
+        app_uptime_secs,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+fn displays(app_handle: &tauri::AppHandle) -> Vec<DisplayInfo> {
+    let Some(window) = app_handle.get_window("main") else {
+        return Vec::new();
+    };
+
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| DisplayInfo {
+            name: monitor.name().cloned(),
+            width: monitor.size().width,
+            height: monitor.size().height,
+            x: monitor.position().x,
+            y: monitor.position().y,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect()
+}
+
+/// 显卡型号（sysinfo 不提供，调用系统工具查询）
+fn gpus() -> Vec<String> {
+    #[cfg(target_os = "windows")]
+    let output = os::hidden_command("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_VideoController | Select-Object -ExpandProperty Name",
+        ])
+        .output();
+    #[cfg(target_os = "macos")]
+    let output = os::hidden_command("system_profiler")
+        .arg("SPDisplaysDataType")
+        .output();
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let output = os::hidden_command("lspci").output();
+
+    let Ok(output) = output else {
+        return Vec::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if cfg!(target_os = "windows") {
+                Some(line)
+            } else if cfg!(target_os = "macos") {
+                line.strip_prefix("Chipset Model:").map(str::trim)
+            } else if line.contains("VGA") || line.contains("3D controller") {
+                line.split_once(": ").map(|(_, name)| name)
+            } else {
+                None
+            }
+        })
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Tauri 命令
+
+#[tauri::command]
+pub async fn get_system_info(app_handle: tauri::AppHandle) -> SystemInfo {
+    collect(&app_handle)
+}
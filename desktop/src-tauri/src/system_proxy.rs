@@ -0,0 +1,425 @@
+// 系统代理
+//
+// 不少加盟店的网络强制经过代理，此时不设置代理的外部请求（同步、上报等）会静默失败。
+// 启动时确定外部请求使用的代理（proxy.mode 为 manual / off 时以设置为准）：
+// 1. 环境变量 HTTPS_PROXY / HTTP_PROXY / ALL_PROXY 和 NO_PROXY
+// 2. 系统代理设置：Windows 为 Internet 选项中的代理（包括自动配置脚本 PAC 和自动检测），
+//    macOS 为网络设置中的 HTTPS / HTTP 代理
+//
+// 检测结果用于壳程序访问外部服务的客户端，并通过 HTTP_PROXY / HTTPS_PROXY / NO_PROXY
+// 环境变量传给 Backend；本机地址始终不经过代理。
+// PAC 脚本只按一个外部地址求值，不区分请求的目标地址。
+
+use serde::Serialize;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::config::{ProxyConfig, ProxyMode};
+
+/// 始终不经过代理的本机地址
+const LOCAL_HOSTS: [&str; 3] = ["127.0.0.1", "localhost", "::1"];
+
+static DETECTED: OnceLock<ProxyInfo> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxySource {
+    /// 不使用代理
+    None,
+    /// 设置中指定
+    Config,
+    /// 环境变量
+    Environment,
+    /// 系统代理设置
+    System,
+    /// 代理自动配置脚本（PAC）或自动检测
+    Pac,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyInfo {
+    pub source: ProxySource,
+    /// 代理地址，例如 http://10.0.0.1:8080
+    pub url: Option<String>,
+    /// 不经过代理的主机
+    pub bypass: Vec<String>,
+    /// 系统设置的自动配置脚本地址
+    pub pac_url: Option<String>,
+    /// 检测过程中的错误（例如 PAC 脚本无法下载）
+    pub error: Option<String>,
+}
+
+impl ProxyInfo {
+    fn direct(source: ProxySource) -> Self {
+        Self {
+            source,
+            url: None,
+            bypass: Vec::new(),
+            pac_url: None,
+            error: None,
+        }
+    }
+}
+
+/// 代理地址统一为 scheme://host:port 格式（未写协议时为 http）
+fn normalize(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        None
+    } else if url.contains("://") {
+        Some(url.to_string())
+    } else {
+        Some(format!("http://{}", url))
+    }
+}
+
+/// 拆分不经过代理的主机列表（Windows 使用分号分隔，"*.corp.local" 转为 ".corp.local"）
+fn split_bypass(list: &str) -> Vec<String> {
+    list.split(|c: char| c == ';' || c == ',' || c.is_whitespace())
+        .map(str::trim)
+        // <local> 表示所有不带点的主机名，NO_PROXY 无法表示
+        .filter(|host| !host.is_empty() && *host != "<local>")
+        .map(|host| match host.strip_prefix("*.") {
+            Some(domain) => format!(".{}", domain),
+            None => host.to_string(),
+        })
+        .collect()
+}
+
+/// 解析 Windows 的代理列表（"http=a:80;https=b:443" 或 "a:80"），优先使用 https 代理
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_proxy_list(list: &str) -> Option<String> {
+    let entries: Vec<&str> = list
+        .split(|c: char| c == ';' || c.is_whitespace())
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    let scheme = |name: &str| {
+        entries.iter().find_map(|entry| {
+            let (scheme, address) = entry.split_once('=')?;
+            scheme.eq_ignore_ascii_case(name).then_some(address)
+        })
+    };
+    scheme("https")
+        .or_else(|| scheme("http"))
+        .or_else(|| entries.iter().copied().find(|entry| !entry.contains('=')))
+        .and_then(normalize)
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+/// 环境变量中的代理
+fn from_environment() -> Option<ProxyInfo> {
+    let url = env_var(&[
+        "HTTPS_PROXY",
+        "https_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+    ])
+    .and_then(|url| normalize(&url))?;
+    Some(ProxyInfo {
+        url: Some(url),
+        bypass: env_var(&["NO_PROXY", "no_proxy"])
+            .map(|list| split_bypass(&list))
+            .unwrap_or_default(),
+        ..ProxyInfo::direct(ProxySource::Environment)
+    })
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::{parse_proxy_list, split_bypass, ProxyInfo, ProxySource};
+    use windows_sys::core::PWSTR;
+    use windows_sys::Win32::Foundation::GlobalFree;
+    use windows_sys::Win32::Networking::WinHttp::{
+        WinHttpCloseHandle, WinHttpGetIEProxyConfigForCurrentUser, WinHttpGetProxyForUrl,
+        WinHttpOpen, WINHTTP_ACCESS_TYPE_NAMED_PROXY, WINHTTP_ACCESS_TYPE_NO_PROXY,
+        WINHTTP_AUTOPROXY_AUTO_DETECT, WINHTTP_AUTOPROXY_CONFIG_URL, WINHTTP_AUTOPROXY_OPTIONS,
+        WINHTTP_AUTO_DETECT_TYPE_DHCP, WINHTTP_AUTO_DETECT_TYPE_DNS_A,
+        WINHTTP_CURRENT_USER_IE_PROXY_CONFIG, WINHTTP_PROXY_INFO,
+    };
+
+    /// 用来求值 PAC 脚本的外部地址
+    const PROBE_URL: &str = "https://www.msftconnecttest.com/connecttest.txt";
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(Some(0)).collect()
+    }
+
+    /// 读取 WinHTTP 分配的字符串并释放
+    unsafe fn take(ptr: PWSTR) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+        let value = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+        GlobalFree(ptr as _);
+        Some(value).filter(|v| !v.trim().is_empty())
+    }
+
+    /// 按自动配置脚本（pac_url 为空时自动检测）求出代理，脚本返回 DIRECT 时为空
+    fn resolve(pac_url: Option<&str>) -> Result<Option<(String, Vec<String>)>, String> {
+        let agent = wide("SmartMart-Desktop");
+        let session = unsafe {
+            WinHttpOpen(
+                agent.as_ptr(),
+                WINHTTP_ACCESS_TYPE_NO_PROXY,
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+            )
+        };
+        if session.is_null() {
+            return Err(format!(
+                "WinHttpOpen 失败: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        let pac_url = pac_url.map(wide);
+        let mut options = WINHTTP_AUTOPROXY_OPTIONS {
+            dwFlags: if pac_url.is_some() {
+                WINHTTP_AUTOPROXY_CONFIG_URL
+            } else {
+                WINHTTP_AUTOPROXY_AUTO_DETECT
+            },
+            dwAutoDetectFlags: if pac_url.is_some() {
+                0
+            } else {
+                WINHTTP_AUTO_DETECT_TYPE_DHCP | WINHTTP_AUTO_DETECT_TYPE_DNS_A
+            },
+            lpszAutoConfigUrl: pac_url
+                .as_ref()
+                .map_or(std::ptr::null(), |url| url.as_ptr()),
+            lpvReserved: std::ptr::null_mut(),
+            dwReserved: 0,
+            fAutoLogonIfChallenged: 1,
+        };
+        let url = wide(PROBE_URL);
+        let mut info: WINHTTP_PROXY_INFO = unsafe { std::mem::zeroed() };
+        let ok = unsafe { WinHttpGetProxyForUrl(session, url.as_ptr(), &mut options, &mut info) };
+        let error = std::io::Error::last_os_error();
+        unsafe { WinHttpCloseHandle(session) };
+        if ok == 0 {
+            return Err(format!("解析代理自动配置脚本失败: {}", error));
+        }
+
+        let (proxy, bypass) = unsafe { (take(info.lpszProxy), take(info.lpszProxyBypass)) };
+        if info.dwAccessType != WINHTTP_ACCESS_TYPE_NAMED_PROXY {
+            return Ok(None);
+        }
+        Ok(proxy
+            .as_deref()
+            .and_then(parse_proxy_list)
+            .map(|url| (url, bypass.as_deref().map(split_bypass).unwrap_or_default())))
+    }
+
+    /// Internet 选项中的代理设置（与浏览器一致：自动配置优先，失败时使用手动代理）
+    pub fn detect() -> Option<ProxyInfo> {
+        let mut config: WINHTTP_CURRENT_USER_IE_PROXY_CONFIG = unsafe { std::mem::zeroed() };
+        if unsafe { WinHttpGetIEProxyConfigForCurrentUser(&mut config) } == 0 {
+            tracing::warn!("读取系统代理设置失败: {}", std::io::Error::last_os_error());
+            return None;
+        }
+        let (pac_url, proxy, bypass) = unsafe {
+            (
+                take(config.lpszAutoConfigUrl),
+                take(config.lpszProxy),
+                take(config.lpszProxyBypass),
+            )
+        };
+        let bypass = bypass.as_deref().map(split_bypass).unwrap_or_default();
+
+        let mut error = None;
+        if config.fAutoDetect != 0 || pac_url.is_some() {
+            match resolve(pac_url.as_deref()) {
+                Ok(resolved) => {
+                    let (url, pac_bypass) = resolved.unzip();
+                    return Some(ProxyInfo {
+                        source: ProxySource::Pac,
+                        url,
+                        bypass: pac_bypass.filter(|b| !b.is_empty()).unwrap_or(bypass),
+                        pac_url,
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    error = Some(e);
+                }
+            }
+        }
+
+        let url = proxy.as_deref().and_then(parse_proxy_list);
+        if url.is_none() && error.is_none() && pac_url.is_none() {
+            return None;
+        }
+        Some(ProxyInfo {
+            source: if url.is_some() {
+                ProxySource::System
+            } else {
+                ProxySource::None
+            },
+            url,
+            bypass,
+            pac_url,
+            error,
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::{normalize, split_bypass, ProxyInfo, ProxySource};
+    use std::collections::HashMap;
+
+    /// 解析 scutil --proxy 的输出：
+    ///   HTTPSEnable : 1
+    ///   HTTPSProxy : proxy.example.com
+    ///   ExceptionsList : <array> {
+    ///     0 : *.local
+    ///   }
+    pub fn detect() -> Option<ProxyInfo> {
+        let output = super::Command::new("scutil").arg("--proxy").output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+
+        let mut values = HashMap::new();
+        let mut exceptions = Vec::new();
+        let mut in_exceptions = false;
+        for line in text.lines().map(str::trim) {
+            if line.starts_with("ExceptionsList") {
+                in_exceptions = true;
+            } else if line == "}" {
+                in_exceptions = false;
+            } else if let Some((key, value)) = line.split_once(" : ") {
+                if in_exceptions {
+                    exceptions.extend(split_bypass(value));
+                } else {
+                    values.insert(key.to_string(), value.trim().to_string());
+                }
+            }
+        }
+
+        let enabled = |key: &str| values.get(key).is_some_and(|v| v == "1");
+        let proxy = |prefix: &str| {
+            let host = values.get(&format!("{}Proxy", prefix))?;
+            let port = values.get(&format!("{}Port", prefix));
+            normalize(&match port {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.clone(),
+            })
+        };
+        let url = enabled("HTTPSEnable")
+            .then(|| proxy("HTTPS"))
+            .flatten()
+            .or_else(|| enabled("HTTPEnable").then(|| proxy("HTTP")).flatten());
+        let pac_url = enabled("ProxyAutoConfigEnable")
+            .then(|| values.get("ProxyAutoConfigURLString").cloned())
+            .flatten();
+        if url.is_none() && pac_url.is_none() {
+            return None;
+        }
+        Some(ProxyInfo {
+            source: if url.is_some() {
+                ProxySource::System
+            } else {
+                ProxySource::None
+            },
+            error: (url.is_none() && pac_url.is_some())
+                .then(|| "暂不支持代理自动配置脚本，请在设置中手动填写代理".to_string()),
+            url,
+            bypass: exceptions,
+            pac_url,
+        })
+    }
+}
+
+fn from_system() -> Option<ProxyInfo> {
+    #[cfg(target_os = "windows")]
+    return windows::detect();
+    #[cfg(target_os = "macos")]
+    return macos::detect();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    None
+}
+
+fn detect(settings: &ProxyConfig) -> ProxyInfo {
+    let mut info = match settings.mode {
+        ProxyMode::Off => ProxyInfo::direct(ProxySource::Config),
+        ProxyMode::Manual => match settings.url.as_deref().and_then(normalize) {
+            Some(url) => ProxyInfo {
+                url: Some(url),
+                ..ProxyInfo::direct(ProxySource::Config)
+            },
+            None => ProxyInfo {
+                error: Some("手动代理未填写代理地址".to_string()),
+                ..ProxyInfo::direct(ProxySource::Config)
+            },
+        },
+        ProxyMode::Auto => from_environment()
+            .or_else(from_system)
+            .unwrap_or_else(|| ProxyInfo::direct(ProxySource::None)),
+    };
+    for host in settings.bypass.iter().flat_map(|list| split_bypass(list)) {
+        if !info.bypass.contains(&host) {
+            info.bypass.push(host);
+        }
+    }
+    info
+}
+
+/// 启动时按设置检测代理（需在启动 Backend 和发出外部请求之前调用）
+pub fn init(settings: &ProxyConfig) {
+    let info = detect(settings);
+    match &info.url {
+        Some(url) => tracing::info!("外部请求使用代理: {} ({:?})", url, info.source),
+        None => tracing::info!("外部请求不使用代理 ({:?})", info.source),
+    }
+    if let Some(error) = &info.error {
+        tracing::warn!("代理检测: {}", error);
+    }
+    let _ = DETECTED.set(info);
+}
+
+/// 检测到的代理
+pub fn current() -> &'static ProxyInfo {
+    DETECTED.get_or_init(|| detect(&ProxyConfig::default()))
+}
+
+/// 把代理设置传给子进程（本机地址始终不经过代理）
+pub fn apply_env(command: &mut Command) {
+    let info = current();
+    let mut no_proxy: Vec<&str> = LOCAL_HOSTS.to_vec();
+    no_proxy.extend(info.bypass.iter().map(String::as_str));
+    // 先清除继承的环境变量（包括小写形式），避免与检测结果不一致
+    for name in [
+        "HTTP_PROXY",
+        "http_proxy",
+        "HTTPS_PROXY",
+        "https_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+        "no_proxy",
+    ] {
+        command.env_remove(name);
+    }
+    command.env("NO_PROXY", no_proxy.join(","));
+    if let Some(url) = &info.url {
+        command.env("HTTP_PROXY", url).env("HTTPS_PROXY", url);
+    }
+}
+
+// Tauri 命令
+
+/// 查看外部请求使用的代理
+#[tauri::command]
+pub fn get_proxy_info() -> ProxyInfo {
+    current().clone()
+}
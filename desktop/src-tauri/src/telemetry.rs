@@ -0,0 +1,264 @@
+// 匿名使用统计（需用户主动开启）
+//
+// 只统计计数：功能使用次数、错误类别次数，以及粗粒度的硬件类型
+// （系统版本、CPU 型号、内存档位、显示器数量）。不包含主机名、用户名、
+// 商品或订单等业务数据。安装标识是随机生成的，关闭统计时一并清除。
+//
+// 计数先累积在内存中，定期写入 <应用数据目录>/telemetry.json，
+// 按配置的间隔批量上传，上传成功后清零。可通过 preview_telemetry 查看将要上传的完整内容。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Manager, State};
+
+use crate::config::{self, ConfigStore, TelemetryConfig};
+use crate::error::AppResult;
+use crate::http;
+use crate::reporting::ReportErr;
+use crate::system_info;
+
+/// 构建时内置的上传地址
+const BUILTIN_ENDPOINT: Option<&str> = option_env!("SMARTMART_TELEMETRY_URL");
+
+/// 统计数据格式版本
+const SCHEMA_VERSION: u32 = 1;
+
+/// 计数写盘、检查上传的间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(300);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNTERS: Mutex<Option<Counters>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Counters {
+    period_start: Option<String>,
+    last_upload: Option<String>,
+    features: BTreeMap<String, u64>,
+    errors: BTreeMap<String, u64>,
+}
+
+fn state_path(app_handle: &tauri::AppHandle) -> PathBuf {
+    config::data_dir(app_handle).join("telemetry.json")
+}
+
+fn load_counters(app_handle: &tauri::AppHandle) -> Counters {
+    std::fs::read_to_string(state_path(app_handle))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_counters(app_handle: &tauri::AppHandle, counters: &Counters) {
+    let path = state_path(app_handle);
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    match serde_json::to_string(counters) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&path, content) {
+                tracing::warn!("保存统计数据失败: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("序列化统计数据失败: {}", e),
+    }
+}
+
+fn increment(select: impl FnOnce(&mut Counters) -> &mut BTreeMap<String, u64>, key: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Ok(mut counters) = COUNTERS.lock() {
+        let counters = counters.get_or_insert_with(Counters::default);
+        *select(counters).entry(key.to_string()).or_default() += 1;
+    }
+}
+
+/// 记录一次功能使用
+pub fn record_feature(feature: &str) {
+    increment(|c| &mut c.features, feature);
+}
+
+/// 记录一次错误（按命令名归类，不包含错误内容）
+pub fn record_error(category: &str) {
+    increment(|c| &mut c.errors, category);
+}
+
+/// 内存档位，避免上传精确配置
+fn memory_bucket(bytes: u64) -> &'static str {
+    match bytes / (1024 * 1024 * 1024) {
+        0..=3 => "<4GB",
+        4..=7 => "4-8GB",
+        8..=15 => "8-16GB",
+        _ => ">=16GB",
+    }
+}
+
+fn build_payload(app_handle: &tauri::AppHandle, settings: &TelemetryConfig) -> Value {
+    let counters = COUNTERS
+        .lock()
+        .ok()
+        .and_then(|c| c.clone())
+        .unwrap_or_default();
+    let system = system_info::collect(app_handle);
+
+    json!({
+        "schema": SCHEMA_VERSION,
+        "install_id": settings.install_id,
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "period_start": counters.period_start,
+        "period_end": chrono::Local::now().to_rfc3339(),
+        "hardware": {
+            "os": system.os.name,
+            "os_version": system.os.version,
+            "arch": system.os.arch,
+            "cpu": system.cpu.brand,
+            "cpu_cores": system.cpu.logical_cores,
+            "memory": memory_bucket(system.memory.total_bytes),
+            "displays": system.displays.len(),
+        },
+        "features": counters.features,
+        "errors": counters.errors,
+    })
+}
+
+fn endpoint(settings: &TelemetryConfig) -> Option<String> {
+    settings
+        .endpoint
+        .clone()
+        .filter(|e| !e.is_empty())
+        .or_else(|| BUILTIN_ENDPOINT.map(str::to_string))
+}
+
+/// 到达上传时间则上传，成功后清零计数
+fn upload_if_due(app_handle: &tauri::AppHandle, settings: &TelemetryConfig) {
+    let Some(endpoint) = endpoint(settings) else {
+        return;
+    };
+
+    // 从上次上传（或开始统计）起满一个间隔才上传
+    let since = COUNTERS
+        .lock()
+        .ok()
+        .and_then(|c| {
+            c.as_ref()
+                .and_then(|c| c.last_upload.clone().or_else(|| c.period_start.clone()))
+        })
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok());
+    let interval = chrono::Duration::hours(settings.upload_interval_hours.max(1) as i64);
+    if since.is_some_and(|t| chrono::Local::now().fixed_offset() - t < interval) {
+        return;
+    }
+
+    let payload = build_payload(app_handle, settings);
+    match http::external().post(&endpoint).send_json(payload) {
+        Ok(_) => {
+            tracing::info!("匿名使用统计已上传");
+            let now = chrono::Local::now().to_rfc3339();
+            if let Ok(mut counters) = COUNTERS.lock() {
+                *counters = Some(Counters {
+                    period_start: Some(now.clone()),
+                    last_upload: Some(now),
+                    ..Default::default()
+                });
+            }
+        }
+        Err(e) => tracing::debug!("上传使用统计失败: {}", e),
+    }
+}
+
+/// 启动统计线程：加载上次未上传的计数，定期写盘并按计划上传
+pub fn start(app_handle: tauri::AppHandle) {
+    let settings = app_handle.state::<ConfigStore>().get().telemetry;
+    ENABLED.store(settings.enabled, Ordering::Relaxed);
+    if settings.enabled {
+        let mut counters = load_counters(&app_handle);
+        counters
+            .period_start
+            .get_or_insert_with(|| chrono::Local::now().to_rfc3339());
+        *COUNTERS.lock().unwrap() = Some(counters);
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("telemetry".into())
+        .spawn(move || loop {
+            std::thread::sleep(FLUSH_INTERVAL);
+
+            let settings = app_handle.state::<ConfigStore>().get().telemetry;
+            if !settings.enabled {
+                continue;
+            }
+
+            upload_if_due(&app_handle, &settings);
+            let counters = COUNTERS.lock().ok().and_then(|c| c.clone());
+            if let Some(counters) = counters {
+                save_counters(&app_handle, &counters);
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动统计线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+#[tauri::command]
+pub fn get_telemetry_enabled(config: State<'_, ConfigStore>) -> bool {
+    config.get().telemetry.enabled
+}
+
+#[tauri::command]
+pub fn set_telemetry_enabled(
+    enabled: bool,
+    app_handle: tauri::AppHandle,
+    config: State<'_, ConfigStore>,
+) -> AppResult<()> {
+    config
+        .update(|c| {
+            c.telemetry.enabled = enabled;
+            c.telemetry.install_id = enabled.then(|| {
+                c.telemetry
+                    .install_id
+                    .clone()
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+            });
+        })
+        .reported("set_telemetry_enabled")?;
+
+    ENABLED.store(enabled, Ordering::Relaxed);
+    let mut counters = COUNTERS.lock().unwrap();
+    if enabled {
+        counters.get_or_insert_with(|| Counters {
+            period_start: Some(chrono::Local::now().to_rfc3339()),
+            ..Default::default()
+        });
+    } else {
+        // 关闭时丢弃未上传的数据
+        *counters = None;
+        let _ = std::fs::remove_file(state_path(&app_handle));
+    }
+
+    tracing::info!("匿名使用统计已{}", if enabled { "开启" } else { "关闭" });
+    Ok(())
+}
+
+/// 预览下一次将要上传的完整内容
+#[tauri::command]
+pub async fn preview_telemetry(
+    app_handle: tauri::AppHandle,
+    config: State<'_, ConfigStore>,
+) -> AppResult<Value> {
+    Ok(build_payload(&app_handle, &config.get().telemetry))
+}
+
+/// 前端记录功能使用
+#[tauri::command]
+pub fn track_feature(feature: String) {
+    record_feature(&feature);
+}
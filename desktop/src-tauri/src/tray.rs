@@ -0,0 +1,176 @@
+// 托盘图标和无窗口模式
+//
+// 后台办公室的电脑只作为局域网主服务器给其他收银终端使用时，不需要打开收银界面。
+// 以 --headless 参数启动或在设置中开启 launch.headless 后，只启动 Backend 和托盘图标，
+// 主窗口保持隐藏，可从托盘菜单打开。
+//
+// 普通模式下同样显示托盘图标。开机自启动（--minimized）时主窗口不显示，只在托盘中运行。
+// 开启 launch.close_to_tray（默认）或无窗口模式下，关闭主窗口只是隐藏到托盘，
+// Backend 继续运行，避免营业中误关窗口导致其他收银终端断开，需从托盘菜单退出。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{
+    CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem,
+    WindowEvent,
+};
+
+use crate::config::ConfigStore;
+use crate::{logging, notify, os, scheduler};
+
+/// 命令行参数
+pub const HEADLESS_ARG: &str = "--headless";
+
+/// 开机自启动时的参数（见 main.rs 中的自启动插件设置）
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+const MENU_TOGGLE_WINDOW: &str = "toggle_window";
+const MENU_RESTART_BACKEND: &str = "restart_backend";
+const MENU_OPEN_LOGS: &str = "open_logs";
+const MENU_QUIT: &str = "quit";
+
+const TITLE_SHOW: &str = "打开 SmartMart";
+const TITLE_HIDE: &str = "隐藏窗口";
+
+static HEADLESS: AtomicBool = AtomicBool::new(false);
+static MINIMIZED: AtomicBool = AtomicBool::new(false);
+static CLOSE_TO_TRAY: AtomicBool = AtomicBool::new(true);
+
+/// 是否已提示过“窗口已隐藏到托盘”
+static HIDE_NOTIFIED: AtomicBool = AtomicBool::new(false);
+
+/// 是否以无窗口模式运行
+pub fn is_headless() -> bool {
+    HEADLESS.load(Ordering::Relaxed)
+}
+
+/// 启动时是否不显示主窗口（无窗口模式或开机自启动）
+pub fn start_hidden() -> bool {
+    is_headless() || MINIMIZED.load(Ordering::Relaxed)
+}
+
+/// 根据命令行参数和设置决定是否以无窗口模式运行（在创建窗口前调用）
+pub fn init(config: &ConfigStore) {
+    let launch = config.get().launch;
+    let headless = std::env::args().any(|arg| arg == HEADLESS_ARG) || launch.headless;
+    HEADLESS.store(headless, Ordering::Relaxed);
+    MINIMIZED.store(
+        std::env::args().any(|arg| arg == MINIMIZED_ARG),
+        Ordering::Relaxed,
+    );
+    CLOSE_TO_TRAY.store(launch.close_to_tray, Ordering::Relaxed);
+    if headless {
+        tracing::info!("以无窗口模式运行，只启动 Backend 和托盘图标");
+    } else if start_hidden() {
+        tracing::info!("开机自启动，主窗口隐藏在托盘中");
+    }
+}
+
+pub fn system_tray() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(MENU_TOGGLE_WINDOW, TITLE_SHOW))
+        .add_item(CustomMenuItem::new(MENU_RESTART_BACKEND, "重启 Backend"))
+        .add_item(CustomMenuItem::new(MENU_OPEN_LOGS, "打开日志文件夹"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(MENU_QUIT, "退出"));
+    SystemTray::new()
+        .with_tooltip(if is_headless() {
+            "SmartMart（服务器模式）"
+        } else {
+            "SmartMart"
+        })
+        .with_menu(menu)
+}
+
+/// 更新托盘菜单中显示 / 隐藏窗口的文字
+fn update_toggle_title(app_handle: &tauri::AppHandle, visible: bool) {
+    let _ = app_handle
+        .tray_handle()
+        .get_item(MENU_TOGGLE_WINDOW)
+        .set_title(if visible { TITLE_HIDE } else { TITLE_SHOW });
+}
+
+/// 显示并激活主窗口
+pub fn show_main_window(app_handle: &tauri::AppHandle) {
+    if let Some(window) = app_handle.get_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        update_toggle_title(app_handle, true);
+    }
+}
+
+/// 隐藏主窗口到托盘
+fn hide_main_window(window: &tauri::Window) {
+    let _ = window.hide();
+    update_toggle_title(&window.app_handle(), false);
+}
+
+fn toggle_main_window(app_handle: &tauri::AppHandle) {
+    let Some(window) = app_handle.get_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        hide_main_window(&window);
+    } else {
+        show_main_window(app_handle);
+    }
+}
+
+pub fn open_log_folder() {
+    let Some(dir) = logging::log_dir() else {
+        return;
+    };
+    if let Err(e) = os::open_path(dir) {
+        tracing::warn!("打开日志文件夹失败: {}", e);
+    }
+}
+
+fn restart_backend(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    let spawned = std::thread::Builder::new()
+        .name("tray-restart".into())
+        .spawn(move || {
+            if let Err(e) = scheduler::execute(&app_handle, "backend_restart", true) {
+                tracing::warn!("从托盘重启 Backend 失败: {}", e);
+            }
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动重启线程失败: {}", e);
+    }
+}
+
+pub fn handle_event(app_handle: &tauri::AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } | SystemTrayEvent::DoubleClick { .. } => {
+            show_main_window(app_handle)
+        }
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            MENU_TOGGLE_WINDOW => toggle_main_window(app_handle),
+            MENU_RESTART_BACKEND => restart_backend(app_handle),
+            MENU_OPEN_LOGS => open_log_folder(),
+            MENU_QUIT => app_handle.exit(0),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// 关闭主窗口时改为隐藏到托盘（无窗口模式或开启了 close_to_tray）
+pub fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        let close_to_tray = is_headless() || CLOSE_TO_TRAY.load(Ordering::Relaxed);
+        if close_to_tray && window.label() == "main" {
+            api.prevent_close();
+            hide_main_window(window);
+            // 第一次隐藏时提示，避免店员以为程序已退出
+            if !HIDE_NOTIFIED.swap(true, Ordering::Relaxed) {
+                notify::show(
+                    &window.app_handle(),
+                    "SmartMart 仍在运行",
+                    "窗口已隐藏到托盘，收银服务继续运行。可从托盘图标打开或退出。",
+                );
+            }
+        }
+    }
+}
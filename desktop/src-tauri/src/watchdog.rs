@@ -0,0 +1,181 @@
+// Backend 看门狗
+//
+// 后台线程定期请求 Backend 健康检查接口并记录耗时，维护最近一段时间的
+// 滚动延迟分位数。p95 超过阈值时发出 backend://slow 事件，
+// 为“收银很卡”这类反馈提供数据。
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Manager, State};
+
+use crate::backend::{self, BackendProcess};
+use crate::config::ConfigStore;
+use crate::events::{self, EventKind};
+use crate::performance;
+
+/// 少于该采样数时不判断是否变慢
+const MIN_SAMPLES: usize = 10;
+
+/// 延迟采样记录
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: VecDeque<u64>,
+    last_ms: Option<u64>,
+    last_ok: bool,
+    /// 最近一次健康检查的时间和结果（Backend 监护线程据此判断是否无响应）
+    last_probe: Option<(Instant, Result<Duration, String>)>,
+    probes: u64,
+    failures: u64,
+    slow: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub last_ms: Option<u64>,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    pub p99_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+    pub probes: u64,
+    pub failures: u64,
+    pub slow: bool,
+    pub threshold_ms: u64,
+}
+
+impl LatencyTracker {
+    fn record(&mut self, result: Result<Duration, String>, window: usize) {
+        self.probes += 1;
+        self.last_probe = Some((Instant::now(), result.clone()));
+        match result {
+            Ok(elapsed) => {
+                let ms = elapsed.as_millis() as u64;
+                self.samples.push_back(ms);
+                while self.samples.len() > window.max(1) {
+                    self.samples.pop_front();
+                }
+                self.last_ms = Some(ms);
+                self.last_ok = true;
+            }
+            Err(_) => {
+                self.failures += 1;
+                self.last_ok = false;
+            }
+        }
+    }
+
+    /// 最近采样的第 p 百分位（最近秩法）
+    fn percentile(&self, p: f64) -> Option<u64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+
+    /// 最近一次健康检查是否成功
+    pub fn last_probe_ok(&self) -> bool {
+        self.last_ok
+    }
+
+    /// 最近一次健康检查的时间和结果
+    pub fn last_probe(&self) -> Option<(Instant, Result<Duration, String>)> {
+        self.last_probe.clone()
+    }
+
+    pub fn stats(&self, threshold_ms: u64) -> LatencyStats {
+        LatencyStats {
+            samples: self.samples.len(),
+            last_ms: self.last_ms,
+            p50_ms: self.percentile(50.0),
+            p95_ms: self.percentile(95.0),
+            p99_ms: self.percentile(99.0),
+            max_ms: self.samples.iter().copied().max(),
+            probes: self.probes,
+            failures: self.failures,
+            slow: self.slow,
+            threshold_ms,
+        }
+    }
+}
+
+/// 启动看门狗线程
+pub fn start(app_handle: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("watchdog".into())
+        .spawn(move || loop {
+            let monitoring = app_handle.state::<ConfigStore>().get().monitoring;
+            let port = app_handle
+                .state::<Mutex<BackendProcess>>()
+                .lock()
+                .unwrap()
+                .port();
+
+            let result = backend::check_health(port);
+            if let Err(e) = &result {
+                tracing::debug!("{}", e);
+            }
+
+            let slow_stats = {
+                let state = app_handle.state::<Mutex<LatencyTracker>>();
+                let mut tracker = state.lock().unwrap();
+                tracker.record(result, monitoring.latency_window);
+
+                let p95 = tracker.percentile(95.0).unwrap_or_default();
+                let enough = tracker.samples.len() >= MIN_SAMPLES;
+                if !tracker.slow && enough && p95 > monitoring.slow_threshold_ms {
+                    tracker.slow = true;
+                    Some(tracker.stats(monitoring.slow_threshold_ms))
+                } else {
+                    // 降到阈值的 80% 以下才算恢复，避免在阈值附近反复触发
+                    if tracker.slow && p95 * 5 <= monitoring.slow_threshold_ms * 4 {
+                        tracker.slow = false;
+                        tracing::info!("Backend 响应恢复正常, p95: {}ms", p95);
+                        events::record(
+                            EventKind::Backend,
+                            "Backend 响应恢复正常",
+                            serde_json::json!({ "p95_ms": p95 }),
+                        );
+                    }
+                    None
+                }
+            };
+
+            if let Some(stats) = slow_stats {
+                tracing::warn!(
+                    "Backend 响应变慢, p95: {:?}ms, 阈值: {}ms",
+                    stats.p95_ms,
+                    stats.threshold_ms
+                );
+                events::record(
+                    EventKind::Backend,
+                    "Backend 响应变慢",
+                    serde_json::json!({ "p95_ms": stats.p95_ms, "threshold_ms": stats.threshold_ms }),
+                );
+                let _ = app_handle.emit_all("backend://slow", stats);
+            }
+
+            std::thread::sleep(performance::interval(Duration::from_secs(
+                monitoring.probe_interval_secs.max(1),
+            )));
+        });
+
+    if let Err(e) = spawned {
+        tracing::error!("启动看门狗线程失败: {}", e);
+    }
+}
+
+// Tauri 命令
+
+#[tauri::command]
+pub fn get_backend_latency(
+    tracker: State<'_, Mutex<LatencyTracker>>,
+    config: State<'_, ConfigStore>,
+) -> LatencyStats {
+    let threshold_ms = config.get().monitoring.slow_threshold_ms;
+    tracker.lock().unwrap().stats(threshold_ms)
+}
@@ -0,0 +1,219 @@
+// 网络唤醒（Wake-on-LAN）
+//
+// 主收银机可以在早上开店时唤醒网络打印机和其他收银终端：
+// - 设备的 MAC 地址保存在设置的 wake_on_lan.devices 中
+// - 定时任务 open_store_wake 依次唤醒所有设备（默认不执行，在定时任务中设置开店时间）
+//
+// 唤醒包（6 个 0xFF + 16 次 MAC 地址）通过 UDP 广播发送，设备需在 BIOS / 网卡设置中开启网络唤醒。
+// 跨网段的设备需要填写该网段的广播地址（路由器一般不转发 255.255.255.255）。
+
+use serde::Serialize;
+use std::net::{Ipv4Addr, UdpSocket};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::config::{ConfigStore, WakeDevice, WakeOnLanConfig};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, EventKind};
+use crate::scheduler;
+
+/// 每个唤醒包重复发送的次数（UDP 可能丢包）
+const REPEAT: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WakeResult {
+    pub name: String,
+    pub mac: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// 解析 MAC 地址，支持 AA:BB:CC:DD:EE:FF、AA-BB-CC-DD-EE-FF、AABB.CCDD.EEFF 和 AABBCCDDEEFF
+pub(crate) fn parse_mac(mac: &str) -> Result<[u8; 6], String> {
+    let digits: String = mac
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect();
+    if digits.len() != 12 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("MAC 地址格式错误: {}", mac));
+    }
+    let mut bytes = [0u8; 6];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    Ok(bytes)
+}
+
+pub(crate) fn format_mac(bytes: &[u8; 6]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn parse_broadcast(address: &str) -> Result<Ipv4Addr, String> {
+    address
+        .trim()
+        .parse()
+        .map_err(|_| format!("广播地址格式错误: {}", address))
+}
+
+fn magic_packet(mac: &[u8; 6]) -> Vec<u8> {
+    let mut packet = vec![0xFF; 6];
+    for _ in 0..16 {
+        packet.extend_from_slice(mac);
+    }
+    packet
+}
+
+fn send(mac: &[u8; 6], broadcast: Ipv4Addr, port: u16) -> Result<(), String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .map_err(|e| format!("创建 UDP 连接失败: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("开启广播失败: {}", e))?;
+    let packet = magic_packet(mac);
+    for i in 0..REPEAT {
+        if i > 0 {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        socket
+            .send_to(&packet, (broadcast, port))
+            .map_err(|e| format!("发送唤醒包失败: {}", e))?;
+    }
+    Ok(())
+}
+
+fn wake(device: &WakeDevice, settings: &WakeOnLanConfig) -> WakeResult {
+    let result = parse_mac(&device.mac).and_then(|mac| {
+        let broadcast = device
+            .broadcast
+            .as_deref()
+            .filter(|b| !b.trim().is_empty())
+            .unwrap_or(&settings.broadcast);
+        send(&mac, parse_broadcast(broadcast)?, settings.port)
+    });
+    if let Err(e) = &result {
+        tracing::warn!("唤醒 {} 失败: {}", device.name, e);
+    }
+    WakeResult {
+        name: device.name.clone(),
+        mac: device.mac.clone(),
+        ok: result.is_ok(),
+        error: result.err(),
+    }
+}
+
+fn wake_all(settings: &WakeOnLanConfig) -> Vec<WakeResult> {
+    let results: Vec<WakeResult> = settings
+        .devices
+        .iter()
+        .map(|device| wake(device, settings))
+        .collect();
+    events::record(EventKind::State, "网络唤醒设备", serde_json::json!(results));
+    results
+}
+
+/// 定时任务：开店时唤醒所有设备
+fn open_store_wake(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let settings = app_handle.state::<ConfigStore>().get().wake_on_lan;
+    if settings.devices.is_empty() {
+        return Ok("没有需要唤醒的设备".to_string());
+    }
+    let results = wake_all(&settings);
+    let failed: Vec<String> = results
+        .iter()
+        .filter_map(|r| r.error.as_ref().map(|e| format!("{}: {}", r.name, e)))
+        .collect();
+    if failed.is_empty() {
+        Ok(format!("已唤醒 {} 台设备", results.len()))
+    } else {
+        Err(failed.join("；"))
+    }
+}
+
+/// 登记开店唤醒定时任务（默认不执行）
+pub fn register_tasks() {
+    scheduler::register(
+        "open_store_wake",
+        "开店唤醒网络打印机和收银终端",
+        None,
+        open_store_wake,
+    );
+}
+
+// Tauri 命令
+
+/// 向指定 MAC 地址发送唤醒包，broadcast 为空时使用设置中的默认广播地址
+#[tauri::command]
+pub async fn send_wol(
+    mac: String,
+    broadcast: Option<String>,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<()> {
+    let settings = config.get().wake_on_lan;
+    let bytes = parse_mac(&mac).map_err(AppError::InvalidArgument)?;
+    let broadcast = parse_broadcast(broadcast.as_deref().unwrap_or(&settings.broadcast))
+        .map_err(AppError::InvalidArgument)?;
+    send(&bytes, broadcast, settings.port).map_err(AppError::Network)?;
+    tracing::info!("已发送唤醒包: {}", format_mac(&bytes));
+    Ok(())
+}
+
+/// 列出保存的设备
+#[tauri::command]
+pub fn list_wol_devices(config: tauri::State<'_, ConfigStore>) -> Vec<WakeDevice> {
+    config.get().wake_on_lan.devices
+}
+
+/// 保存设备的 MAC 地址（同名设备覆盖）
+#[tauri::command]
+pub fn save_wol_device(
+    name: String,
+    mac: String,
+    broadcast: Option<String>,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<Vec<WakeDevice>> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::InvalidArgument("设备名称不能为空".to_string()));
+    }
+    let mac = format_mac(&parse_mac(&mac).map_err(AppError::InvalidArgument)?);
+    let broadcast = broadcast.filter(|b| !b.trim().is_empty());
+    if let Some(broadcast) = &broadcast {
+        parse_broadcast(broadcast).map_err(AppError::InvalidArgument)?;
+    }
+
+    let device = WakeDevice {
+        name,
+        mac,
+        broadcast,
+    };
+    let updated = config.update(|c| {
+        let devices = &mut c.wake_on_lan.devices;
+        match devices.iter_mut().find(|d| d.name == device.name) {
+            Some(existing) => *existing = device.clone(),
+            None => devices.push(device.clone()),
+        }
+    })?;
+    tracing::info!("已保存网络唤醒设备: {} ({})", device.name, device.mac);
+    Ok(updated.wake_on_lan.devices)
+}
+
+/// 删除保存的设备
+#[tauri::command]
+pub fn remove_wol_device(
+    name: String,
+    config: tauri::State<'_, ConfigStore>,
+) -> AppResult<Vec<WakeDevice>> {
+    let updated = config.update(|c| c.wake_on_lan.devices.retain(|d| d.name != name))?;
+    Ok(updated.wake_on_lan.devices)
+}
+
+/// 唤醒所有保存的设备
+#[tauri::command]
+pub async fn wake_all_devices(config: tauri::State<'_, ConfigStore>) -> AppResult<Vec<WakeResult>> {
+    Ok(wake_all(&config.get().wake_on_lan))
+}
@@ -0,0 +1,73 @@
+/**
+ * Backend 请求
+ *
+ * Backend 在本机时经壳程序代理（backend_request），由壳程序统计耗时、
+ * 发现慢请求；连接局域网内其他电脑的 Backend 时直接请求。
+ *
+ * 页面统一通过 backendRequest / backendFetch 访问 Backend。代理只能转发 JSON，
+ * 上传文件和表单（FormData、URLSearchParams）仍由 backendFetch 直接请求。
+ */
+
+import { invoke } from '@tauri-apps/api/tauri';
+import { API_BASE_URL, IS_LOCAL_BACKEND } from './config';
+
+export interface BackendResponse<T = any> {
+  status: number;
+  ok: boolean;
+  body: T;
+}
+
+export async function backendRequest<T = any>(
+  method: string,
+  path: string,
+  body?: unknown,
+): Promise<BackendResponse<T>> {
+  if (IS_LOCAL_BACKEND) {
+    const response = await invoke<{ status: number; body: T }>('backend_request', {
+      request: { method, path, body: body ?? null },
+    });
+    return { ...response, ok: response.status >= 200 && response.status < 300 };
+  }
+
+  const response = await fetch(`${API_BASE_URL}${path}`, {
+    method,
+    headers: body === undefined ? undefined : { 'Content-Type': 'application/json' },
+    body: body === undefined ? undefined : JSON.stringify(body),
+  });
+  const text = await response.text();
+  let parsed: any = text;
+  try {
+    parsed = JSON.parse(text);
+  } catch {
+    // 非 JSON 响应保留原文
+  }
+  return { status: response.status, ok: response.ok, body: parsed };
+}
+
+/** backendFetch 的响应（与 fetch 的 Response 用法相同） */
+export interface BackendFetchResponse {
+  ok: boolean;
+  status: number;
+  json(): Promise<any>;
+  text(): Promise<string>;
+}
+
+/** fetch 风格的 Backend 请求，path 为以 / 开头的接口路径（可带查询参数） */
+export async function backendFetch(path: string, init: RequestInit = {}): Promise<BackendFetchResponse> {
+  const body = init.body;
+  if (body instanceof FormData || body instanceof URLSearchParams || body instanceof Blob) {
+    return fetch(`${API_BASE_URL}${path}`, init);
+  }
+
+  const response = await backendRequest(
+    init.method ?? 'GET',
+    path,
+    typeof body === 'string' ? JSON.parse(body) : undefined,
+  );
+  return {
+    ok: response.ok,
+    status: response.status,
+    json: async () => response.body,
+    text: async () => (typeof response.body === 'string' ? response.body : JSON.stringify(response.body)),
+  };
+}
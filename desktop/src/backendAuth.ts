@@ -0,0 +1,41 @@
+/**
+ * Backend 访问 Token
+ *
+ * 壳程序启动的 Backend 只接受带访问 Token 的请求。渲染前通过 get_backend_credentials 取得 Token，
+ * 之后对本机 Backend 的 fetch 请求自动加上 Authorization 头。
+ */
+
+import { invoke } from '@tauri-apps/api/tauri';
+import { API_BASE_URL, BACKEND_TOKEN, IS_LOCAL_BACKEND, setBackendToken } from './config';
+
+interface BackendCredentials {
+  port: number;
+  token: string;
+  lan_mode: boolean;
+}
+
+function isBackendUrl(input: RequestInfo | URL): boolean {
+  const url = input instanceof Request ? input.url : input.toString();
+  return url.startsWith(API_BASE_URL);
+}
+
+export async function installBackendAuth() {
+  if (!IS_LOCAL_BACKEND) return;
+  try {
+    const credentials = await invoke<BackendCredentials>('get_backend_credentials');
+    setBackendToken(credentials.token);
+  } catch (error) {
+    console.warn('读取 Backend 访问 Token 失败', error);
+    return;
+  }
+
+  const originalFetch = window.fetch.bind(window);
+  window.fetch = (input: RequestInfo | URL, init?: RequestInit) => {
+    if (!BACKEND_TOKEN || !isBackendUrl(input)) {
+      return originalFetch(input, init);
+    }
+    const headers = new Headers(init?.headers ?? (input instanceof Request ? input.headers : undefined));
+    headers.set('Authorization', `Bearer ${BACKEND_TOKEN}`);
+    return originalFetch(input, { ...init, headers });
+  };
+}
@@ -1,12 +1,46 @@
 import { ReactNode, useState, useEffect } from 'react';
 import { useNavigate, useLocation } from 'react-router-dom';
-import { API_BASE_URL } from '../config';
+import { listen } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/tauri';
+import { backendFetch } from '../api';
 import './Layout.css';
 
 interface LayoutProps {
   children: ReactNode;
 }
 
+interface VolumeStatus {
+  level: 'ok' | 'notice' | 'warning' | 'critical';
+  message: string;
+  space: { mount_point: string };
+}
+
+interface BatteryStatus {
+  level: 'unknown' | 'normal' | 'low' | 'critical';
+  percent: number | null;
+}
+
+interface BackendStatus {
+  state: 'starting' | 'running' | 'unresponsive' | 'restarting' | 'failed' | 'stopped';
+  supervised: boolean;
+  retry_in_secs: number | null;
+  last_error: string | null;
+}
+
+interface AccessibilitySettings {
+  high_contrast: boolean;
+  reduced_motion: boolean;
+  text_scale: number;
+}
+
+// 按系统辅助功能设置调整界面（样式见 App.css）
+const applyAccessibility = (settings: AccessibilitySettings) => {
+  const root = document.documentElement;
+  root.classList.toggle('a11y-high-contrast', settings.high_contrast);
+  root.classList.toggle('a11y-reduced-motion', settings.reduced_motion);
+  root.style.setProperty('--a11y-text-scale', String(settings.text_scale));
+};
+
 // 所有菜单项配置
 const ALL_MENU_ITEMS = [
   { id: 'dashboard', path: '/', icon: '🏠', label: '仪表盘' },
@@ -33,12 +67,18 @@ const Layout = ({ children }: LayoutProps) => {
   const navigate = useNavigate();
   const location = useLocation();
   const [pageVisibility, setPageVisibility] = useState<Record<string, boolean>>(DEFAULT_VISIBILITY);
+  // 剩余空间严重不足的分区（挂载点 → 提示），显示无法关闭的横幅直到空间恢复
+  const [diskAlerts, setDiskAlerts] = useState<Record<string, string>>({});
+  // 使用电池且电量低时提醒收银员尽快完成交易
+  const [battery, setBattery] = useState<BatteryStatus | null>(null);
+  // Backend 崩溃或无响应时显示正在重新连接
+  const [backendStatus, setBackendStatus] = useState<BackendStatus | null>(null);
 
   // 从后端加载页面可见性设置
   useEffect(() => {
     const loadVisibility = async () => {
       try {
-        const response = await fetch(`${API_BASE_URL}/settings`);
+        const response = await backendFetch(`/settings`);
         if (response.ok) {
           const settings = await response.json();
           if (settings?.page_visibility) {
@@ -68,6 +108,104 @@ const Layout = ({ children }: LayoutProps) => {
     };
   }, []);
 
+  // 拖放文件到窗口：价目表打开商品导入
+  useEffect(() => {
+    const unlisten = listen<{ kind: string; files: { path: string; name: string }[] }>(
+      'import://dropped',
+      (event) => {
+        const { kind, files } = event.payload;
+        if (kind === 'csv' && files.length > 0) {
+          navigate('/products', { state: { droppedCsv: files[0] } });
+        }
+      }
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+
+  // 磁盘空间严重不足
+  useEffect(() => {
+    const apply = (volume: VolumeStatus) => {
+      setDiskAlerts((alerts) => {
+        const next = { ...alerts };
+        if (volume.level === 'critical') {
+          next[volume.space.mount_point] = volume.message;
+        } else {
+          delete next[volume.space.mount_point];
+        }
+        return next;
+      });
+    };
+
+    invoke<VolumeStatus[]>('get_disk_space')
+      .then((volumes) => volumes.forEach(apply))
+      .catch((error) => console.error('检查磁盘空间失败:', error));
+    const unlisten = listen<VolumeStatus>('disk://space', (event) => apply(event.payload));
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 电池电量
+  useEffect(() => {
+    invoke<BatteryStatus>('get_battery_status')
+      .then(setBattery)
+      .catch((error) => console.error('读取电池状态失败:', error));
+    const unlisten = listen<BatteryStatus>('power://battery', (event) => setBattery(event.payload));
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Backend 状态
+  useEffect(() => {
+    invoke<BackendStatus>('get_backend_status')
+      .then(setBackendStatus)
+      .catch((error) => console.error('读取 Backend 状态失败:', error));
+    const unlisten = listen<BackendStatus>('backend-status-changed', (event) =>
+      setBackendStatus(event.payload)
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 系统辅助功能设置
+  useEffect(() => {
+    invoke<AccessibilitySettings>('get_accessibility_settings')
+      .then(applyAccessibility)
+      .catch((error) => console.error('读取辅助功能设置失败:', error));
+    const unlisten = listen<AccessibilitySettings>('accessibility://changed', (event) =>
+      applyAccessibility(event.payload)
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 低配模式下减少动画
+  useEffect(() => {
+    const apply = (mode: { reduce_animations: boolean }) =>
+      document.documentElement.classList.toggle('perf-reduce-motion', mode.reduce_animations);
+
+    invoke<{ reduce_animations: boolean }>('get_performance_mode')
+      .then(apply)
+      .catch((error) => console.error('读取性能模式失败:', error));
+    const unlisten = listen<{ reduce_animations: boolean }>('performance://mode', (event) =>
+      apply(event.payload)
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // 根据可见性设置过滤菜单项（必显示页面始终显示）
   const visibleMenuItems = ALL_MENU_ITEMS.filter(
     (item) => REQUIRED_PAGES.includes(item.id) || pageVisibility[item.id] !== false
@@ -116,6 +254,23 @@ const Layout = ({ children }: LayoutProps) => {
 
       {/* 主内容区 */}
       <main className="main-content">
+        {Object.entries(diskAlerts).map(([mountPoint, message]) => (
+          <div key={mountPoint} className="disk-alert">
+            ⚠️ 磁盘空间严重不足：{message}。数据库可能无法保存订单，请立即清理磁盘。
+          </div>
+        ))}
+        {battery && (battery.level === 'low' || battery.level === 'critical') && (
+          <div className={`battery-alert ${battery.level}`}>
+            🔋 电池电量{battery.level === 'critical' ? '严重不足' : '低'}（{battery.percent ?? '?'}%），请尽快完成当前交易并接通电源
+          </div>
+        )}
+        {backendStatus?.supervised &&
+          ['unresponsive', 'restarting', 'failed'].includes(backendStatus.state) && (
+            <div className="backend-alert">
+              🔄 {backendStatus.state === 'unresponsive' ? '后台服务无响应' : '后台服务已停止'}，正在重新连接
+              {backendStatus.retry_in_secs ? `（${backendStatus.retry_in_secs} 秒后重试）` : '…'}
+            </div>
+          )}
         {children}
       </main>
     </div>
@@ -11,8 +11,41 @@
 const API_HOST = import.meta.env.VITE_API_HOST || "localhost";
 const API_PORT = import.meta.env.VITE_API_PORT || "8000";
 
-export const API_BASE_URL = `http://${API_HOST}:${API_PORT}`;
-export const WS_URL = `ws://${API_HOST}:${API_PORT}/ws`;
+export let API_BASE_URL = `http://${API_HOST}:${API_PORT}`;
+export let WS_URL = `ws://${API_HOST}:${API_PORT}/ws`;
+
+// Backend 是否运行在本机（本机时请求可经壳程序代理，以便统计耗时）
+export const IS_LOCAL_BACKEND = ["localhost", "127.0.0.1"].includes(API_HOST);
+
+// 本机 Backend 且未指定端口时，使用壳程序实际启动 Backend 的端口（默认端口被占用时会改用其他端口）
+export const FOLLOW_BACKEND_PORT = IS_LOCAL_BACKEND && !import.meta.env.VITE_API_PORT;
+
+// 壳程序为每次启动生成的 Backend 访问 Token（连接局域网内其他电脑的 Backend 时不需要）
+export let BACKEND_TOKEN: string | null = null;
+
+let backendPort: string | number = API_PORT;
+
+function updateUrls() {
+  API_BASE_URL = `http://${API_HOST}:${backendPort}`;
+  WS_URL = withAccessToken(`ws://${API_HOST}:${backendPort}/ws`);
+}
+
+export function setBackendPort(port: number) {
+  backendPort = port;
+  updateUrls();
+}
+
+export function setBackendToken(token: string | null) {
+  BACKEND_TOKEN = token;
+  updateUrls();
+}
+
+// 为无法设置请求头的地址（WebSocket、<img>）加上访问 Token
+export function withAccessToken(url: string): string {
+  if (!BACKEND_TOKEN) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}access_token=${encodeURIComponent(BACKEND_TOKEN)}`;
+}
 
 // 设备 ID（用于 WebSocket 连接）
 export const DEVICE_ID = `desktop-${Date.now()}`;
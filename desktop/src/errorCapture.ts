@@ -0,0 +1,84 @@
+/**
+ * 前端错误转发
+ *
+ * 把未捕获的异常、未处理的 Promise 拒绝和 console.error / console.warn
+ * 转发到壳程序日志（log_frontend_error），与 Rust、Backend 日志放在同一时间线。
+ */
+
+import { invoke } from '@tauri-apps/api/tauri';
+
+type Level = 'error' | 'warn' | 'info';
+
+interface FrontendErrorPayload {
+  level: Level;
+  kind: string;
+  message: string;
+  stack?: string;
+  source?: string;
+  line?: number;
+  column?: number;
+  route?: string;
+}
+
+const originalConsole = {
+  error: console.error.bind(console),
+  warn: console.warn.bind(console),
+};
+
+function describe(value: unknown): string {
+  if (value instanceof Error) {
+    return `${value.name}: ${value.message}`;
+  }
+  if (typeof value === 'string') {
+    return value;
+  }
+  try {
+    return JSON.stringify(value);
+  } catch {
+    return String(value);
+  }
+}
+
+function forward(payload: Omit<FrontendErrorPayload, 'route'>) {
+  const route = window.location.pathname + window.location.hash;
+  invoke('log_frontend_error', { payload: { ...payload, route } }).catch((error) => {
+    // 转发失败时只输出到控制台，避免递归
+    originalConsole.warn('转发前端日志失败:', error);
+  });
+}
+
+export function installErrorForwarding() {
+  window.addEventListener('error', (event) => {
+    forward({
+      level: 'error',
+      kind: 'error',
+      message: event.message || describe(event.error),
+      stack: event.error instanceof Error ? event.error.stack : undefined,
+      source: event.filename || undefined,
+      line: event.lineno || undefined,
+      column: event.colno || undefined,
+    });
+  });
+
+  window.addEventListener('unhandledrejection', (event) => {
+    forward({
+      level: 'error',
+      kind: 'unhandledrejection',
+      message: describe(event.reason),
+      stack: event.reason instanceof Error ? event.reason.stack : undefined,
+    });
+  });
+
+  (['error', 'warn'] as const).forEach((level) => {
+    console[level] = (...args: unknown[]) => {
+      originalConsole[level](...args);
+      const error = args.find((arg): arg is Error => arg instanceof Error);
+      forward({
+        level,
+        kind: 'console',
+        message: args.map(describe).join(' '),
+        stack: error?.stack,
+      });
+    };
+  });
+}
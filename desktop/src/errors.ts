@@ -0,0 +1,67 @@
+/**
+ * 壳程序命令错误
+ *
+ * Tauri 命令失败时返回 { code, message_key, detail }：
+ * - code：稳定的错误码，用于分支处理（例如 BACKEND_UNAVAILABLE）
+ * - message_key：本地化文案的键
+ * - detail：原始错误信息，便于排查
+ */
+
+export interface AppError {
+  code: string;
+  message_key: string;
+  detail: string;
+}
+
+/** 错误码（与壳程序 AppError::code 一致） */
+export const ErrorCode = {
+  BackendUnavailable: 'BACKEND_UNAVAILABLE',
+  BackendStartFailed: 'BACKEND_START_FAILED',
+  InvalidArgument: 'INVALID_ARGUMENT',
+  Config: 'CONFIG_ERROR',
+  Io: 'IO_ERROR',
+  Network: 'NETWORK_ERROR',
+  NotInitialized: 'NOT_INITIALIZED',
+  Autostart: 'AUTOSTART_FAILED',
+  CrashReporting: 'CRASH_REPORTING_FAILED',
+  Internal: 'INTERNAL',
+} as const;
+
+/** message_key 对应的文案 */
+const MESSAGES: Record<string, string> = {
+  'error.backend_unavailable': '无法连接 Backend 服务，请稍后重试',
+  'error.backend_start_failed': 'Backend 服务启动失败',
+  'error.invalid_argument': '参数无效',
+  'error.config_error': '读写配置失败',
+  'error.io_error': '读写文件失败',
+  'error.network_error': '网络连接失败',
+  'error.not_initialized': '功能尚未就绪，请稍后重试',
+  'error.autostart_failed': '设置开机自启动失败',
+  'error.crash_reporting_failed': '设置错误上报失败',
+  'error.internal': '内部错误',
+};
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    'code' in error &&
+    'detail' in error
+  );
+}
+
+/** 取出可显示的错误信息：按 message_key 显示文案并附上 detail，没有对应文案时显示 detail */
+export function errorMessage(error: unknown): string {
+  if (!isAppError(error)) {
+    return String(error);
+  }
+  const message = MESSAGES[error.message_key];
+  if (!message) {
+    return error.detail || error.code;
+  }
+  // 参数错误的 detail 本身就是给用户看的说明
+  if (error.code === ErrorCode.InvalidArgument && error.detail) {
+    return error.detail;
+  }
+  return error.detail ? `${message}（${error.detail}）` : message;
+}
@@ -1,12 +1,54 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
 import App from "./App";
+import { installBackendAuth } from "./backendAuth";
+import { FOLLOW_BACKEND_PORT, setBackendPort } from "./config";
+import { installErrorForwarding } from "./errorCapture";
 import "./App.css";
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>,
-);
+installErrorForwarding();
+
+// Backend 就绪（或启动超时）后再渲染，避免首批请求失败
+async function waitForBackend() {
+  try {
+    let resolve: () => void = () => {};
+    const settled = new Promise<void>((r) => (resolve = r));
+    // 先订阅再查询状态，避免错过查询期间发出的事件
+    const unlisten = await Promise.all([
+      listen("backend-ready", () => resolve()),
+      listen("backend-error", () => resolve()),
+    ]);
+    const state = await invoke<{ state: string }>("get_startup_state");
+    if (state.state !== "waiting") resolve();
+    await settled;
+    unlisten.forEach((stop) => stop());
+  } catch (error) {
+    console.warn("读取 Backend 启动状态失败", error);
+  }
+}
+
+// 渲染前取得 Backend 的实际端口，端口变化（重启后改用其他端口）时更新
+async function resolveBackendPort() {
+  if (!FOLLOW_BACKEND_PORT) return;
+  try {
+    setBackendPort(await invoke<number>("get_backend_port"));
+    await listen<number>("backend://port", (event) => setBackendPort(event.payload));
+  } catch (error) {
+    console.warn("读取 Backend 端口失败，使用默认端口", error);
+  }
+}
+
+waitForBackend()
+  .then(resolveBackendPort)
+  .then(installBackendAuth)
+  .finally(() => {
+    ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+      <React.StrictMode>
+        <App />
+      </React.StrictMode>,
+    );
+  });
 
 
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { API_BASE_URL } from '../config';
+import { backendFetch } from '../api';
 import './Analysis.css';
 
 interface RestockSuggestion {
@@ -54,8 +54,8 @@ export default function Analysis() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/analysis/restock_suggestion?days=${days}&safety_stock_days=${safetyStockDays}`
+      const response = await backendFetch(
+        `/analysis/restock_suggestion?days=${days}&safety_stock_days=${safetyStockDays}`
       );
       if (response.ok) {
         const data = await response.json();
@@ -74,8 +74,8 @@ export default function Analysis() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/analysis/anomaly_detection?days=${days}&threshold_std=2.0`
+      const response = await backendFetch(
+        `/analysis/anomaly_detection?days=${days}&threshold_std=2.0`
       );
       if (response.ok) {
         const data = await response.json();
@@ -1,6 +1,9 @@
 // 将原来的 App.tsx 内容移到这里，作为收银页面
 import { useState, useEffect, useRef, useCallback } from "react";
-import { API_BASE_URL, WS_URL, DEVICE_ID } from "../config";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
+import { WS_URL, DEVICE_ID } from "../config";
+import { backendFetch, backendRequest } from "../api";
 import "./Cashier.css";
 
 interface Product {
@@ -256,6 +259,64 @@ const Cashier = () => {
     };
   }, []);
 
+  // 系统唤醒后旧连接可能已失效，立即重连
+  useEffect(() => {
+    const unlisten = listen("power://resume", () => {
+      console.log("🔄 系统已唤醒，重新连接 WebSocket");
+      reconnectAttempts.current = 0;
+      if (reconnectTimeoutRef.current) {
+        clearTimeout(reconnectTimeoutRef.current);
+        reconnectTimeoutRef.current = null;
+      }
+      if (wsRef.current) {
+        // onclose 中会自动重连
+        wsRef.current.close();
+      } else {
+        connectWebSocket();
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 全局快捷键（在设置中绑定，由壳程序转发）
+  useEffect(() => {
+    const unlisten = listen<{ action: string }>("shortcut://triggered", (event) => {
+      switch (event.payload.action) {
+        case "checkout":
+          if (cart.length > 0) {
+            setShowConfirmModal(true);
+          }
+          break;
+        case "focus_search":
+          manualInputRef.current?.focus();
+          break;
+        case "manual_add":
+          setShowManualAddModal(true);
+          break;
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [cart.length]);
+
+  // 对话框或其他程序在前台时，扫码枪输入由壳程序识别后转发（设置中开启扫码枪全局识别）
+  useEffect(() => {
+    const unlisten = listen<{ code: string; suppressed: boolean; foreground: boolean }>("scanner://scan", (event) => {
+      // 主窗口在前台时按键照常输入，由下面的扫码枪监听处理
+      if (event.payload.foreground && !event.payload.suppressed) return;
+      handleScan(event.payload.code);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [cart.length]);
+
   // 扫码枪监听 + Enter 提交订单
   useEffect(() => {
     const handleKeyPress = (e: KeyboardEvent) => {
@@ -322,13 +383,16 @@ const Cashier = () => {
   }, [showConfirmModal]);
 
   const handleScan = async (query: string) => {
+    // 记录到壳程序事件历史，便于事后排查
+    invoke("record_event", { kind: "scan", message: query, data: null }).catch(() => {});
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/products/search?q=${encodeURIComponent(query)}`
+      const response = await backendRequest(
+        "GET",
+        `/products/search?q=${encodeURIComponent(query)}`
       );
       
       if (response.ok) {
-        const data = await response.json();
+        const data = response.body;
         
         if (data.type === 'exact' && data.products.length === 1) {
           // 精确匹配（条码），直接添加
@@ -450,7 +514,7 @@ const Cashier = () => {
     const totalAmount = getTotalAmount();
 
     try {
-      const response = await fetch(`${API_BASE_URL}/orders/create`, {
+      const response = await backendFetch(`/orders/create`, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify({
@@ -1,5 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
-import { API_BASE_URL } from '../config';
+import { invoke } from '@tauri-apps/api/tauri';
+import { backendFetch } from '../api';
+import { errorMessage, isAppError } from '../errors';
 import './Database.css';
 
 interface TableInfo {
@@ -39,7 +41,7 @@ const Database = () => {
   const loadTables = useCallback(async () => {
     setLoading(true);
     try {
-      const response = await fetch(`${API_BASE_URL}/database/tables`);
+      const response = await backendFetch(`/database/tables`);
       if (response.ok) {
         const data = await response.json();
         setTables(data.tables || []);
@@ -54,7 +56,7 @@ const Database = () => {
   // 加载统计信息
   const loadStats = useCallback(async () => {
     try {
-      const response = await fetch(`${API_BASE_URL}/database/stats`);
+      const response = await backendFetch(`/database/stats`);
       if (response.ok) {
         const data = await response.json();
         setStats(data);
@@ -71,8 +73,8 @@ const Database = () => {
     setLoadingData(true);
     try {
       const skip = (page - 1) * pageSize;
-      const response = await fetch(
-        `${API_BASE_URL}/database/tables/${tableName}?skip=${skip}&limit=${pageSize}`
+      const response = await backendFetch(
+        `/database/tables/${tableName}?skip=${skip}&limit=${pageSize}`
       );
       if (response.ok) {
         const data = await response.json();
@@ -141,8 +143,8 @@ const Database = () => {
     if (!confirmed) return;
 
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/database/tables/${selectedTable}/records?ids=${selectedIds.join(',')}`,
+      const response = await backendFetch(
+        `/database/tables/${selectedTable}/records?ids=${selectedIds.join(',')}`,
         { method: 'DELETE' }
       );
 
@@ -170,8 +172,8 @@ const Database = () => {
     }
 
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/database/tables/${selectedTable}/clear?confirm=CONFIRM_CLEAR`,
+      const response = await backendFetch(
+        `/database/tables/${selectedTable}/clear?confirm=CONFIRM_CLEAR`,
         { method: 'DELETE' }
       );
 
@@ -196,41 +198,37 @@ const Database = () => {
   // 导出表数据
   const handleExport = async (format: 'json' | 'csv') => {
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/database/tables/${selectedTable}/export?format=${format}`
+      const response = await backendFetch(
+        `/database/tables/${selectedTable}/export?format=${format}`
       );
 
       if (response.ok) {
         const result = await response.json();
         
-        let content: string;
-        let mimeType: string;
-        
-        if (format === 'csv') {
-          content = result.content;
-          mimeType = 'text/csv';
-        } else {
-          content = JSON.stringify(result.data, null, 2);
-          mimeType = 'application/json';
+        const content = format === 'csv'
+          ? result.content
+          : JSON.stringify(result.data, null, 2);
+
+        // 选择保存位置
+        const path = await invoke<string | null>('save_file', {
+          defaultName: result.filename,
+          filters: [format === 'csv'
+            ? { name: 'CSV 文件', extensions: ['csv'] }
+            : { name: 'JSON 文件', extensions: ['json'] }],
+        });
+        if (!path) return;
+
+        await invoke('save_text_file', { path, content });
+        if (confirm(`已导出到 ${path}\n\n是否立即打开？`)) {
+          await invoke('open_path', { path });
         }
-
-        // 创建下载链接
-        const blob = new Blob([content], { type: mimeType });
-        const url = URL.createObjectURL(blob);
-        const a = document.createElement('a');
-        a.href = url;
-        a.download = result.filename;
-        document.body.appendChild(a);
-        a.click();
-        document.body.removeChild(a);
-        URL.revokeObjectURL(url);
       } else {
         const error = await response.json();
         alert(`导出失败: ${error.detail || '未知错误'}`);
       }
     } catch (error) {
       console.error('导出失败:', error);
-      alert('导出失败，请检查网络连接');
+      alert(isAppError(error) ? `导出失败: ${errorMessage(error)}` : '导出失败，请检查网络连接');
     }
   };
 
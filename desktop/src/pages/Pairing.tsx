@@ -1,19 +1,21 @@
 import { useState, useEffect } from 'react';
-import { API_BASE_URL } from '../config';
+import { invoke } from '@tauri-apps/api/tauri';
 import QRCode from 'qrcode';
+import { errorMessage } from '../errors';
 import './Pairing.css';
 
+// 壳程序生成的配对码（二维码中的端口为 Backend 实际使用的端口）
 interface PairingInfo {
   http_url: string;
   ws_url: string;
   token: string;
   expires_in: number;
-  local_ip: string;
-  all_ips: string[];
+  address: string;
+  addresses: string[];
+  qr_payload: string;
 }
 
 interface DeviceInfo {
-  id: number;
   device_id: string;
   device_type: string | null;
   device_name: string | null;
@@ -25,6 +27,8 @@ interface DeviceInfo {
 export default function Pairing() {
   const [pairingInfo, setPairingInfo] = useState<PairingInfo | null>(null);
   const [selectedIp, setSelectedIp] = useState<string>('');
+  // 未指定地址时壳程序选择的地址
+  const [recommendedIp, setRecommendedIp] = useState<string>('');
   const [qrCodeUrl, setQrCodeUrl] = useState<string>('');
   const [countdown, setCountdown] = useState<number>(0);
   const [loading, setLoading] = useState(false);
@@ -48,11 +52,7 @@ export default function Pairing() {
   async function fetchDevices() {
     setDevicesLoading(true);
     try {
-      const response = await fetch(`${API_BASE_URL}/pairing/devices`);
-      if (response.ok) {
-        const data = await response.json();
-        setDevices(data);
-      }
+      setDevices(await invoke<DeviceInfo[]>('list_paired_devices'));
     } catch (err) {
       console.error('获取设备列表失败:', err);
     } finally {
@@ -63,19 +63,12 @@ export default function Pairing() {
   // 删除设备
   async function deleteDevice(deviceId: string) {
     try {
-      const response = await fetch(`${API_BASE_URL}/pairing/devices/${deviceId}`, {
-        method: 'DELETE'
-      });
-      if (response.ok) {
-        setDevices(devices.filter(d => d.device_id !== deviceId));
-        setDeleteConfirm(null);
-      } else {
-        const data = await response.json();
-        alert(data.detail || '删除失败');
-      }
+      await invoke('revoke_paired_device', { deviceId });
+      setDevices(devices.filter(d => d.device_id !== deviceId));
+      setDeleteConfirm(null);
     } catch (err) {
       console.error('删除设备失败:', err);
-      alert('删除设备失败');
+      alert(`删除设备失败: ${errorMessage(err)}`);
     }
   }
 
@@ -113,78 +106,39 @@ export default function Pairing() {
       return () => clearTimeout(timer);
     } else if (countdown === 0 && pairingInfo) {
       // Token 过期，重新生成
-      generatePairingCode();
+      generatePairingCode(selectedIp);
     }
   }, [countdown]);
 
-  // 当选中的 IP 变化时，重新生成二维码
-  useEffect(() => {
-    if (pairingInfo && selectedIp) {
-      updateQrCode(selectedIp, pairingInfo.token);
-    }
-  }, [selectedIp]);
-
-  async function updateQrCode(ip: string, token: string) {
-    const port = 8000;
-    const qrData = JSON.stringify({
-      http_url: `http://${ip}:${port}`,
-      ws_url: `ws://${ip}:${port}/ws`,
-      token: token,
-      type: 'smartmart_pairing'
-    });
-
-    const qrDataUrl = await QRCode.toDataURL(qrData, {
-      width: 300,
-      margin: 2,
-      color: {
-        dark: '#000000',
-        light: '#FFFFFF'
-      }
-    });
-
-    setQrCodeUrl(qrDataUrl);
-  }
-
-  async function generatePairingCode() {
+  async function generatePairingCode(address?: string) {
     setLoading(true);
     setError('');
 
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/pairing/generate_pairing_code?validity_seconds=300`,
-        { method: 'POST' }
-      );
-
-      if (response.ok) {
-        const data: PairingInfo = await response.json();
-        setPairingInfo(data);
-        setSelectedIp(data.local_ip);
-        setCountdown(data.expires_in);
-
-        // 生成二维码数据
-        const qrData = JSON.stringify({
-          http_url: data.http_url,
-          ws_url: data.ws_url,
-          token: data.token,
-          type: 'smartmart_pairing'
-        });
-
-        // 生成二维码图片
-        const qrDataUrl = await QRCode.toDataURL(qrData, {
-          width: 300,
-          margin: 2,
-          color: {
-            dark: '#000000',
-            light: '#FFFFFF'
-          }
-        });
-
-        setQrCodeUrl(qrDataUrl);
-      } else {
-        throw new Error('生成配对码失败');
+      const data = await invoke<PairingInfo>('create_pairing_code', {
+        address,
+        validitySecs: 300,
+      });
+      setPairingInfo(data);
+      setSelectedIp(data.address);
+      if (!address) {
+        setRecommendedIp(data.address);
       }
-    } catch (err: any) {
-      setError(err.message);
+      setCountdown(data.expires_in);
+
+      // 生成二维码图片
+      const qrDataUrl = await QRCode.toDataURL(data.qr_payload, {
+        width: 300,
+        margin: 2,
+        color: {
+          dark: '#000000',
+          light: '#FFFFFF'
+        }
+      });
+
+      setQrCodeUrl(qrDataUrl);
+    } catch (err) {
+      setError(`生成配对码失败: ${errorMessage(err)}`);
     } finally {
       setLoading(false);
     }
@@ -214,7 +168,7 @@ export default function Pairing() {
         {error && (
           <div className="error-box">
             <p>❌ {error}</p>
-            <button onClick={generatePairingCode}>重试</button>
+            <button onClick={() => generatePairingCode()}>重试</button>
           </div>
         )}
 
@@ -236,7 +190,7 @@ export default function Pairing() {
                 )}
               </div>
 
-              <button className="refresh-btn" onClick={generatePairingCode}>
+              <button className="refresh-btn" onClick={() => generatePairingCode(selectedIp)}>
                 🔄 刷新二维码
               </button>
             </div>
@@ -247,20 +201,20 @@ export default function Pairing() {
               <div className="info-item">
                 <div className="info-label">选择本机 IP（点击切换）</div>
                 <div className="ip-selector">
-                  {pairingInfo.all_ips && pairingInfo.all_ips.length > 0 ? (
+                  {pairingInfo.addresses.length > 1 ? (
                     // 推荐的 IP 放到最前面
-                    [...pairingInfo.all_ips].sort((a, b) => {
-                      if (a === pairingInfo.local_ip) return -1;
-                      if (b === pairingInfo.local_ip) return 1;
+                    [...pairingInfo.addresses].sort((a, b) => {
+                      if (a === recommendedIp) return -1;
+                      if (b === recommendedIp) return 1;
                       return 0;
                     }).map(ip => (
                       <button
                         key={ip}
                         className={`ip-option ${ip === selectedIp ? 'active' : ''}`}
-                        onClick={() => setSelectedIp(ip)}
+                        onClick={() => ip !== selectedIp && generatePairingCode(ip)}
                       >
                         {ip}
-                        {ip === pairingInfo.local_ip && <span className="recommended">推荐</span>}
+                        {ip === recommendedIp && <span className="recommended">推荐</span>}
                       </button>
                     ))
                   ) : (
@@ -271,12 +225,12 @@ export default function Pairing() {
 
               <div className="info-item">
                 <div className="info-label">HTTP 地址</div>
-                <div className="info-value code">http://{selectedIp}:8000</div>
+                <div className="info-value code">{pairingInfo.http_url}</div>
               </div>
 
               <div className="info-item">
                 <div className="info-label">WebSocket 地址</div>
-                <div className="info-value code">ws://{selectedIp}:8000/ws</div>
+                <div className="info-value code">{pairingInfo.ws_url}</div>
               </div>
 
               <div className="info-item">
@@ -1,5 +1,9 @@
 import { useState, useEffect, useRef, useCallback } from 'react';
+import { useLocation, useNavigate } from 'react-router-dom';
+import { invoke } from '@tauri-apps/api/tauri';
 import { API_BASE_URL } from '../config';
+import { backendFetch } from '../api';
+import { ErrorCode, errorMessage, isAppError } from '../errors';
 import './Products.css';
 
 interface Product {
@@ -14,6 +18,14 @@ interface Product {
   created_at?: string;
 }
 
+/** 导入失败的提示：Backend 不可用时提示稍后重新导入，其他错误显示原因 */
+function importErrorMessage(error: unknown): string {
+  if (isAppError(error) && error.code === ErrorCode.BackendUnavailable) {
+    return `Backend 服务暂时不可用，请稍后重新导入（${error.detail}）`;
+  }
+  return errorMessage(error);
+}
+
 const Products = () => {
   const [products, setProducts] = useState<Product[]>([]);
   const [loading, setLoading] = useState(false);
@@ -106,8 +118,8 @@ const Products = () => {
     
     try {
       // 使用搜索接口查找商品
-      const response = await fetch(
-        `${API_BASE_URL}/products/search?q=${encodeURIComponent(barcode)}`
+      const response = await backendFetch(
+        `/products/search?q=${encodeURIComponent(barcode)}`
       );
       
       if (response.ok) {
@@ -333,7 +345,7 @@ const Products = () => {
 
   const loadCategories = async () => {
     try {
-      const response = await fetch(`${API_BASE_URL}/products/categories`);
+      const response = await backendFetch(`/products/categories`);
       if (response.ok) {
         const data = await response.json();
         setCategories(data.categories || []);
@@ -347,12 +359,12 @@ const Products = () => {
     setLoading(true);
     try {
       const skip = (page - 1) * pageSize;
-      let url = `${API_BASE_URL}/products/?skip=${skip}&limit=${pageSize}`;
+      let path = `/products/?skip=${skip}&limit=${pageSize}`;
       if (selectedCategory) {
-        url += `&category=${encodeURIComponent(selectedCategory)}`;
+        path += `&category=${encodeURIComponent(selectedCategory)}`;
       }
       
-      const response = await fetch(url);
+      const response = await backendFetch(path);
       
       if (response.ok) {
         const data = await response.json();
@@ -396,8 +408,8 @@ const Products = () => {
     setLoading(true);
     try {
       // 使用新的搜索接口（支持条码和名称）
-      const response = await fetch(
-        `${API_BASE_URL}/products/search?q=${encodeURIComponent(searchQuery)}`
+      const response = await backendFetch(
+        `/products/search?q=${encodeURIComponent(searchQuery)}`
       );
       
       if (response.ok) {
@@ -444,7 +456,7 @@ const Products = () => {
       if (selectedImage) {
         const imageFormData = new FormData();
         imageFormData.append('file', selectedImage);
-        const uploadResponse = await fetch(`${API_BASE_URL}/products/upload_image`, {
+        const uploadResponse = await backendFetch(`/products/upload_image`, {
           method: 'POST',
           body: imageFormData,
         });
@@ -471,7 +483,7 @@ const Products = () => {
         params.append('image_url', uploadedImageUrl);
       }
 
-      const response = await fetch(`${API_BASE_URL}/products/`, {
+      const response = await backendFetch(`/products/`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/x-www-form-urlencoded' },
         body: params,
@@ -529,7 +541,7 @@ const Products = () => {
     }
 
     try {
-      const response = await fetch(`${API_BASE_URL}/products/${productId}`, {
+      const response = await backendFetch(`/products/${productId}`, {
         method: 'DELETE',
       });
 
@@ -563,7 +575,7 @@ const Products = () => {
       if (selectedImage) {
         const imageFormData = new FormData();
         imageFormData.append('file', selectedImage);
-        const uploadResponse = await fetch(`${API_BASE_URL}/products/upload_image`, {
+        const uploadResponse = await backendFetch(`/products/upload_image`, {
           method: 'POST',
           body: imageFormData,
         });
@@ -589,7 +601,7 @@ const Products = () => {
         formData.append('image_url', uploadedImageUrl);
       }
 
-      const response = await fetch(`${API_BASE_URL}/products/${editingProduct.id}`, {
+      const response = await backendFetch(`/products/${editingProduct.id}`, {
         method: 'PUT',
         body: formData,
       });
@@ -612,36 +624,38 @@ const Products = () => {
     }
   };
 
-  const handleImportCSV = () => {
-    const input = document.createElement('input');
-    input.type = 'file';
-    input.accept = '.csv';
-    input.onchange = async (e: any) => {
-      const file = e.target.files[0];
-      if (!file) return;
-
-      const formData = new FormData();
-      formData.append('file', file);
-
-      try {
-        const response = await fetch(`${API_BASE_URL}/products/import_csv`, {
-          method: 'POST',
-          body: formData,
-        });
+  // 拖放到窗口的价目表（由 Layout 跳转过来）
+  const location = useLocation();
+  const navigate = useNavigate();
+  useEffect(() => {
+    const dropped = (location.state as { droppedCsv?: { path: string; name: string } } | null)?.droppedCsv;
+    if (!dropped) return;
+    // 清除跳转状态，避免刷新后重复导入
+    navigate(location.pathname, { replace: true, state: null });
+
+    if (!confirm(`导入商品文件 ${dropped.name}？`)) return;
+    invoke<{ imported_count: number }>('import_dropped_csv', { path: dropped.path })
+      .then((result) => {
+        alert(`成功导入 ${result.imported_count} 个商品！`);
+        loadAllProducts(1);
+      })
+      .catch((error) => alert(importErrorMessage(error)));
+  }, [location.state]);
+
+  const handleImportCSV = async () => {
+    const path = await invoke<string | null>('pick_file', {
+      filters: [{ name: 'CSV 文件', extensions: ['csv'] }],
+    });
+    if (!path) return;
 
-        if (response.ok) {
-          const result = await response.json();
-          alert(`成功导入 ${result.imported_count} 个商品！`);
-        } else {
-          const error = await response.json();
-          alert(`导入失败: ${error.detail || '未知错误'}`);
-        }
-      } catch (error) {
-        console.error('导入失败:', error);
-        alert('导入失败，请检查网络连接');
-      }
-    };
-    input.click();
+    try {
+      const result = await invoke<{ imported_count: number }>('import_dropped_csv', { path });
+      alert(`成功导入 ${result.imported_count} 个商品！`);
+      loadAllProducts(1);
+    } catch (error) {
+      console.error('导入失败:', error);
+      alert(importErrorMessage(error));
+    }
   };
 
   return (
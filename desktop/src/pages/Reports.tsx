@@ -1,5 +1,7 @@
 import { useState, useEffect } from 'react';
-import { API_BASE_URL } from '../config';
+import { invoke } from '@tauri-apps/api/tauri';
+import { backendFetch } from '../api';
+import { errorMessage } from '../errors';
 import './Reports.css';
 
 // ===================== 类型定义 =====================
@@ -170,7 +172,7 @@ export default function Reports() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(`${API_BASE_URL}/reports/sales_daily?date=${selectedDate}`);
+      const response = await backendFetch(`/reports/sales_daily?date=${selectedDate}`);
       if (response.ok) {
         setDailyReport(await response.json());
       } else {
@@ -183,11 +185,22 @@ export default function Reports() {
     }
   }
 
+  async function exportDailyPdf() {
+    try {
+      const result = await invoke<{ path: string }>('render_report_pdf', { date: selectedDate });
+      if (confirm(`日结报表已保存到:\n${result.path}\n\n是否立即打开？`)) {
+        await invoke('open_path', { path: result.path });
+      }
+    } catch (error) {
+      alert(`导出 PDF 失败: ${errorMessage(error)}`);
+    }
+  }
+
   async function fetchTopProducts() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(`${API_BASE_URL}/reports/top_products?days=${days}`);
+      const response = await backendFetch(`/reports/top_products?days=${days}`);
       if (response.ok) {
         setTopProducts(await response.json());
       } else {
@@ -204,7 +217,7 @@ export default function Reports() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(`${API_BASE_URL}/reports/slow_movers?days=${days}&min_stock=0`);
+      const response = await backendFetch(`/reports/slow_movers?days=${days}&min_stock=0`);
       if (response.ok) {
         setSlowMovers(await response.json());
       } else {
@@ -221,13 +234,13 @@ export default function Reports() {
     setLoading(true);
     setError('');
     try {
-      let url = `${API_BASE_URL}/reports/profit?include_no_cost=${includeNoCost}`;
+      let path = `/reports/profit?include_no_cost=${includeNoCost}`;
       if (profitDateMode === 'custom') {
-        url += `&start_date=${profitStartDate}&end_date=${profitEndDate}`;
+        path += `&start_date=${profitStartDate}&end_date=${profitEndDate}`;
       } else {
-        url += `&days=${days}`;
+        path += `&days=${days}`;
       }
-      const response = await fetch(url);
+      const response = await backendFetch(path);
       if (response.ok) {
         setProfitReport(await response.json());
       } else {
@@ -244,8 +257,8 @@ export default function Reports() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/analysis/restock_suggestion?days=${days}&safety_stock_days=${safetyStockDays}`
+      const response = await backendFetch(
+        `/analysis/restock_suggestion?days=${days}&safety_stock_days=${safetyStockDays}`
       );
       if (response.ok) {
         setRestockSuggestions(await response.json());
@@ -263,8 +276,8 @@ export default function Reports() {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch(
-        `${API_BASE_URL}/analysis/anomaly_detection?days=${days}&threshold_std=2.0`
+      const response = await backendFetch(
+        `/analysis/anomaly_detection?days=${days}&threshold_std=2.0`
       );
       if (response.ok) {
         setAnomalies(await response.json());
@@ -357,6 +370,9 @@ export default function Reports() {
             onChange={(e) => setSelectedDate(e.target.value)}
             max={getTodayDate()}
           />
+          <button className="quick-btn" onClick={exportDailyPdf}>
+            导出 PDF
+          </button>
         </div>
 
         <div className="big-stats">
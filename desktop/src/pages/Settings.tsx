@@ -1,6 +1,7 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
-import { API_BASE_URL } from '../config';
+import { backendFetch } from '../api';
+import { errorMessage } from '../errors';
 import './Settings.css';
 
 // 页面配置 - 收银台和系统设置为必显示
@@ -82,6 +83,22 @@ const Settings = () => {
   const [autostartEnabled, setAutostartEnabled] = useState(false);
   const [autostartLoading, setAutostartLoading] = useState(true);
   const [saving, setSaving] = useState(false);
+
+  // 错误上报状态
+  const [crashReportingEnabled, setCrashReportingEnabled] = useState(false);
+  const [crashUploadConsent, setCrashUploadConsent] = useState<'ask' | 'always' | 'never'>('ask');
+
+  // 使用统计状态
+  const [telemetryEnabled, setTelemetryEnabled] = useState(false);
+
+  // 导入热文件夹
+  const [importFolder, setImportFolder] = useState<string | null>(null);
+
+  // 扫码枪全局识别
+  const [scannerStatus, setScannerStatus] = useState<{ supported: boolean; enabled: boolean; running: boolean; error: string | null } | null>(null);
+  
+  // 性能模式
+  const [performanceMode, setPerformanceMode] = useState<{ level: 'auto' | 'standard' | 'low'; effective: 'standard' | 'low'; hardware_acceleration: boolean; restart_required: boolean } | null>(null);
   
   // 消息提示
   const [message, setMessage] = useState<{ type: 'success' | 'error'; text: string } | null>(null);
@@ -90,13 +107,18 @@ const Settings = () => {
   useEffect(() => {
     loadSettings();
     checkAutostartStatus();
+    checkCrashReportingStatus();
+    checkTelemetryStatus();
+    checkImportFolder();
+    checkScannerStatus();
+    checkPerformanceMode();
   }, []);
 
   // 从后端 API 加载设置
   const loadSettings = async () => {
     try {
       setSettingsLoading(true);
-      const response = await fetch(`${API_BASE_URL}/settings`);
+      const response = await backendFetch(`/settings`);
       if (response.ok) {
         const data = await response.json();
         setSettings(data);
@@ -113,7 +135,7 @@ const Settings = () => {
   // 保存设置到后端
   const saveSettings = async (newSettings: Partial<SettingsData>) => {
     try {
-      const response = await fetch(`${API_BASE_URL}/settings`, {
+      const response = await backendFetch(`/settings`, {
         method: 'PUT',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify(newSettings),
@@ -157,7 +179,153 @@ const Settings = () => {
         showMessage('success', '已启用开机自启动');
       }
     } catch (error) {
-      showMessage('error', `设置失败: ${error}`);
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const checkCrashReportingStatus = async () => {
+    try {
+      const enabled = await invoke<boolean>('get_crash_reporting');
+      setCrashReportingEnabled(enabled);
+      const consent = await invoke<'ask' | 'always' | 'never'>('get_crash_upload_consent');
+      setCrashUploadConsent(consent);
+    } catch (error) {
+      console.error('获取错误上报状态失败:', error);
+    }
+  };
+
+  const toggleCrashReporting = async () => {
+    setSaving(true);
+    try {
+      await invoke('set_crash_reporting', { enabled: !crashReportingEnabled });
+      setCrashReportingEnabled(!crashReportingEnabled);
+      showMessage('success', crashReportingEnabled ? '已关闭错误上报' : '已开启错误上报');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const toggleCrashUpload = async () => {
+    const consent = crashUploadConsent === 'always' ? 'ask' : 'always';
+    setSaving(true);
+    try {
+      await invoke('set_crash_upload_consent', { consent });
+      setCrashUploadConsent(consent);
+      showMessage('success', consent === 'always' ? '崩溃报告将自动上传' : '发生崩溃后将询问是否上传');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const checkTelemetryStatus = async () => {
+    try {
+      const enabled = await invoke<boolean>('get_telemetry_enabled');
+      setTelemetryEnabled(enabled);
+    } catch (error) {
+      console.error('获取使用统计状态失败:', error);
+    }
+  };
+
+  const toggleTelemetry = async () => {
+    setSaving(true);
+    try {
+      await invoke('set_telemetry_enabled', { enabled: !telemetryEnabled });
+      setTelemetryEnabled(!telemetryEnabled);
+      showMessage('success', telemetryEnabled ? '已关闭使用统计' : '已开启使用统计');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const previewTelemetry = async () => {
+    try {
+      const payload = await invoke<unknown>('preview_telemetry');
+      alert(JSON.stringify(payload, null, 2));
+    } catch (error) {
+      showMessage('error', `获取统计内容失败: ${errorMessage(error)}`);
+    }
+  };
+
+  const checkImportFolder = async () => {
+    try {
+      const status = await invoke<{ enabled: boolean; path: string | null }>('get_import_folder_status');
+      setImportFolder(status.enabled ? status.path : null);
+    } catch (error) {
+      console.error('获取热文件夹设置失败:', error);
+    }
+  };
+
+  const chooseImportFolder = async () => {
+    try {
+      const path = await invoke<string | null>('pick_folder', { directory: importFolder });
+      if (!path) return;
+      await invoke('set_import_folder', { path });
+      setImportFolder(path);
+      showMessage('success', '已设置热文件夹，放入的价目表将自动导入');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    }
+  };
+
+  const clearImportFolder = async () => {
+    try {
+      await invoke('set_import_folder', { path: null });
+      setImportFolder(null);
+      showMessage('success', '已停用热文件夹');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    }
+  };
+
+  const checkScannerStatus = async () => {
+    try {
+      setScannerStatus(await invoke('get_scanner_status'));
+    } catch (error) {
+      console.error('获取扫码枪设置失败:', error);
+    }
+  };
+
+  const toggleScannerHook = async () => {
+    const enabled = !scannerStatus?.enabled;
+    setSaving(true);
+    try {
+      await invoke('set_scanner_hook', { enabled });
+      showMessage('success', enabled ? '已开启扫码枪全局识别' : '已关闭扫码枪全局识别');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
+    } finally {
+      setSaving(false);
+      checkScannerStatus();
+    }
+  };
+
+  const checkPerformanceMode = async () => {
+    try {
+      setPerformanceMode(await invoke('get_performance_mode'));
+    } catch (error) {
+      console.error('获取性能模式失败:', error);
+    }
+  };
+
+  const changePerformanceMode = async (level: string, hardwareAcceleration?: boolean) => {
+    setSaving(true);
+    try {
+      const mode = await invoke<NonNullable<typeof performanceMode>>('set_performance_mode', {
+        level,
+        hardwareAcceleration,
+      });
+      setPerformanceMode(mode);
+      showMessage('success', mode.restart_required ? '已保存，重启后完全生效' : '已修改性能模式');
+    } catch (error) {
+      showMessage('error', `设置失败: ${errorMessage(error)}`);
     } finally {
       setSaving(false);
     }
@@ -174,7 +342,7 @@ const Settings = () => {
     setLoginError('');
     
     try {
-      const response = await fetch(`${API_BASE_URL}/settings/verify-password`, {
+      const response = await backendFetch(`/settings/verify-password`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify({ password: loginPassword }),
@@ -211,7 +379,7 @@ const Settings = () => {
   const handleResetToDefault = async () => {
     setResetLoading(true);
     try {
-      const response = await fetch(`${API_BASE_URL}/settings/reset-to-default`, {
+      const response = await backendFetch(`/settings/reset-to-default`, {
         method: 'POST',
       });
       
@@ -249,7 +417,7 @@ const Settings = () => {
     
     setResetLoading(true);
     try {
-      const response = await fetch(`${API_BASE_URL}/settings/reset-password`, {
+      const response = await backendFetch(`/settings/reset-password`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify({
@@ -552,6 +720,200 @@ const Settings = () => {
               </span>
             </div>
           </div>
+          <div className="settings-card">
+            <div className="setting-item">
+              <div className="setting-info">
+                <div className="setting-icon blue">🩺</div>
+                <div className="setting-content">
+                  <div className="setting-label">错误上报</div>
+                  <div className="setting-description">程序崩溃时匿名上报错误信息，帮助改进软件</div>
+                </div>
+              </div>
+              <div className="setting-control">
+                <label className="switch">
+                  <input
+                    type="checkbox"
+                    checked={crashReportingEnabled}
+                    onChange={toggleCrashReporting}
+                    disabled={saving}
+                  />
+                  <span className="slider"></span>
+                </label>
+              </div>
+            </div>
+            <div className={`setting-status ${crashReportingEnabled ? 'enabled' : ''}`}>
+              <span className="status-text">{crashReportingEnabled ? '已开启' : '未开启'}</span>
+            </div>
+          </div>
+          <div className="settings-card">
+            <div className="setting-item">
+              <div className="setting-info">
+                <div className="setting-icon blue">📤</div>
+                <div className="setting-content">
+                  <div className="setting-label">自动上传崩溃报告</div>
+                  <div className="setting-description">启动时发现上次的崩溃报告后，自动上传报告和相关日志，不再询问</div>
+                </div>
+              </div>
+              <div className="setting-control">
+                <label className="switch">
+                  <input
+                    type="checkbox"
+                    checked={crashUploadConsent === 'always'}
+                    onChange={toggleCrashUpload}
+                    disabled={saving}
+                  />
+                  <span className="slider"></span>
+                </label>
+              </div>
+            </div>
+            <div className={`setting-status ${crashUploadConsent === 'always' ? 'enabled' : ''}`}>
+              <span className="status-text">
+                {crashUploadConsent === 'always' ? '自动上传' : crashUploadConsent === 'never' ? '不上传' : '每次询问'}
+              </span>
+            </div>
+          </div>
+          <div className="settings-card">
+            <div className="setting-item">
+              <div className="setting-info">
+                <div className="setting-icon blue">📊</div>
+                <div className="setting-content">
+                  <div className="setting-label">匿名使用统计</div>
+                  <div className="setting-description">
+                    仅统计功能使用次数和硬件类型，不包含任何经营数据。
+                    <a href="#" onClick={(e) => { e.preventDefault(); previewTelemetry(); }}>查看上传内容</a>
+                  </div>
+                </div>
+              </div>
+              <div className="setting-control">
+                <label className="switch">
+                  <input
+                    type="checkbox"
+                    checked={telemetryEnabled}
+                    onChange={toggleTelemetry}
+                    disabled={saving}
+                  />
+                  <span className="slider"></span>
+                </label>
+              </div>
+            </div>
+            <div className={`setting-status ${telemetryEnabled ? 'enabled' : ''}`}>
+              <span className="status-text">{telemetryEnabled ? '已开启' : '未开启'}</span>
+            </div>
+          </div>
+          <div className="settings-card">
+            <div className="setting-item">
+              <div className="setting-info">
+                <div className="setting-icon blue">📂</div>
+                <div className="setting-content">
+                  <div className="setting-label">导入热文件夹</div>
+                  <div className="setting-description">
+                    放入该文件夹的 CSV / XLSX 价目表自动导入为商品，处理后移到 done 或 error 子文件夹
+                  </div>
+                </div>
+              </div>
+              <div className="setting-control">
+                <button className="btn-secondary" onClick={chooseImportFolder} disabled={saving}>
+                  选择文件夹
+                </button>
+                {importFolder && (
+                  <button className="btn-secondary" onClick={clearImportFolder} disabled={saving}>
+                    停用
+                  </button>
+                )}
+              </div>
+            </div>
+            <div className={`setting-status ${importFolder ? 'enabled' : ''}`}>
+              <span className="status-text">{importFolder ?? '未设置'}</span>
+            </div>
+          </div>
+          {scannerStatus?.supported && (
+            <div className="settings-card">
+              <div className="setting-item">
+                <div className="setting-info">
+                  <div className="setting-icon blue">🔍</div>
+                  <div className="setting-content">
+                    <div className="setting-label">扫码枪全局识别</div>
+                    <div className="setting-description">
+                      弹出对话框或其他程序在前台时也能识别扫码枪输入，条码不会输入到其他窗口
+                    </div>
+                  </div>
+                </div>
+                <div className="setting-control">
+                  <label className="switch">
+                    <input
+                      type="checkbox"
+                      checked={scannerStatus.enabled}
+                      onChange={toggleScannerHook}
+                      disabled={saving}
+                    />
+                    <span className="slider"></span>
+                  </label>
+                </div>
+              </div>
+              <div className={`setting-status ${scannerStatus.running ? 'enabled' : ''}`}>
+                <span className="status-text">
+                  {scannerStatus.error ?? (scannerStatus.running ? '已开启' : '未开启')}
+                </span>
+              </div>
+            </div>
+          )}
+          {performanceMode && (
+            <div className="settings-card">
+              <div className="setting-item">
+                <div className="setting-info">
+                  <div className="setting-icon blue">⚡</div>
+                  <div className="setting-content">
+                    <div className="setting-label">性能模式</div>
+                    <div className="setting-description">
+                      低配模式降低后台检查频率、减少动画，适合配置较低的收银机
+                    </div>
+                  </div>
+                </div>
+                <div className="setting-control">
+                  <select
+                    className="setting-select"
+                    value={performanceMode.level}
+                    onChange={(e) => changePerformanceMode(e.target.value)}
+                    disabled={saving}
+                  >
+                    <option value="auto">自动</option>
+                    <option value="standard">标准</option>
+                    <option value="low">低配</option>
+                  </select>
+                </div>
+              </div>
+              <div className="setting-item">
+                <div className="setting-info">
+                  <div className="setting-icon blue">🖥️</div>
+                  <div className="setting-content">
+                    <div className="setting-label">硬件加速</div>
+                    <div className="setting-description">
+                      界面花屏或闪烁时关闭，重启后生效
+                    </div>
+                  </div>
+                </div>
+                <div className="setting-control">
+                  <label className="switch">
+                    <input
+                      type="checkbox"
+                      checked={performanceMode.hardware_acceleration}
+                      onChange={() =>
+                        changePerformanceMode(performanceMode.level, !performanceMode.hardware_acceleration)
+                      }
+                      disabled={saving}
+                    />
+                    <span className="slider"></span>
+                  </label>
+                </div>
+              </div>
+              <div className={`setting-status ${performanceMode.effective === 'low' ? 'enabled' : ''}`}>
+                <span className="status-text">
+                  {performanceMode.effective === 'low' ? '当前为低配模式' : '当前为标准模式'}
+                  {performanceMode.restart_required ? '（重启后完全生效）' : ''}
+                </span>
+              </div>
+            </div>
+          )}
         </div>
 
         {/* 安全设置 */}
@@ -0,0 +1,40 @@
+/**
+ * 启动画面
+ *
+ * 等待壳程序确认 Backend 就绪（就绪后壳程序关闭启动画面并显示主窗口）。
+ * 超时未就绪时显示失败原因，可以直接打开主窗口或查看日志。
+ */
+
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+
+interface StartupState {
+  state: 'waiting' | 'ready' | 'failed';
+  error: string | null;
+  elapsed_ms: number;
+}
+
+const splash = document.getElementById('splash') as HTMLElement;
+const status = document.getElementById('status') as HTMLElement;
+
+function showError(reason: string | null) {
+  splash.classList.add('error');
+  status.textContent = `服务启动失败：${reason ?? '未知原因'}`;
+}
+
+document.getElementById('open-main')?.addEventListener('click', () => {
+  invoke('open_main_window').catch((error) => console.warn('打开主窗口失败', error));
+});
+document.getElementById('open-logs')?.addEventListener('click', () => {
+  invoke('open_log_folder').catch((error) => console.warn('打开日志目录失败', error));
+});
+
+async function init() {
+  await listen<{ reason: string }>('backend-error', (event) => showError(event.payload.reason));
+  const state = await invoke<StartupState>('get_startup_state');
+  if (state.state === 'failed') {
+    showError(state.error);
+  }
+}
+
+init().catch((error) => console.warn('读取启动状态失败', error));
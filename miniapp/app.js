@@ -1,8 +1,12 @@
 // app.js
+import { getApiUrl } from './config'
+
 App({
   onLaunch() {
     console.log('SmartMart 小程序启动')
     
+    this.installDeviceAuth()
+    
     // 从本地存储读取配置
     const serverUrl = wx.getStorageSync('serverUrl') || ''
     const deviceId = wx.getStorageSync('deviceId') || this.generateDeviceId()
@@ -31,6 +35,31 @@ App({
     console.error('小程序错误:', error)
   },
 
+  // 发往 Backend 的请求自动带上设备 Token（配对时取得，撤销设备后失效）
+  installDeviceAuth() {
+    const withToken = (options) => {
+      const deviceToken = wx.getStorageSync('deviceToken')
+      const apiUrl = getApiUrl(this.globalData.serverUrl)
+      if (!deviceToken || !apiUrl || !options || !String(options.url).startsWith(apiUrl)) {
+        return options
+      }
+      return {
+        ...options,
+        header: { ...options.header, Authorization: `Bearer ${deviceToken}` }
+      }
+    }
+    
+    ;['request', 'uploadFile', 'downloadFile'].forEach((name) => {
+      const original = wx[name]
+      Object.defineProperty(wx, name, {
+        configurable: true,
+        enumerable: true,
+        writable: true,
+        value: (options) => original.call(wx, withToken(options))
+      })
+    })
+  },
+
   // 生成设备ID
   generateDeviceId() {
     return 'miniapp-' + Date.now() + '-' + Math.random().toString(36).substring(2, 9)
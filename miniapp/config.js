@@ -15,8 +15,10 @@ export function getApiUrl(serverUrl) {
   return `http://${serverUrl}`
 }
 
-export function getWsUrl(serverUrl) {
+// WebSocket 无法设置请求头，设备 Token 通过查询参数传递
+export function getWsUrl(serverUrl, deviceToken) {
   if (!serverUrl) return null
-  return `ws://${serverUrl}/ws`
+  const url = `ws://${serverUrl}/ws`
+  return deviceToken ? `${url}?access_token=${encodeURIComponent(deviceToken)}` : url
 }
 
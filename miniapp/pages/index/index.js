@@ -1,5 +1,5 @@
 // pages/index/index.js
-import { getWsUrl } from '../../config'
+import { getApiUrl, getWsUrl } from '../../config'
 
 const app = getApp()
 
@@ -197,6 +197,7 @@ Page({
           // 清除存储
           wx.removeStorageSync('serverUrl')
           wx.removeStorageSync('pairingToken')
+          wx.removeStorageSync('deviceToken')
           app.globalData.serverUrl = ''
           app.globalData.pairingToken = ''
           
@@ -313,6 +314,30 @@ Page({
     this.connectWebSocket()
   },
 
+  // 使用扫码取得的一次性配对 Token 换取设备 Token
+  pairDevice(pairingToken) {
+    const apiUrl = getApiUrl(this.data.serverUrl)
+    return new Promise((resolve, reject) => {
+      wx.request({
+        url: `${apiUrl}/pairing/pair`,
+        method: 'POST',
+        data: {
+          token: pairingToken,
+          device_id: this.data.deviceId,
+          device_type: 'miniapp'
+        },
+        success: (res) => {
+          if (res.statusCode === 200) {
+            resolve(res.data.device_token)
+          } else {
+            reject(new Error((res.data && res.data.detail) || `HTTP ${res.statusCode}`))
+          }
+        },
+        fail: reject
+      })
+    })
+  },
+
   // 连接 WebSocket
   connectWebSocket() {
     const { serverUrl, deviceId } = this.data
@@ -330,9 +355,39 @@ Page({
       return
     }
     
+    // 有新的配对 Token 时先完成配对，再使用设备 Token 连接
+    const pairingToken = wx.getStorageSync('pairingToken') || app.globalData.pairingToken || ''
+    if (pairingToken) {
+      wx.removeStorageSync('pairingToken')
+      app.globalData.pairingToken = ''
+      wx.showLoading({ title: '配对中...' })
+      this.pairDevice(pairingToken)
+        .then((deviceToken) => {
+          wx.hideLoading()
+          wx.setStorageSync('deviceToken', deviceToken)
+          this.connectWebSocket()
+        })
+        .catch((error) => {
+          console.error('❌ 配对失败:', error)
+          wx.hideLoading()
+          wx.showModal({
+            title: '配对失败',
+            content: error.message || '配对 Token 无效或已过期，请重新扫码配对',
+            confirmText: '重新扫码',
+            cancelText: '取消',
+            success: (res) => {
+              if (res.confirm) {
+                this.scanToConnect()
+              }
+            }
+          })
+        })
+      return
+    }
+    
     this.setData({ connecting: true })
     
-    const wsUrl = getWsUrl(serverUrl)
+    const wsUrl = getWsUrl(serverUrl, wx.getStorageSync('deviceToken'))
     console.log('连接到:', wsUrl)
     
     wx.showLoading({ title: '连接中...' })
@@ -396,13 +451,11 @@ Page({
       app.globalData.wsConnected = true
       app.globalData.socketTask = socketTask
       
-      // 发送设备注册消息（带 Token 验证）
-      const pairingToken = wx.getStorageSync('pairingToken') || app.globalData.pairingToken || ''
+      // 发送设备注册消息（设备 Token 已在连接时出示）
       this.sendMessage({
         type: 'REGISTER',
         device_id: deviceId,
         device_type: 'miniapp',
-        token: pairingToken,
         ts: Date.now()
       })
       
@@ -453,6 +506,7 @@ Page({
         
         // 清除无效的 Token
         wx.removeStorageSync('pairingToken')
+        wx.removeStorageSync('deviceToken')
         app.globalData.pairingToken = ''
         
         // 断开连接